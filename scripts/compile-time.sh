@@ -0,0 +1,34 @@
+#!/usr/bin/env bash
+# Time incremental rebuilds of the wide-struct tests (tests/compile_time_*.rs).
+#
+# Each round touches one test file and rebuilds only that test target, so the
+# time is dominated by expanding and type-checking its `#[smart_lock]` struct.
+# Reports the median of ROUNDS rebuilds (default 5) per field count.
+#
+#   scripts/compile-time.sh            # 10, 20, 40, 100 fields
+#   ROUNDS=9 scripts/compile-time.sh 40
+set -euo pipefail
+
+cd "$(dirname "$0")/../smart-lock"
+rounds=${ROUNDS:-5}
+sizes=("$@")
+[ ${#sizes[@]} -eq 0 ] && sizes=(10 20 40 100)
+
+# Build dependencies and the proc macro once, outside the timed runs.
+for n in "${sizes[@]}"; do
+    cargo test --quiet --no-run --test "compile_time_$n" 2>/dev/null
+done
+
+printf '| Fields | Incremental compile |\n|--------|-------------------|\n'
+for n in "${sizes[@]}"; do
+    times=()
+    for _ in $(seq "$rounds"); do
+        touch "tests/compile_time_$n.rs"
+        start=$(date +%s.%N)
+        cargo test --quiet --no-run --test "compile_time_$n" 2>/dev/null
+        end=$(date +%s.%N)
+        times+=("$(awk "BEGIN { print $end - $start }")")
+    done
+    median=$(printf '%s\n' "${times[@]}" | sort -n | sed -n "$(((rounds + 1) / 2))p")
+    printf '| %-6s | ~%.2fs             |\n' "$n" "$median"
+done
@@ -0,0 +1,71 @@
+use crate::parse::{deferred_bound, ParsedStruct};
+use quote::quote;
+
+/// `arbitrary::Arbitrary` for `FooLock`: each field is generated on its own and the
+/// result is wrapped via `From<Foo>`, so `#[no_lock]` and `#[boxed]` fields need
+/// nothing special. Available when every field implements `Arbitrary`.
+pub fn generate(parsed: &ParsedStruct) -> proc_macro2::TokenStream {
+    let struct_name = &parsed.name;
+    let lock_name = parsed.lock_name();
+
+    let impl_prefix = parsed.impl_prefix();
+    let ty_generics = parsed.ty_generics();
+
+    let arbitrary = quote!(smart_lock::__private::arbitrary);
+    let bounds: Vec<proc_macro2::TokenStream> = parsed
+        .fields
+        .iter()
+        .map(|field| deferred_bound(&field.ty, quote!(#arbitrary::Arbitrary<'__arbitrary>)))
+        .collect();
+    let arbitrary_where = parsed.where_clause_with(&bounds);
+
+    let field_inits: Vec<proc_macro2::TokenStream> = parsed
+        .fields
+        .iter()
+        .map(|field| {
+            let name = &field.name;
+            quote! { #name: #arbitrary::Arbitrary::arbitrary(u)?, }
+        })
+        .collect();
+    // The last field takes whatever input is left.
+    let take_rest_inits: Vec<proc_macro2::TokenStream> = parsed
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let name = &field.name;
+            if i + 1 == parsed.fields.len() {
+                quote! { #name: #arbitrary::Arbitrary::arbitrary_take_rest(u)?, }
+            } else {
+                quote! { #name: #arbitrary::Arbitrary::arbitrary(&mut u)?, }
+            }
+        })
+        .collect();
+    let size_hints: Vec<proc_macro2::TokenStream> = parsed
+        .fields
+        .iter()
+        .map(|field| {
+            let ty = &field.ty;
+            quote! { <#ty as #arbitrary::Arbitrary<'__arbitrary>>::size_hint(depth) }
+        })
+        .collect();
+
+    quote! {
+        /// Generates every field with its own `Arbitrary` impl and locks the result,
+        /// for fuzzing code that takes the lock.
+        impl<'__arbitrary, #impl_prefix> #arbitrary::Arbitrary<'__arbitrary> for #lock_name #ty_generics #arbitrary_where {
+            fn arbitrary(u: &mut #arbitrary::Unstructured<'__arbitrary>) -> #arbitrary::Result<Self> {
+                ::core::result::Result::Ok(::core::convert::From::from(#struct_name { #(#field_inits)* }))
+            }
+
+            #[allow(unused_mut)]
+            fn arbitrary_take_rest(mut u: #arbitrary::Unstructured<'__arbitrary>) -> #arbitrary::Result<Self> {
+                ::core::result::Result::Ok(::core::convert::From::from(#struct_name { #(#take_rest_inits)* }))
+            }
+
+            fn size_hint(depth: usize) -> (usize, ::core::option::Option<usize>) {
+                #arbitrary::size_hint::and_all(&[#(#size_hints),*])
+            }
+        }
+    }
+}
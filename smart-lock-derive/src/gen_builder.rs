@@ -1,24 +1,40 @@
-use crate::parse::ParsedStruct;
+use crate::parse::{ParsedStruct, PresetMode};
 use quote::{format_ident, quote};
+use syn::ext::IdentExt;
 
 pub fn generate(parsed: &ParsedStruct) -> proc_macro2::TokenStream {
     let vis = &parsed.vis;
-    let lock_name = format_ident!("{}Lock", &parsed.name);
-    let builder_name = format_ident!("{}LockBuilder", &parsed.name);
-    let guard_name = format_ident!("{}LockGuard", &parsed.name);
+    let lock_name = parsed.lock_name();
+    let builder_name = parsed.builder_name();
+    let guard_name = parsed.guard_name();
 
     let impl_prefix = parsed.impl_prefix();
+    let poison = parsed.hook_arg(quote!('_));
     let bare_prefix = parsed.bare_prefix();
     let ty_generics = parsed.ty_generics();
     let where_clause = parsed.where_clause();
 
-    let lock_name_str = format!("{}Lock", parsed.name);
+    let lock_name_str = parsed.lock_name().to_string();
+    let order_doc = if parsed.has_custom_order() {
+        let names: Vec<String> = parsed
+            .in_acquisition_order(parsed.fields.iter().collect())
+            .iter()
+            .filter(|f| !f.no_lock)
+            .map(|f| format!("`{}`", f.name))
+            .collect();
+        format!(
+            "Locks are acquired in the order set by `#[lock(order = N)]` ({}) to prevent deadlocks.",
+            names.join(", ")
+        )
+    } else {
+        "Locks are acquired in field declaration order to prevent deadlocks.".to_string()
+    };
     let builder_doc = format!(
         "Type-state builder for selecting which fields of [`{lock_name_str}`] to lock.\n\n\
          Each field starts as `Unlocked`. Call `.read_field()`, `.write_field()`, or \
          `.upgrade_field()` to select the lock mode, then `.lock().await` to acquire all \
          selected locks atomically.\n\n\
-         Locks are acquired in field declaration order to prevent deadlocks. \
+         {order_doc} \
          A field can only be locked once (calling `.write_x()` on an already-locked field \
          is a compile error)."
     );
@@ -49,12 +65,56 @@ pub fn generate(parsed: &ParsedStruct) -> proc_macro2::TokenStream {
     let struct_def = quote! {
         #[doc = #builder_doc]
         #[must_use = "builder does nothing until .lock().await is called"]
-        #vis struct #builder_name<'a, #impl_prefix #(#generic_names),*> #where_clause {
-            lock: &'a #lock_name #ty_generics,
+        #vis struct #builder_name<'__sl, #impl_prefix #(#generic_names),*> #where_clause {
+            lock: &'__sl #lock_name #ty_generics,
             _marker: std::marker::PhantomData<(#(#generic_names),*)>,
         }
     };
 
+    // --- Field markers for with_mode() ---
+    let field_module = parsed.field_module_name();
+    let marker_names: Vec<&syn::Ident> = parsed
+        .fields
+        .iter()
+        .filter(|f| !f.no_lock)
+        .map(|f| &f.name)
+        .collect();
+    let marker_docs: Vec<String> = marker_names
+        .iter()
+        .map(|name| format!("Marker for the `{}` field.", name.unraw()))
+        .collect();
+    let module_doc = format!(
+        "Type-level markers for the locked fields of [`{lock_name_str}`], one unit struct \
+         per field, for [`{builder_name}::with_mode`]."
+    );
+    let with_mode_doc = format!(
+        "Set the field marked by `Field` (from [`{field_module}`]) to mode `M`.\n\n\
+         Equivalent to `.read_x()`, `.write_x()` or `.upgrade_x()` for `M` = `ReadLocked`, \
+         `WriteLocked` or `UpgradeLocked`, for code generic over the lock mode. Like \
+         those, the field must still be `Unlocked`."
+    );
+    let marker_module = quote! {
+        #[doc = #module_doc]
+        #[allow(non_camel_case_types)]
+        #vis mod #field_module {
+            #(
+                #[doc = #marker_docs]
+                pub struct #marker_names;
+            )*
+        }
+    };
+    let with_mode_impl = quote! {
+        impl<'__sl, #impl_prefix #(#generic_names),*> #builder_name<'__sl, #bare_prefix #(#generic_names),*> #where_clause {
+            #[doc = #with_mode_doc]
+            #vis fn with_mode<Field, M: smart_lock::LockMode>(self) -> <Self as smart_lock::WithMode<Field, M>>::Output
+            where
+                Self: smart_lock::WithMode<Field, M>,
+            {
+                smart_lock::WithMode::__with_mode(self)
+            }
+        }
+    };
+
     // --- Per-field impl blocks (locked fields only) ---
     let mut field_impls = Vec::new();
 
@@ -125,26 +185,115 @@ pub fn generate(parsed: &ParsedStruct) -> proc_macro2::TokenStream {
             })
             .collect();
 
+        let upgrade_method_on_guard = format_ident!("upgrade_{}", field_name);
+        let lock_then_upgrade_method = format_ident!("lock_then_upgrade_{}", field_name);
+        let lock_then_upgrade_doc = format!(
+            "Acquire all requested locks, then upgrade `{0}` to a write lock.\n\n\
+             Shorthand for `.lock().await.upgrade_{0}().await`: the other fields are locked \
+             in their requested modes, and `{0}` is returned `WriteLocked` once its readers drain.",
+            field_name_str
+        );
+        let free_bounds: Vec<proc_macro2::TokenStream> = free_generics
+            .iter()
+            .map(|f| quote!(#f: smart_lock::LockMode))
+            .collect();
+
+        field_impls.push(quote! {
+            impl<'__sl, #impl_prefix #(#free_bounds),*> #builder_name<'__sl, #bare_prefix #(#upgrade_params),*> #where_clause {
+                #[doc = #lock_then_upgrade_doc]
+                #vis async fn #lock_then_upgrade_method(self) -> #guard_name<'__sl, #bare_prefix #(#write_params),*> {
+                    self.lock().await.#upgrade_method_on_guard().await
+                }
+            }
+        });
+
         field_impls.push(quote! {
-            impl<'a, #impl_prefix #(#free_generics),*> #builder_name<'a, #bare_prefix #(#input_params),*> #where_clause {
+            impl<'__sl, #impl_prefix #(#free_generics),*> #builder_name<'__sl, #bare_prefix #(#input_params),*> #where_clause {
                 #[doc = #write_doc]
-                #vis fn #write_method(self) -> #builder_name<'a, #bare_prefix #(#write_params),*> {
+                #vis fn #write_method(self) -> #builder_name<'__sl, #bare_prefix #(#write_params),*> {
                     #builder_name { lock: self.lock, _marker: std::marker::PhantomData }
                 }
 
                 #[doc = #read_doc]
-                #vis fn #read_method(self) -> #builder_name<'a, #bare_prefix #(#read_params),*> {
+                #vis fn #read_method(self) -> #builder_name<'__sl, #bare_prefix #(#read_params),*> {
                     #builder_name { lock: self.lock, _marker: std::marker::PhantomData }
                 }
 
                 #[doc = #upgrade_doc]
-                #vis fn #upgrade_method(self) -> #builder_name<'a, #bare_prefix #(#upgrade_params),*> {
+                #vis fn #upgrade_method(self) -> #builder_name<'__sl, #bare_prefix #(#upgrade_params),*> {
+                    #builder_name { lock: self.lock, _marker: std::marker::PhantomData }
+                }
+            }
+        });
+
+        let mode_params: Vec<proc_macro2::TokenStream> = (0..locked_count)
+            .map(|j| {
+                if j == gi {
+                    quote!(__M)
+                } else {
+                    let f = &generic_names[j];
+                    quote!(#f)
+                }
+            })
+            .collect();
+        field_impls.push(quote! {
+            impl<'__sl, #impl_prefix #(#free_generics,)* __M: smart_lock::LockMode>
+                smart_lock::WithMode<#field_module::#field_name, __M>
+                for #builder_name<'__sl, #bare_prefix #(#input_params),*> #where_clause
+            {
+                type Output = #builder_name<'__sl, #bare_prefix #(#mode_params),*>;
+
+                #[inline(always)]
+                fn __with_mode(self) -> Self::Output {
                     #builder_name { lock: self.lock, _marker: std::marker::PhantomData }
                 }
             }
         });
     }
 
+    // --- Named presets: builder_<name>() on the lock ---
+    let preset_methods: Vec<proc_macro2::TokenStream> = parsed
+        .presets
+        .iter()
+        .map(|preset| {
+            let method = format_ident!("builder_{}", preset.name);
+            let doc = format!(
+                "Start a builder with the `{}` preset applied: `{}`.\n\n\
+                 Equivalent to chaining those calls on [`builder()`](Self::builder); more \
+                 fields can still be selected before `.lock().await`.",
+                preset.name,
+                preset.spec.value()
+            );
+            let mut modes: Vec<proc_macro2::TokenStream> = (0..locked_count)
+                .map(|_| quote!(smart_lock::Unlocked))
+                .collect();
+            for (field_index, mode) in &preset.modes {
+                let gi = field_to_generic[*field_index].unwrap();
+                modes[gi] = match mode {
+                    PresetMode::Read => quote!(smart_lock::ReadLocked),
+                    PresetMode::Write => quote!(smart_lock::WriteLocked),
+                    PresetMode::Upgrade => quote!(smart_lock::UpgradeLocked),
+                };
+            }
+            quote! {
+                #[doc = #doc]
+                #vis fn #method(&self) -> #builder_name<'_, #bare_prefix #(#modes),*> {
+                    #builder_name { lock: self, _marker: std::marker::PhantomData }
+                }
+            }
+        })
+        .collect();
+
+    let preset_impl = if preset_methods.is_empty() {
+        quote!()
+    } else {
+        quote! {
+            impl<#impl_prefix> #lock_name #ty_generics #where_clause {
+                #(#preset_methods)*
+            }
+        }
+    };
+
     // --- lock() method ---
     let lock_bounds: Vec<proc_macro2::TokenStream> = generic_names
         .iter()
@@ -158,22 +307,22 @@ pub fn generate(parsed: &ParsedStruct) -> proc_macro2::TokenStream {
         .map(|(i, field)| {
             let name = &field.name;
             let ty = &field.ty;
+            let acquire = parsed.acquire_call(field, quote!(self.lock), false);
             if field.no_lock {
                 quote! { let #name = &self.lock.#name; }
             } else {
                 let gi = field_to_generic[i].unwrap();
                 let f = &generic_names[gi];
+                // `acquire` skips the lock entirely for `Unlocked` fields.
                 quote! {
-                    let #name = if <#f as smart_lock::LockMode>::MODE == smart_lock::LockModeKind::None {
-                        smart_lock::FieldGuard::<'_, #ty, #f>::unlocked()
-                    } else {
-                        smart_lock::FieldGuard::<'_, #ty, #f>::acquire(&self.lock.#name).await
-                    };
+                    let #name = smart_lock::FieldGuard::<'_, #ty, #f #poison>::#acquire.await;
                 }
             }
         })
         .collect();
+    let lock_fields = parsed.in_acquisition_order(lock_fields);
 
+    let field_id_name = parsed.field_id_name();
     let try_lock_fields: Vec<proc_macro2::TokenStream> = parsed
         .fields
         .iter()
@@ -181,31 +330,33 @@ pub fn generate(parsed: &ParsedStruct) -> proc_macro2::TokenStream {
         .map(|(i, field)| {
             let name = &field.name;
             let ty = &field.ty;
+            let acquire = parsed.acquire_call(field, quote!(self.lock), true);
             if field.no_lock {
                 quote! { let #name = &self.lock.#name; }
             } else {
                 let gi = field_to_generic[i].unwrap();
                 let f = &generic_names[gi];
+                // `acquire` skips the lock entirely for `Unlocked` fields.
                 quote! {
-                    let #name = if <#f as smart_lock::LockMode>::MODE == smart_lock::LockModeKind::None {
-                        smart_lock::FieldGuard::<'_, #ty, #f>::unlocked()
-                    } else {
-                        smart_lock::FieldGuard::<'_, #ty, #f>::try_acquire(&self.lock.#name)?
+                    let Some(#name) = smart_lock::FieldGuard::<'_, #ty, #f #poison>::#acquire else {
+                        return Err(#field_id_name::#name);
                     };
                 }
             }
         })
         .collect();
+    let try_lock_fields = parsed.in_acquisition_order(try_lock_fields);
 
     let field_names: Vec<&syn::Ident> = parsed.fields.iter().map(|f| &f.name).collect();
 
     let lock_impl = quote! {
-        impl<'a, #impl_prefix #(#lock_bounds),*> #builder_name<'a, #bare_prefix #(#generic_names),*> #where_clause {
+        impl<'__sl, #impl_prefix #(#lock_bounds),*> #builder_name<'__sl, #bare_prefix #(#generic_names),*> #where_clause {
             /// Acquire all requested locks and return the guard.
             ///
-            /// Locks are acquired in field declaration order (not call order) to prevent deadlocks.
+            /// Locks are acquired in acquisition order (field declaration order unless
+            /// overridden with `#[lock(order = N)]`), not call order, to prevent deadlocks.
             /// Unlocked fields are skipped with zero overhead.
-            #vis async fn lock(self) -> #guard_name<'a, #bare_prefix #(#generic_names),*> {
+            #vis async fn lock(self) -> #guard_name<'__sl, #bare_prefix #(#generic_names),*> {
                 #(#lock_fields)*
                 #guard_name { lock: self.lock, #(#field_names),* }
             }
@@ -214,10 +365,18 @@ pub fn generate(parsed: &ParsedStruct) -> proc_macro2::TokenStream {
             ///
             /// Returns `None` if any lock is currently held in a conflicting mode.
             /// On failure, all already-acquired locks are released (the partially-built
-            /// guard is dropped). Locks are attempted in field declaration order.
-            #vis fn try_lock(self) -> Option<#guard_name<'a, #bare_prefix #(#generic_names),*>> {
+            /// guard is dropped). Locks are attempted in acquisition order.
+            #vis fn try_lock(self) -> Option<#guard_name<'__sl, #bare_prefix #(#generic_names),*>> {
+                self.try_lock_verbose().ok()
+            }
+
+            /// Like [`try_lock`](Self::try_lock), but on failure reports the first
+            /// field whose lock was held in a conflicting mode.
+            ///
+            /// For contention diagnostics and retry policies aimed at the hot field.
+            #vis fn try_lock_verbose(self) -> Result<#guard_name<'__sl, #bare_prefix #(#generic_names),*>, #field_id_name> {
                 #(#try_lock_fields)*
-                Some(#guard_name { lock: self.lock, #(#field_names),* })
+                Ok(#guard_name { lock: self.lock, #(#field_names),* })
             }
         }
     };
@@ -240,17 +399,19 @@ pub fn generate(parsed: &ParsedStruct) -> proc_macro2::TokenStream {
         .map(|(i, field)| {
             let name = &field.name;
             let ty = &field.ty;
+            let acquire = parsed.acquire_call(field, quote!(self.lock), false);
             if field.no_lock {
                 quote! { let #name = &self.lock.#name; }
             } else {
                 let gi = field_to_generic[i].unwrap();
                 let f = &generic_names[gi];
                 quote! {
-                    let #name = smart_lock::FieldGuard::<'_, #ty, <#f as smart_lock::DefaultRead>::Output>::acquire(&self.lock.#name).await;
+                    let #name = smart_lock::FieldGuard::<'_, #ty, <#f as smart_lock::DefaultRead>::Output #poison>::#acquire.await;
                 }
             }
         })
         .collect();
+    let rest_read_lock_fields = parsed.in_acquisition_order(rest_read_lock_fields);
 
     let rest_read_try_lock_fields: Vec<proc_macro2::TokenStream> = parsed
         .fields
@@ -259,20 +420,22 @@ pub fn generate(parsed: &ParsedStruct) -> proc_macro2::TokenStream {
         .map(|(i, field)| {
             let name = &field.name;
             let ty = &field.ty;
+            let acquire = parsed.acquire_call(field, quote!(self.lock), true);
             if field.no_lock {
                 quote! { let #name = &self.lock.#name; }
             } else {
                 let gi = field_to_generic[i].unwrap();
                 let f = &generic_names[gi];
                 quote! {
-                    let #name = smart_lock::FieldGuard::<'_, #ty, <#f as smart_lock::DefaultRead>::Output>::try_acquire(&self.lock.#name)?;
+                    let #name = smart_lock::FieldGuard::<'_, #ty, <#f as smart_lock::DefaultRead>::Output #poison>::#acquire?;
                 }
             }
         })
         .collect();
+    let rest_read_try_lock_fields = parsed.in_acquisition_order(rest_read_try_lock_fields);
 
     let rest_read_impl = quote! {
-        impl<'a, #impl_prefix #(#rest_read_bounds),*> #builder_name<'a, #bare_prefix #(#generic_names),*> #where_clause {
+        impl<'__sl, #impl_prefix #(#rest_read_bounds),*> #builder_name<'__sl, #bare_prefix #(#generic_names),*> #where_clause {
             /// Acquire locks for all fields, filling any `Unlocked` fields with read locks.
             ///
             /// Fields already set to `WriteLocked` or `UpgradeLocked` keep their mode.
@@ -280,7 +443,7 @@ pub fn generate(parsed: &ParsedStruct) -> proc_macro2::TokenStream {
             ///
             /// This is a shorthand for when you want to write a few fields and read the rest,
             /// without listing every field in the builder.
-            #vis async fn lock_rest_read(self) -> #guard_name<'a, #bare_prefix #(#rest_read_output_generics),*> {
+            #vis async fn lock_rest_read(self) -> #guard_name<'__sl, #bare_prefix #(#rest_read_output_generics),*> {
                 #(#rest_read_lock_fields)*
                 #guard_name { lock: self.lock, #(#field_names),* }
             }
@@ -290,7 +453,7 @@ pub fn generate(parsed: &ParsedStruct) -> proc_macro2::TokenStream {
             ///
             /// Returns `None` if any lock is currently held in a conflicting mode.
             /// On failure, all already-acquired locks are released.
-            #vis fn try_lock_rest_read(self) -> Option<#guard_name<'a, #bare_prefix #(#rest_read_output_generics),*>> {
+            #vis fn try_lock_rest_read(self) -> Option<#guard_name<'__sl, #bare_prefix #(#rest_read_output_generics),*>> {
                 #(#rest_read_try_lock_fields)*
                 Some(#guard_name { lock: self.lock, #(#field_names),* })
             }
@@ -299,7 +462,10 @@ pub fn generate(parsed: &ParsedStruct) -> proc_macro2::TokenStream {
 
     quote! {
         #struct_def
+        #marker_module
+        #with_mode_impl
         #(#field_impls)*
+        #preset_impl
         #lock_impl
         #rest_read_impl
     }
@@ -0,0 +1,115 @@
+use crate::parse::ParsedStruct;
+use quote::quote;
+use syn::ext::IdentExt;
+
+pub fn generate(parsed: &ParsedStruct) -> proc_macro2::TokenStream {
+    let vis = &parsed.vis;
+    let field_id_name = parsed.field_id_name();
+    let lock_name_str = parsed.lock_name().to_string();
+
+    let field_id_doc = if parsed.has_custom_order() {
+        format!(
+            "Identifies a field of [`{lock_name_str}`] at runtime.\n\n\
+             Variants are named after the fields and ordered by declaration. Note that \
+             `#[lock(order = N)]` overrides the lock acquisition order, so it differs from \
+             the order of these ids."
+        )
+    } else {
+        format!(
+            "Identifies a field of [`{lock_name_str}`] at runtime.\n\n\
+             Variants are named after the fields and ordered by declaration, so sorting ids \
+             yields the lock acquisition order."
+        )
+    };
+
+    let field_count = parsed.fields.len();
+    let variants: Vec<&syn::Ident> = parsed.fields.iter().map(|f| &f.name).collect();
+
+    let variant_docs: Vec<String> = parsed
+        .fields
+        .iter()
+        .map(|f| format!("The `{}` field.", f.name.unraw()))
+        .collect();
+
+    let index_arms: Vec<proc_macro2::TokenStream> = variants
+        .iter()
+        .enumerate()
+        .map(|(i, v)| quote! { Self::#v => #i, })
+        .collect();
+
+    let name_arms: Vec<proc_macro2::TokenStream> = variants
+        .iter()
+        .map(|v| {
+            let name_str = v.unraw().to_string();
+            quote! { Self::#v => #name_str, }
+        })
+        .collect();
+
+    let locked_arms: Vec<proc_macro2::TokenStream> = parsed
+        .fields
+        .iter()
+        .map(|f| {
+            let v = &f.name;
+            let locked = !f.no_lock;
+            quote! { Self::#v => #locked, }
+        })
+        .collect();
+
+    let lock_name = parsed.lock_name();
+    let impl_prefix = parsed.impl_prefix();
+    let ty_generics = parsed.ty_generics();
+    let where_clause = parsed.where_clause();
+    let fields_doc = format!(
+        "Every field's [`{field_id_name}`], in declaration order, for code that walks the \
+         fields generically, e.g. with `name()` and `is_locked()`."
+    );
+    let field_names: Vec<String> = variants.iter().map(|v| v.unraw().to_string()).collect();
+
+    quote! {
+        #[doc = #field_id_doc]
+        #[allow(non_camel_case_types, dead_code)]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        #vis enum #field_id_name {
+            #(
+                #[doc = #variant_docs]
+                #variants,
+            )*
+        }
+
+        impl #field_id_name {
+            /// Every field id, in declaration order.
+            #vis const ALL: [Self; #field_count] = [#(Self::#variants),*];
+
+            /// The field's position in declaration order.
+            #vis const fn index(self) -> usize {
+                match self {
+                    #(#index_arms)*
+                }
+            }
+
+            /// The field's name as written in the struct.
+            #vis const fn name(self) -> &'static str {
+                match self {
+                    #(#name_arms)*
+                }
+            }
+
+            /// Whether the field is wrapped in an `RwLock`, i.e. not `#[no_lock]`.
+            #vis const fn is_locked(self) -> bool {
+                match self {
+                    #(#locked_arms)*
+                }
+            }
+        }
+
+        impl<#impl_prefix> #lock_name #ty_generics #where_clause {
+            /// Every field's name, in declaration order (including `#[no_lock]` fields).
+            #vis const FIELD_NAMES: &'static [&'static str] = &[#(#field_names),*];
+
+            #[doc = #fields_doc]
+            #vis fn fields() -> impl Iterator<Item = #field_id_name> {
+                #field_id_name::ALL.into_iter()
+            }
+        }
+    }
+}
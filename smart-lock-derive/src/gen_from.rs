@@ -1,9 +1,9 @@
-use crate::parse::ParsedStruct;
-use quote::{format_ident, quote};
+use crate::parse::{deferred_bound, ParsedStruct};
+use quote::quote;
 
 pub fn generate(parsed: &ParsedStruct) -> proc_macro2::TokenStream {
     let struct_name = &parsed.name;
-    let lock_name = format_ident!("{}Lock", struct_name);
+    let lock_name = parsed.lock_name();
 
     let impl_prefix = parsed.impl_prefix();
     let ty_generics = parsed.ty_generics();
@@ -17,18 +17,42 @@ pub fn generate(parsed: &ParsedStruct) -> proc_macro2::TokenStream {
             if field.no_lock {
                 quote! { #name: value.#name, }
             } else {
-                quote! { #name: smart_lock::RwLock::new(value.#name), }
+                let init = parsed.lock_field_init(field, quote!(value.#name));
+                quote! { #name: #init, }
             }
         })
         .collect();
 
+    let hidden_inits = parsed.hidden_field_inits();
+
+    // A lock around a single collection collects into it.
+    let from_iterator_impl = match parsed.fields.as_slice() {
+        [field] if !field.no_lock => {
+            let ty = &field.ty;
+            let from_iter_where = parsed
+                .where_clause_with(&[deferred_bound(ty, quote!(::core::iter::FromIterator<__A>))]);
+            quote! {
+                impl<#impl_prefix __A> ::core::iter::FromIterator<__A> for #lock_name #ty_generics #from_iter_where {
+                    fn from_iter<__I: ::core::iter::IntoIterator<Item = __A>>(iter: __I) -> Self {
+                        Self::from_parts(smart_lock::RwLock::new(::core::iter::FromIterator::from_iter(iter)))
+                    }
+                }
+            }
+        }
+        _ => quote!(),
+    };
+
     quote! {
         impl<#impl_prefix> From<#struct_name #ty_generics> for #lock_name #ty_generics #where_clause {
             fn from(value: #struct_name #ty_generics) -> Self {
                 Self {
                     #(#field_inits)*
+                    #(#hidden_inits)*
+                    __smart_lock_instance: smart_lock::__private::InstanceId::new(),
                 }
             }
         }
+
+        #from_iterator_impl
     }
 }
@@ -0,0 +1,193 @@
+use crate::parse::{upper_camel, ParsedStruct};
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+
+/// Per-group builders from `#[lock(group = "name")]`: `builder_<name>()` on the lock
+/// returns a `FooNameGroupBuilder` that only selects the group's fields, wrapping
+/// the full builder, and locks into a `FooNameGroupGuard`: the full guard with every
+/// other field `Unlocked`.
+pub fn generate(parsed: &ParsedStruct) -> proc_macro2::TokenStream {
+    if parsed.groups.is_empty() {
+        return quote!();
+    }
+
+    let vis = &parsed.vis;
+    let lock_name = parsed.lock_name();
+    let builder_name = parsed.builder_name();
+    let field_id_name = parsed.field_id_name();
+    let guard_name = parsed.guard_name();
+    let struct_name_str = parsed.name.unraw().to_string();
+
+    let impl_prefix = parsed.impl_prefix();
+    let bare_prefix = parsed.bare_prefix();
+    let alias_prefix = parsed.alias_prefix_without_defaults();
+    let ty_generics = parsed.ty_generics();
+    let where_clause = parsed.where_clause();
+
+    // Map field index → generic index (None for no_lock fields)
+    let field_to_generic: Vec<Option<usize>> = {
+        let mut gi = 0;
+        parsed
+            .fields
+            .iter()
+            .map(|f| {
+                if f.no_lock {
+                    None
+                } else {
+                    let idx = gi;
+                    gi += 1;
+                    Some(idx)
+                }
+            })
+            .collect()
+    };
+    let locked_count = field_to_generic.iter().filter(|g| g.is_some()).count();
+
+    let groups = parsed.groups.iter().map(|group| {
+        let camel = upper_camel(&group.name.to_string());
+        let group_builder = format_ident!("{}{}GroupBuilder", struct_name_str, camel);
+        let group_guard = format_ident!("{}{}GroupGuard", struct_name_str, camel);
+        let method = format_ident!("builder_{}", group.name);
+        let params: Vec<syn::Ident> = (0..group.fields.len())
+            .map(|p| format_ident!("F{}", p))
+            .collect();
+
+        // The full builder's mode parameters: the group's own for its fields,
+        // `Unlocked` for every other field.
+        let mut inner_modes: Vec<proc_macro2::TokenStream> = (0..locked_count)
+            .map(|_| quote!(smart_lock::Unlocked))
+            .collect();
+        for (f, &i) in params.iter().zip(&group.fields) {
+            inner_modes[field_to_generic[i].unwrap()] = quote!(#f);
+        }
+        let unlocked: Vec<proc_macro2::TokenStream> = params
+            .iter()
+            .map(|_| quote!(smart_lock::Unlocked))
+            .collect();
+
+        let field_list = group
+            .fields
+            .iter()
+            .map(|&i| format!("`{}`", parsed.fields[i].name.unraw()))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let builder_doc = format!(
+            "Builder for the `{}` group of [`{}`]: {}.\n\n\
+             Only the group's fields can be selected. Created by \
+             [`{}::builder_{}()`]({}::{}); locks in the same order as the full builder.",
+            group.name, lock_name, field_list, lock_name, group.name, lock_name, method
+        );
+        let guard_doc = format!(
+            "[`{}`] for the `{}` group ({}), with every other field `Unlocked`, as \
+             returned by `builder_{}()`.",
+            guard_name, group.name, field_list, group.name
+        );
+        let lock_doc = format!(
+            "Acquire the requested locks and return the guard, like [`{0}::lock`].",
+            builder_name
+        );
+        let try_lock_doc = format!(
+            "Try to acquire the requested locks without blocking, like [`{0}::try_lock`].",
+            builder_name
+        );
+        let try_lock_verbose_doc = format!(
+            "Like `try_lock`, but reports the first field that was held, like \
+             [`{0}::try_lock_verbose`].",
+            builder_name
+        );
+        let method_doc = format!(
+            "Start a builder restricted to the `{}` group: {}.",
+            group.name, field_list
+        );
+
+        let field_impls = group.fields.iter().enumerate().map(|(p, &i)| {
+            let field_name = &parsed.fields[i].name;
+            let field_name_str = field_name.unraw().to_string();
+            let write_method = format_ident!("write_{}", field_name);
+            let read_method = format_ident!("read_{}", field_name);
+            let upgrade_method = format_ident!("upgrade_{}", field_name);
+            let write_doc = format!("Request exclusive write access to `{}`.", field_name_str);
+            let read_doc = format!("Request shared read access to `{}`.", field_name_str);
+            let upgrade_doc = format!("Request upgradable read access to `{}`.", field_name_str);
+
+            let free: Vec<&syn::Ident> = params
+                .iter()
+                .enumerate()
+                .filter(|(q, _)| *q != p)
+                .map(|(_, f)| f)
+                .collect();
+            let with_mode = |mode: proc_macro2::TokenStream| -> Vec<proc_macro2::TokenStream> {
+                params
+                    .iter()
+                    .enumerate()
+                    .map(|(q, f)| if q == p { mode.clone() } else { quote!(#f) })
+                    .collect()
+            };
+            let input = with_mode(quote!(smart_lock::Unlocked));
+            let write = with_mode(quote!(smart_lock::WriteLocked));
+            let read = with_mode(quote!(smart_lock::ReadLocked));
+            let upgrade = with_mode(quote!(smart_lock::UpgradeLocked));
+
+            quote! {
+                impl<'__sl, #impl_prefix #(#free),*> #group_builder<'__sl, #bare_prefix #(#input),*> #where_clause {
+                    #[doc = #write_doc]
+                    #vis fn #write_method(self) -> #group_builder<'__sl, #bare_prefix #(#write),*> {
+                        #group_builder { __smart_lock_builder: self.__smart_lock_builder.#write_method() }
+                    }
+
+                    #[doc = #read_doc]
+                    #vis fn #read_method(self) -> #group_builder<'__sl, #bare_prefix #(#read),*> {
+                        #group_builder { __smart_lock_builder: self.__smart_lock_builder.#read_method() }
+                    }
+
+                    #[doc = #upgrade_doc]
+                    #vis fn #upgrade_method(self) -> #group_builder<'__sl, #bare_prefix #(#upgrade),*> {
+                        #group_builder { __smart_lock_builder: self.__smart_lock_builder.#upgrade_method() }
+                    }
+                }
+            }
+        });
+
+        quote! {
+            #[doc = #builder_doc]
+            #[must_use = "builder does nothing until .lock().await is called"]
+            #vis struct #group_builder<'__sl, #impl_prefix #(#params),*> #where_clause {
+                #[doc(hidden)]
+                __smart_lock_builder: #builder_name<'__sl, #bare_prefix #(#inner_modes),*>,
+            }
+
+            #[doc = #guard_doc]
+            #vis type #group_guard<'__sl, #alias_prefix #(#params),*> = #guard_name<'__sl, #bare_prefix #(#inner_modes),*>;
+
+            #(#field_impls)*
+
+            impl<'__sl, #impl_prefix #(#params: smart_lock::LockMode),*> #group_builder<'__sl, #bare_prefix #(#params),*> #where_clause {
+                #[doc = #lock_doc]
+                #vis async fn lock(self) -> #group_guard<'__sl, #bare_prefix #(#params),*> {
+                    self.__smart_lock_builder.lock().await
+                }
+
+                #[doc = #try_lock_doc]
+                #vis fn try_lock(self) -> Option<#group_guard<'__sl, #bare_prefix #(#params),*>> {
+                    self.__smart_lock_builder.try_lock()
+                }
+
+                #[doc = #try_lock_verbose_doc]
+                #vis fn try_lock_verbose(self) -> Result<#group_guard<'__sl, #bare_prefix #(#params),*>, #field_id_name> {
+                    self.__smart_lock_builder.try_lock_verbose()
+                }
+            }
+
+            impl<#impl_prefix> #lock_name #ty_generics #where_clause {
+                #[doc = #method_doc]
+                #vis fn #method(&self) -> #group_builder<'_, #bare_prefix #(#unlocked),*> {
+                    #group_builder { __smart_lock_builder: self.builder() }
+                }
+            }
+        }
+    });
+
+    quote! {
+        #(#groups)*
+    }
+}
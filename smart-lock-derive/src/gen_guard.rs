@@ -1,18 +1,19 @@
-use crate::parse::ParsedStruct;
+use crate::parse::{deferred_bound, guard_debug_body, upper_camel, ParsedStruct};
 use quote::{format_ident, quote};
+use syn::ext::IdentExt;
 
 pub fn generate(parsed: &ParsedStruct) -> proc_macro2::TokenStream {
     let vis = &parsed.vis;
-    let lock_name = format_ident!("{}Lock", &parsed.name);
-    let builder_name = format_ident!("{}LockBuilder", &parsed.name);
-    let guard_name = format_ident!("{}LockGuard", &parsed.name);
+    let lock_name = parsed.lock_name();
+    let builder_name = parsed.builder_name();
+    let guard_name = parsed.guard_name();
 
     let impl_prefix = parsed.impl_prefix();
     let bare_prefix = parsed.bare_prefix();
     let ty_generics = parsed.ty_generics();
     let where_clause = parsed.where_clause();
 
-    let lock_name_str = format!("{}Lock", parsed.name);
+    let lock_name_str = parsed.lock_name().to_string();
     let guard_doc = format!(
         "Guard holding acquired locks for [`{lock_name_str}`].\n\n\
          Access fields via `guard.field_name` — uses `Deref`/`DerefMut` based on the lock mode:\n\
@@ -21,7 +22,12 @@ pub fn generate(parsed: &ParsedStruct) -> proc_macro2::TokenStream {
          - **`UpgradeLocked`**: `*guard.field` for read, `.upgrade_field().await` to promote to write\n\
          - **`Unlocked`**: compile error on any access\n\
          - **`#[no_lock]`**: always accessible as `&T` (no locking needed)\n\n\
-         All locks are released when the guard is dropped."
+         All locks are released when the guard is dropped, in the reverse of the order \
+         they were acquired; the fields are declared in that order.\n\n\
+         The guard is `Send` (so it can be held across `.await` in spawned tasks) whenever \
+         the lock itself is `Send + Sync`; this is asserted at compile time for the all-read \
+         and all-write shapes. Use [`smart_lock::assert_send!`](smart_lock::assert_send) to \
+         pin other shapes."
     );
 
     // Map field index → generic index (None for no_lock fields)
@@ -54,38 +60,104 @@ pub fn generate(parsed: &ParsedStruct) -> proc_macro2::TokenStream {
             let name = &field.name;
             let ty = &field.ty;
             if field.no_lock {
-                quote! { pub #name: &'a #ty, }
+                quote! { pub #name: &'__sl #ty, }
             } else {
                 let gi = field_to_generic[i].unwrap();
                 let f = &generic_names[gi];
-                quote! { pub #name: smart_lock::FieldGuard<'a, #ty, #f>, }
+                let poison = parsed.hook_arg(quote!('__sl));
+                quote! { pub #name: smart_lock::FieldGuard<'__sl, #ty, #f #poison>, }
             }
         })
         .collect();
+    // Declared in reverse acquisition order: fields drop in declaration order, so
+    // dropping the guard releases the locks in the reverse of the order they were taken.
+    let mut guard_fields = parsed.in_acquisition_order(guard_fields);
+    guard_fields.reverse();
 
     let all_unlocked: Vec<proc_macro2::TokenStream> = (0..locked_count)
         .map(|_| quote!(smart_lock::Unlocked))
         .collect();
+    let all_read: Vec<proc_macro2::TokenStream> = (0..locked_count)
+        .map(|_| quote!(smart_lock::ReadLocked))
+        .collect();
+    let all_write: Vec<proc_macro2::TokenStream> = (0..locked_count)
+        .map(|_| quote!(smart_lock::WriteLocked))
+        .collect();
+
+    // Static assertion that the all-read and all-write guards are Send. It assumes
+    // every field is `Send + Sync`: when one isn't, the lock's own `Send + Sync`
+    // assertion already reports it, and the guard check would only repeat it.
+    let assert_name = format_ident!("_assert_{}_send", guard_name);
+    let field_send_sync: Vec<proc_macro2::TokenStream> = parsed
+        .fields
+        .iter()
+        .map(|field| deferred_bound(&field.ty, quote!(Send + Sync)))
+        .collect();
+    let assert_where = parsed.where_clause_with(&field_send_sync);
 
     let guard_name_str = guard_name.to_string();
+    let guard_debug = guard_debug_body(&guard_name_str, &parsed.fields);
 
     // --- Guard struct definition ---
     let guard_struct = quote! {
         #[doc = #guard_doc]
         #[must_use = "guard releases all locks when dropped"]
-        #vis struct #guard_name<'a, #impl_prefix #(#generic_names),*> #where_clause {
+        #vis struct #guard_name<'__sl, #impl_prefix #(#generic_names),*> #where_clause {
             #[doc(hidden)]
-            lock: &'a #lock_name #ty_generics,
+            lock: &'__sl #lock_name #ty_generics,
             #(#guard_fields)*
         }
 
-        impl<'a, #impl_prefix #(#generic_names),*> std::fmt::Debug for #guard_name<'a, #bare_prefix #(#generic_names),*> #where_clause {
+        #[doc(hidden)]
+        #[allow(non_snake_case, clippy::multiple_bound_locations)]
+        const _: () = {
+            // Guard types are taken as arguments so their implied `T: '__sl` bounds apply.
+            fn #assert_name<'__sl, #impl_prefix>(
+                _all_read: Option<#guard_name<'__sl, #bare_prefix #(#all_read),*>>,
+                _all_write: Option<#guard_name<'__sl, #bare_prefix #(#all_write),*>>,
+            ) #assert_where {
+                fn _require_send<T: Send>(_: &T) {}
+                _require_send(&_all_read);
+                _require_send(&_all_write);
+            }
+        };
+
+        impl<'__sl, #impl_prefix #(#generic_names),*> std::fmt::Debug for #guard_name<'__sl, #bare_prefix #(#generic_names),*> #where_clause {
             fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                f.debug_struct(#guard_name_str).finish_non_exhaustive()
+                #guard_debug
             }
         }
     };
 
+    // Locked field indices in acquisition order.
+    let locked_order: Vec<usize> = parsed
+        .acquisition_order()
+        .into_iter()
+        .filter(|&i| !parsed.fields[i].no_lock)
+        .collect();
+    // `debug-deadlock`: check before upgrading field `i` that no field acquired after
+    // it is held. `later_mode(j)` gives the mode of a later field `j`.
+    let upgrade_check = |i: usize,
+                         later_mode: &dyn Fn(usize) -> proc_macro2::TokenStream|
+     -> proc_macro2::TokenStream {
+        if !cfg!(feature = "debug-deadlock") {
+            return quote!();
+        }
+        let pos = locked_order.iter().position(|&j| j == i).unwrap();
+        let later: Vec<proc_macro2::TokenStream> = locked_order[pos + 1..]
+            .iter()
+            .map(|&j| {
+                let name = parsed.fields[j].name.unraw().to_string();
+                let mode = later_mode(j);
+                quote!((#name, #mode))
+            })
+            .collect();
+        let field = parsed.fields[i].name.unraw().to_string();
+        quote! {
+            smart_lock::__private::check_upgrade_order(#lock_name_str, #field, &[#(#later),*]);
+        }
+    };
+
     // --- Upgrade/downgrade impl blocks per field (locked fields only) ---
     let mut transition_impls = Vec::new();
 
@@ -123,12 +195,25 @@ pub fn generate(parsed: &ParsedStruct) -> proc_macro2::TokenStream {
             field_name_str
         );
 
+        let downgrade_to_upgrade_method = format_ident!("downgrade_{}_to_upgrade", field_name);
+        let downgrade_to_upgrade_doc = format!(
+            "Atomically downgrade `{0}` from exclusive write to upgradable read.\n\n\
+             Immediately allows other readers, but keeps the upgrade slot, so no other \
+             writer can run before `.upgrade_{0}().await` regains write access. \
+             Synchronous (no `.await`).",
+            field_name_str
+        );
+
         let free_generics: Vec<&syn::Ident> = generic_names
             .iter()
             .enumerate()
             .filter(|(j, _)| *j != gi)
             .map(|(_, name)| name)
             .collect();
+        let check = upgrade_check(i, &|j| {
+            let f = &generic_names[field_to_generic[j].unwrap()];
+            quote!(<#f as smart_lock::LockMode>::MODE)
+        });
 
         let other_fields: Vec<proc_macro2::TokenStream> = parsed
             .fields
@@ -159,6 +244,16 @@ pub fn generate(parsed: &ParsedStruct) -> proc_macro2::TokenStream {
         let read_output = make_params(quote!(smart_lock::ReadLocked));
         let write_input = make_params(quote!(smart_lock::WriteLocked));
 
+        let upgrade_if_method = format_ident!("upgrade_{}_if", field_name);
+        let upgrade_if_doc = format!(
+            "Upgrade `{0}` to exclusive write only if `pred` holds for its current value.\n\n\
+             Returns `Ok` with the upgraded guard, or `Err` with this guard unchanged if \
+             `pred` returned `false`. The upgradable lock is held throughout, so no other \
+             writer can change `{0}` between the check and the upgrade. Carries the same \
+             deadlock caveat as [`upgrade_{0}`](Self::{1}).",
+            field_name_str, upgrade_method
+        );
+        let field_ty = &field.ty;
         let try_upgrade_method = format_ident!("try_upgrade_{}", field_name);
         let try_upgrade_doc = format!(
             "Try to upgrade `{}` from upgradable read to exclusive write without blocking.\n\n\
@@ -168,11 +263,29 @@ pub fn generate(parsed: &ParsedStruct) -> proc_macro2::TokenStream {
             field_name_str, field_name_str
         );
 
-        // Upgrade from UpgradeLocked + Downgrade from UpgradeLocked + Try upgrade
+        // Upgrades are available on every shape and bounded on the field's mode, so
+        // calling one on a field that isn't `UpgradeLocked` reports `Upgradable`'s
+        // message instead of a missing method. The `debug-deadlock` upgrade check
+        // reads the other fields' `LockMode::MODE`.
+        let own = &generic_names[gi];
+        let upgrade_impl_generics: Vec<proc_macro2::TokenStream> = (0..locked_count)
+            .map(|j| {
+                let f = &generic_names[j];
+                if j == gi || !cfg!(feature = "debug-deadlock") {
+                    quote!(#f)
+                } else {
+                    quote!(#f: smart_lock::LockMode)
+                }
+            })
+            .collect();
         transition_impls.push(quote! {
-            impl<'a, #impl_prefix #(#free_generics),*> #guard_name<'a, #bare_prefix #(#upgrade_input),*> #where_clause {
+            impl<'__sl, #impl_prefix #(#upgrade_impl_generics),*> #guard_name<'__sl, #bare_prefix #(#generic_names),*> #where_clause {
                 #[doc = #upgrade_doc]
-                #vis async fn #upgrade_method(self) -> #guard_name<'a, #bare_prefix #(#write_output),*> {
+                #vis async fn #upgrade_method(self) -> #guard_name<'__sl, #bare_prefix #(#write_output),*>
+                where
+                    #own: smart_lock::Upgradable,
+                {
+                    #check
                     #guard_name {
                         lock: self.lock,
                         #field_name: self.#field_name.upgrade().await,
@@ -180,8 +293,26 @@ pub fn generate(parsed: &ParsedStruct) -> proc_macro2::TokenStream {
                     }
                 }
 
+                #[doc = #upgrade_if_doc]
+                #vis async fn #upgrade_if_method(
+                    self,
+                    pred: impl FnOnce(&#field_ty) -> bool,
+                ) -> Result<#guard_name<'__sl, #bare_prefix #(#write_output),*>, Self>
+                where
+                    #own: smart_lock::Upgradable,
+                {
+                    if pred(&*self.#field_name) {
+                        Ok(self.#upgrade_method().await)
+                    } else {
+                        Err(self)
+                    }
+                }
+
                 #[doc = #try_upgrade_doc]
-                #vis fn #try_upgrade_method(self) -> Result<#guard_name<'a, #bare_prefix #(#write_output),*>, Self> {
+                #vis fn #try_upgrade_method(self) -> Result<#guard_name<'__sl, #bare_prefix #(#write_output),*>, Self>
+                where
+                    #own: smart_lock::Upgradable,
+                {
                     match self.#field_name.try_upgrade() {
                         Ok(upgraded) => Ok(#guard_name {
                             lock: self.lock,
@@ -195,9 +326,14 @@ pub fn generate(parsed: &ParsedStruct) -> proc_macro2::TokenStream {
                         }),
                     }
                 }
+            }
+        });
 
+        // Downgrade from UpgradeLocked
+        transition_impls.push(quote! {
+            impl<'__sl, #impl_prefix #(#free_generics),*> #guard_name<'__sl, #bare_prefix #(#upgrade_input),*> #where_clause {
                 #[doc = #downgrade_from_upgrade_doc]
-                #vis fn #downgrade_method(self) -> #guard_name<'a, #bare_prefix #(#read_output),*> {
+                #vis fn #downgrade_method(self) -> #guard_name<'__sl, #bare_prefix #(#read_output),*> {
                     #guard_name {
                         lock: self.lock,
                         #field_name: self.#field_name.downgrade(),
@@ -207,21 +343,344 @@ pub fn generate(parsed: &ParsedStruct) -> proc_macro2::TokenStream {
             }
         });
 
-        // Downgrade from WriteLocked
+        // Split the raw write guard out. Hooked guards can't become raw guards
+        // (see `FieldGuard::into_inner_guard`), so poison/subscribe structs skip it.
+        let into_raw_write = if parsed.poison || parsed.subscribe {
+            quote!()
+        } else {
+            let into_raw_method = format_ident!("into_raw_write_{}", field_name);
+            let into_raw_doc = format!(
+                "Split the write lock on `{0}` out as the underlying \
+                 [`RwLockWriteGuard`](smart_lock::RwLockWriteGuard), for APIs built directly \
+                 on `async_lock`.\n\n\
+                 Returns the raw guard together with the rest of this guard, where `{0}` is \
+                 now `Unlocked`. Both keep their locks until dropped.",
+                field_name_str
+            );
+            let unlocked_output = make_params(quote!(smart_lock::Unlocked));
+            quote! {
+                #[doc = #into_raw_doc]
+                #vis fn #into_raw_method(
+                    self,
+                ) -> (
+                    smart_lock::RwLockWriteGuard<'__sl, #field_ty>,
+                    #guard_name<'__sl, #bare_prefix #(#unlocked_output),*>,
+                ) {
+                    (
+                        self.#field_name.into_inner_guard(),
+                        #guard_name {
+                            lock: self.lock,
+                            #field_name: smart_lock::FieldGuard::unlocked(),
+                            #(#other_fields)*
+                        },
+                    )
+                }
+            }
+        };
+
+        // Downgrade from WriteLocked, and split out the raw guard
         transition_impls.push(quote! {
-            impl<'a, #impl_prefix #(#free_generics),*> #guard_name<'a, #bare_prefix #(#write_input),*> #where_clause {
+            impl<'__sl, #impl_prefix #(#free_generics),*> #guard_name<'__sl, #bare_prefix #(#write_input),*> #where_clause {
                 #[doc = #downgrade_from_write_doc]
-                #vis fn #downgrade_method(self) -> #guard_name<'a, #bare_prefix #(#read_output),*> {
+                #vis fn #downgrade_method(self) -> #guard_name<'__sl, #bare_prefix #(#read_output),*> {
                     #guard_name {
                         lock: self.lock,
                         #field_name: self.#field_name.downgrade(),
                         #(#other_fields)*
                     }
                 }
+
+                #[doc = #downgrade_to_upgrade_doc]
+                #vis fn #downgrade_to_upgrade_method(self) -> #guard_name<'__sl, #bare_prefix #(#upgrade_input),*> {
+                    #guard_name {
+                        lock: self.lock,
+                        #field_name: self.#field_name.downgrade_to_upgradable(),
+                        #(#other_fields)*
+                    }
+                }
+
+                #into_raw_write
             }
         });
     }
 
+    // --- upgrade_all() / try_upgrade_all() when every locked field is UpgradeLocked ---
+    let upgrade_all_impl = if locked_count == 0 {
+        quote!()
+    } else {
+        let all_upgrade: Vec<proc_macro2::TokenStream> = (0..locked_count)
+            .map(|_| quote!(smart_lock::UpgradeLocked))
+            .collect();
+        let order = &locked_order;
+        let upgraded = |i: usize| format_ident!("__upgraded_{}", parsed.fields[i].name);
+        let upgrade_steps: Vec<proc_macro2::TokenStream> = order
+            .iter()
+            .map(|&i| {
+                let name = &parsed.fields[i].name;
+                let var = upgraded(i);
+                let check = upgrade_check(i, &|_| quote!(smart_lock::LockModeKind::Upgrade));
+                quote! {
+                    #check
+                    let #var = self.#name.upgrade().await;
+                }
+            })
+            .collect();
+        // Each field of the finished guard: upgraded locals for locked fields.
+        let finished: Vec<proc_macro2::TokenStream> = parsed
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(i, f)| {
+                let name = &f.name;
+                if f.no_lock {
+                    quote!(#name: self.#name,)
+                } else {
+                    let var = upgraded(i);
+                    quote!(#name: #var,)
+                }
+            })
+            .collect();
+        // A failed `try_upgrade` at position `k` rolls back the fields before it,
+        // without reporting the unused write locks to subscribers.
+        let try_steps: Vec<proc_macro2::TokenStream> = order
+            .iter()
+            .enumerate()
+            .map(|(k, &i)| {
+                let name = &parsed.fields[i].name;
+                let var = upgraded(i);
+                let rollback: Vec<proc_macro2::TokenStream> = parsed
+                    .fields
+                    .iter()
+                    .enumerate()
+                    .map(|(j, f)| {
+                        let n = &f.name;
+                        if j == i {
+                            quote!(#n: __original,)
+                        } else if order[..k].contains(&j) {
+                            let v = upgraded(j);
+                            quote!(#n: #v.cancel_upgrade(),)
+                        } else {
+                            quote!(#n: self.#n,)
+                        }
+                    })
+                    .collect();
+                quote! {
+                    let #var = match self.#name.try_upgrade() {
+                        Ok(upgraded) => upgraded,
+                        Err(__original) => {
+                            return Err(#guard_name { lock: self.lock, #(#rollback)* });
+                        }
+                    };
+                }
+            })
+            .collect();
+        quote! {
+            impl<'__sl, #impl_prefix> #guard_name<'__sl, #bare_prefix #(#all_upgrade),*> #where_clause {
+                /// Upgrade every field to exclusive write, one at a time in acquisition order.
+                ///
+                /// Waits for each field's readers to drain while still holding the fields
+                /// after it, so the deadlock warning on the per-field `upgrade_*()` applies:
+                /// a task reading one field and then waiting to write a later one blocks
+                /// forever with this guard. Prefer [`try_upgrade_all`](Self::try_upgrade_all)
+                /// where such tasks exist.
+                #vis async fn upgrade_all(self) -> #guard_name<'__sl, #bare_prefix #(#all_write),*> {
+                    #(#upgrade_steps)*
+                    #guard_name { lock: self.lock, #(#finished)* }
+                }
+
+                /// Try to upgrade every field to exclusive write without blocking.
+                ///
+                /// Either every field is upgraded, or none is: if any field still has
+                /// readers, the fields already upgraded are downgraded back to upgradable
+                /// read and the original guard is returned in `Err`.
+                #vis fn try_upgrade_all(self) -> Result<#guard_name<'__sl, #bare_prefix #(#all_write),*>, Self> {
+                    #(#try_steps)*
+                    Ok(#guard_name { lock: self.lock, #(#finished)* })
+                }
+            }
+        }
+    };
+
+    // --- upgrade_subset!(): a hidden upgrade plan tracking each field's target mode ---
+    let upgrade_plan_impl = if locked_count == 0 {
+        quote!()
+    } else {
+        let plan_name = format_ident!("{}UpgradePlan", guard_name);
+        let targets: Vec<syn::Ident> = (0..locked_count)
+            .map(|i| format_ident!("__To{}", i))
+            .collect();
+        let mark_impls = parsed.fields.iter().enumerate().filter(|(_, f)| !f.no_lock).map(|(i, field)| {
+            let gi = field_to_generic[i].unwrap();
+            let method = format_ident!("__plan_upgrade_{}", field.name);
+            let current = &generic_names[gi];
+            let target = &targets[gi];
+            let marked: Vec<proc_macro2::TokenStream> = targets
+                .iter()
+                .enumerate()
+                .map(|(j, t)| if j == gi { quote!(smart_lock::WriteLocked) } else { quote!(#t) })
+                .collect();
+            quote! {
+                impl<'__sl, #impl_prefix #(#generic_names,)* #(#targets),*> #plan_name<'__sl, #bare_prefix #(#generic_names,)* #(#targets),*> #where_clause {
+                    #[doc(hidden)]
+                    #vis fn #method(self) -> #plan_name<'__sl, #bare_prefix #(#generic_names,)* #(#marked),*>
+                    where
+                        #current: smart_lock::__private::SubsetUpgradable<#target>,
+                    {
+                        #plan_name { guard: self.guard, _targets: std::marker::PhantomData }
+                    }
+                }
+            }
+        });
+        let upgraded = |i: usize| format_ident!("__upgraded_{}", parsed.fields[i].name);
+        let apply_steps: Vec<proc_macro2::TokenStream> = locked_order
+            .iter()
+            .map(|&i| {
+                let name = &parsed.fields[i].name;
+                let gi = field_to_generic[i].unwrap();
+                let current = &generic_names[gi];
+                let target = &targets[gi];
+                let var = upgraded(i);
+                let check = upgrade_check(i, &|j| {
+                    let f = &generic_names[field_to_generic[j].unwrap()];
+                    quote!(<#f as smart_lock::LockMode>::MODE)
+                });
+                let check = if check.is_empty() {
+                    check
+                } else {
+                    quote! {
+                        if <#current as smart_lock::LockMode>::MODE != <#target as smart_lock::LockMode>::MODE {
+                            #check
+                        }
+                    }
+                };
+                quote! {
+                    #check
+                    let #var = __guard.#name.upgrade_to::<#target>().await;
+                }
+            })
+            .collect();
+        let finished: Vec<proc_macro2::TokenStream> = parsed
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(i, f)| {
+                let name = &f.name;
+                if f.no_lock {
+                    quote!(#name: __guard.#name,)
+                } else {
+                    let var = upgraded(i);
+                    quote!(#name: #var,)
+                }
+            })
+            .collect();
+        quote! {
+            /// The guard with the target mode of each field, built by `upgrade_subset!`.
+            #[doc(hidden)]
+            #vis struct #plan_name<'__sl, #impl_prefix #(#generic_names,)* #(#targets),*> #where_clause {
+                guard: #guard_name<'__sl, #bare_prefix #(#generic_names),*>,
+                _targets: std::marker::PhantomData<fn() -> (#(#targets,)*)>,
+            }
+
+            impl<'__sl, #impl_prefix #(#generic_names),*> #guard_name<'__sl, #bare_prefix #(#generic_names),*> #where_clause {
+                #[doc(hidden)]
+                #vis fn __upgrade_plan(self) -> #plan_name<'__sl, #bare_prefix #(#generic_names,)* #(#generic_names),*> {
+                    #plan_name { guard: self, _targets: std::marker::PhantomData }
+                }
+            }
+
+            #(#mark_impls)*
+
+            impl<'__sl, #impl_prefix #(#generic_names: smart_lock::__private::UpgradeTo<#targets>,)* #(#targets: smart_lock::LockMode),*> #plan_name<'__sl, #bare_prefix #(#generic_names,)* #(#targets),*> #where_clause {
+                /// Upgrade the marked fields one at a time in acquisition order.
+                #[doc(hidden)]
+                #vis async fn __apply(self) -> #guard_name<'__sl, #bare_prefix #(#targets),*> {
+                    let __guard = self.guard;
+                    #(#apply_steps)*
+                    #guard_name { lock: __guard.lock, #(#finished)* }
+                }
+            }
+        }
+    };
+
+    // --- downgrade_all() when every locked field is WriteLocked ---
+    let downgrade_all_impl = if locked_count == 0 {
+        quote!()
+    } else {
+        let downgraded: Vec<proc_macro2::TokenStream> = parsed
+            .fields
+            .iter()
+            .map(|f| {
+                let name = &f.name;
+                if f.no_lock {
+                    quote!(#name: self.#name,)
+                } else {
+                    quote!(#name: self.#name.downgrade(),)
+                }
+            })
+            .collect();
+        quote! {
+            impl<'__sl, #impl_prefix> #guard_name<'__sl, #bare_prefix #(#all_write),*> #where_clause {
+                /// Atomically downgrade every field from exclusive write to shared read,
+                /// in declaration order, without releasing any of them.
+                ///
+                /// For an exclusive setup phase followed by a read-only phase: no writer
+                /// can slip in between the two. Synchronous (no `.await`).
+                #vis fn downgrade_all(self) -> #guard_name<'__sl, #bare_prefix #(#all_read),*> {
+                    #guard_name { lock: self.lock, #(#downgraded)* }
+                }
+            }
+        }
+    };
+
+    // --- Type aliases for common guard shapes ---
+    let alias_prefix = parsed.alias_prefix();
+    let struct_name_str = parsed.name.unraw().to_string();
+    let read_alias = parsed.read_guard_alias();
+    let write_alias = parsed.write_guard_alias();
+    let read_alias_doc =
+        format!("[`{guard_name_str}`] with every field `ReadLocked`, as returned by `lock_all()`.");
+    let write_alias_doc = format!(
+        "[`{guard_name_str}`] with every field `WriteLocked`, as returned by `lock_all_mut()`."
+    );
+    let field_write_aliases: Vec<proc_macro2::TokenStream> = parsed
+        .fields
+        .iter()
+        .enumerate()
+        .filter(|(_, f)| !f.no_lock)
+        .map(|(i, field)| {
+            let gi = field_to_generic[i].unwrap();
+            let name = field.name.unraw().to_string();
+            let camel = upper_camel(&name);
+            let alias = format_ident!("{}{}WriteGuard", struct_name_str, camel);
+            let doc = format!(
+                "[`{guard_name_str}`] with only `{name}` locked, `WriteLocked`, as returned by \
+                 `builder().write_{name}().lock()`."
+            );
+            let modes: Vec<proc_macro2::TokenStream> = (0..locked_count)
+                .map(|j| {
+                    if j == gi {
+                        quote!(smart_lock::WriteLocked)
+                    } else {
+                        quote!(smart_lock::Unlocked)
+                    }
+                })
+                .collect();
+            quote! {
+                #[doc = #doc]
+                #vis type #alias<'__sl, #alias_prefix> = #guard_name<'__sl, #bare_prefix #(#modes),*>;
+            }
+        })
+        .collect();
+    let aliases = quote! {
+        #[doc = #read_alias_doc]
+        #vis type #read_alias<'__sl, #alias_prefix> = #guard_name<'__sl, #bare_prefix #(#all_read),*>;
+
+        #[doc = #write_alias_doc]
+        #vis type #write_alias<'__sl, #alias_prefix> = #guard_name<'__sl, #bare_prefix #(#all_write),*>;
+
+        #(#field_write_aliases)*
+    };
+
     // --- relock() method ---
     let lock_bounds: Vec<proc_macro2::TokenStream> = generic_names
         .iter()
@@ -229,7 +688,7 @@ pub fn generate(parsed: &ParsedStruct) -> proc_macro2::TokenStream {
         .collect();
 
     let relock_impl = quote! {
-        impl<'a, #impl_prefix #(#lock_bounds),*> #guard_name<'a, #bare_prefix #(#generic_names),*> #where_clause {
+        impl<'__sl, #impl_prefix #(#lock_bounds),*> #guard_name<'__sl, #bare_prefix #(#generic_names),*> #where_clause {
             /// Drop all held locks and return a fresh builder for the same lock.
             ///
             /// This lets you re-acquire a different set of fields without dropping
@@ -238,15 +697,399 @@ pub fn generate(parsed: &ParsedStruct) -> proc_macro2::TokenStream {
             /// **Warning:** There is a moment between dropping the old locks and
             /// acquiring new ones where no locks are held. Other tasks may modify
             /// fields during this gap. Do not assume atomicity across a `relock()`.
-            #vis fn relock(self) -> #builder_name<'a, #bare_prefix #(#all_unlocked),*> {
+            #vis fn relock(self) -> #builder_name<'__sl, #bare_prefix #(#all_unlocked),*> {
                 #builder_name { lock: self.lock, _marker: std::marker::PhantomData }
             }
+
+            /// Release every lock, yield once to the async runtime, and re-acquire the
+            /// same fields in the same modes.
+            ///
+            /// Tasks woken by the release, such as readers queued behind a long-held
+            /// write guard, get to run in between, so a write-heavy loop can call this
+            /// periodically to let them through. Like [`relock()`](Self::relock), it is
+            /// not atomic: other tasks may change the fields before they are re-acquired.
+            #vis async fn yield_then_relock(self) -> Self {
+                let lock = self.lock;
+                drop(self);
+                smart_lock::__private::yield_now().await;
+                #builder_name { lock, _marker: std::marker::PhantomData }.lock().await
+            }
+        }
+    };
+
+    // --- split(): every locked field's guard, moved out in declaration order ---
+    let split_impl = if locked_count == 0 {
+        quote!()
+    } else {
+        let poison = parsed.hook_arg(quote!('__sl));
+        let (split_tys, split_names): (Vec<proc_macro2::TokenStream>, Vec<&syn::Ident>) = parsed
+            .fields
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| !f.no_lock)
+            .map(|(i, field)| {
+                let ty = &field.ty;
+                let f = &generic_names[field_to_generic[i].unwrap()];
+                (
+                    quote!(smart_lock::FieldGuard<'__sl, #ty, #f #poison>),
+                    &field.name,
+                )
+            })
+            .unzip();
+        quote! {
+            impl<'__sl, #impl_prefix #(#generic_names),*> #guard_name<'__sl, #bare_prefix #(#generic_names),*> #where_clause {
+                /// Consume the guard and return each locked field's guard, in declaration
+                /// order, so they can be dropped independently.
+                ///
+                /// Each `FieldGuard` keeps its mode and its lock; `Unlocked` fields come
+                /// back as empty guards. Releasing one field early, e.g. a write guard,
+                /// lets other tasks in while the rest stay held. `#[no_lock]` fields are
+                /// left out.
+                #vis fn split(self) -> (#(#split_tys,)*) {
+                    (#(self.#split_names,)*)
+                }
+            }
+        }
+    };
+
+    // --- write_ref_*(): runtime-checked write access, for code generic over modes ---
+    let write_refs: Vec<proc_macro2::TokenStream> = parsed
+        .fields
+        .iter()
+        .filter(|f| !f.no_lock)
+        .map(|field| {
+            let name = &field.name;
+            let ty = &field.ty;
+            let method = format_ident!("write_ref_{}", name);
+            let doc = format!(
+                "`Some(&mut {name})` if `{name}` is write-locked in this guard, otherwise `None`.\n\n\
+                 For code that is generic over the guard's modes and so can't use `DerefMut` \
+                 directly; with a concrete `WriteLocked` field, `&mut *guard.{name}` is simpler.",
+                name = name.unraw()
+            );
+            quote! {
+                #[doc = #doc]
+                #vis fn #method(&mut self) -> Option<&mut #ty> {
+                    smart_lock::FieldGuard::try_get_mut(&mut self.#name)
+                }
+            }
+        })
+        .collect();
+    // mode_of(): each field's mode parameter as a runtime `LockModeKind`.
+    let field_id_name = parsed.field_id_name();
+    let mode_arms: Vec<proc_macro2::TokenStream> = parsed
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let name = &field.name;
+            match field_to_generic[i] {
+                Some(gi) => {
+                    let f = &generic_names[gi];
+                    quote!(#field_id_name::#name => <#f as smart_lock::LockMode>::MODE,)
+                }
+                None => quote!(#field_id_name::#name => smart_lock::LockModeKind::None,),
+            }
+        })
+        .collect();
+    // get_dyn(): a clone of any readable field, boxed as `dyn Any`. Needs every
+    // field to be `Clone + 'static`, like `snapshot_all()`.
+    let get_dyn_arms: Vec<proc_macro2::TokenStream> = parsed
+        .fields
+        .iter()
+        .map(|field| {
+            let name = &field.name;
+            let value = if field.no_lock {
+                quote!(::core::option::Option::Some(self.#name))
+            } else {
+                quote!(smart_lock::FieldGuard::try_get(&self.#name))
+            };
+            quote! {
+                #field_id_name::#name => #value.map(|value| {
+                    ::std::boxed::Box::new(::core::clone::Clone::clone(value)) as ::std::boxed::Box<dyn ::core::any::Any>
+                }),
+            }
+        })
+        .collect();
+    let get_dyn_bounds: Vec<proc_macro2::TokenStream> = parsed
+        .fields
+        .iter()
+        .map(|field| deferred_bound(&field.ty, quote!(::core::clone::Clone + 'static)))
+        .collect();
+    let get_dyn_fn = quote! {
+        /// A clone of the field `id`, boxed as `dyn Any`, or `None` if this guard
+        /// holds it `Unlocked`.
+        ///
+        /// For scripting or RPC layers that address fields by runtime id; downcast
+        /// the result to the field's type. Only available when every field is
+        /// `Clone + 'static`.
+        #vis fn get_dyn(&self, id: #field_id_name) -> ::core::option::Option<::std::boxed::Box<dyn ::core::any::Any>>
+        where
+            #(#get_dyn_bounds),*
+        {
+            match id {
+                #(#get_dyn_arms)*
+            }
+        }
+    };
+    // fields_locked(): one bit per field, at its `FieldId::index()`. Only for
+    // structs whose fields fit in a `u64`.
+    let fields_locked_fn = if parsed.fields.len() <= 64 {
+        let bits: Vec<proc_macro2::TokenStream> = parsed
+            .fields
+            .iter()
+            .enumerate()
+            .filter_map(|(i, _)| {
+                let f = &generic_names[field_to_generic[i]?];
+                Some(quote! {
+                    | if matches!(<#f as smart_lock::LockMode>::MODE, smart_lock::LockModeKind::None) { 0 } else { 1u64 << #i }
+                })
+            })
+            .collect();
+        quote! {
+            /// A bitmask of the fields this guard holds in any mode: bit
+            /// `id.index()` is set for each locked field's id.
+            ///
+            /// A compact runtime summary of the guard's shape, for code that
+            /// branches on it without naming the concrete type; `mode_of()` gives
+            /// the mode of one field. `#[no_lock]` fields are never set. Only
+            /// generated for structs with at most 64 fields.
+            #vis fn fields_locked(&self) -> u64 {
+                0u64 #(#bits)*
+            }
+        }
+    } else {
+        quote!()
+    };
+    let write_ref_impl = quote! {
+        impl<'__sl, #impl_prefix #(#lock_bounds),*> #guard_name<'__sl, #bare_prefix #(#generic_names),*> #where_clause {
+            #(#write_refs)*
+
+            /// The mode this guard holds the field `id` in, as a runtime value.
+            ///
+            /// For code that reports on guards without naming their concrete type.
+            /// `#[no_lock]` fields have no lock and report `LockModeKind::None`; tell
+            /// them apart with `id.is_locked()`.
+            #vis fn mode_of(&self, id: #field_id_name) -> smart_lock::LockModeKind {
+                match id {
+                    #(#mode_arms)*
+                }
+            }
+
+            #fields_locked_fn
+
+            #get_dyn_fn
+        }
+    };
+
+    // --- map_field() for homogeneous structs ---
+    let map_field_impl = match parsed.homogeneous_type() {
+        Some(elem_ty) => {
+            let field_names: Vec<&syn::Ident> = parsed.fields.iter().map(|f| &f.name).collect();
+            quote! {
+                impl<'__sl, #impl_prefix #(#lock_bounds),*> #guard_name<'__sl, #bare_prefix #(#generic_names),*> #where_clause {
+                    /// Apply `f` to the field addressed by `id` at runtime.
+                    ///
+                    /// Lets uniform fields be processed table-style, e.g. by iterating
+                    /// over a list of field ids.
+                    ///
+                    /// # Panics
+                    ///
+                    /// If the addressed field is `Unlocked` in this guard.
+                    #vis fn map_field<__U>(&self, id: #field_id_name, f: impl FnOnce(&#elem_ty) -> __U) -> __U {
+                        let value = match id {
+                            #(#field_id_name::#field_names => smart_lock::FieldGuard::try_get(&self.#field_names),)*
+                        };
+                        match value {
+                            Some(value) => f(value),
+                            None => panic!("map_field: field `{}` is not locked in this guard", id.name()),
+                        }
+                    }
+                }
+            }
+        }
+        None => quote!(),
+    };
+
+    // --- map_all(): every field as a reference matching its mode (none for empty structs) ---
+    let view_name = parsed.view_name();
+    let view_doc = format!(
+        "Every field of a [`{guard_name}`], borrowed according to its mode, as passed to \
+         `map_all()`: `&mut T` for `WriteLocked`, `&T` for `ReadLocked` and `UpgradeLocked`, \
+         `()` for `Unlocked`, and `&T` for `#[no_lock]` fields."
+    );
+    let view_fields: Vec<proc_macro2::TokenStream> = parsed
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let name = &field.name;
+            let ty = &field.ty;
+            match field_to_generic[i] {
+                None => quote! { pub #name: &'__view #ty, },
+                Some(gi) => {
+                    let f = &generic_names[gi];
+                    quote! { pub #name: <#f as smart_lock::FieldView>::Ref<'__view, #ty>, }
+                }
+            }
+        })
+        .collect();
+    let view_inits: Vec<proc_macro2::TokenStream> = parsed
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let name = &field.name;
+            match field_to_generic[i] {
+                None => quote! { #name: self.#name, },
+                Some(gi) => {
+                    let f = &generic_names[gi];
+                    quote! { #name: <#f as smart_lock::FieldView>::view(&mut self.#name), }
+                }
+            }
+        })
+        .collect();
+    let view_bounds: Vec<proc_macro2::TokenStream> = generic_names
+        .iter()
+        .map(|f| quote!(#f: smart_lock::FieldView))
+        .collect();
+    let outlives: Vec<proc_macro2::TokenStream> = parsed
+        .fields
+        .iter()
+        .map(|field| {
+            let ty = &field.ty;
+            quote!(#ty: '__view)
+        })
+        .collect();
+    let view_where = parsed.where_clause_with(&outlives);
+    let map_all_impl = if parsed.fields.is_empty() {
+        quote!()
+    } else {
+        quote! {
+            #[doc = #view_doc]
+            #vis struct #view_name<'__view, #impl_prefix #(#view_bounds),*> #view_where {
+                #(#view_fields)*
+            }
+
+            impl<'__sl, #impl_prefix #(#view_bounds),*> #guard_name<'__sl, #bare_prefix #(#generic_names),*> #where_clause {
+                /// Compute a value from every field at once.
+                ///
+                /// `f` gets a view struct with one member per field, borrowed according to
+                /// its mode (`&mut` for write-locked fields, `&` for read, upgradable and
+                /// `#[no_lock]` ones, `()` for unlocked ones). The guard keeps its locks;
+                /// they are released when it is dropped.
+                #vis fn map_all<'__view, __V>(&'__view mut self, f: impl FnOnce(#view_name<'__view, #bare_prefix #(#generic_names),*>) -> __V) -> __V {
+                    f(#view_name { #(#view_inits)* })
+                }
+            }
+        }
+    };
+
+    // --- to_owned(): clone every field into the original struct ---
+    let struct_name = &parsed.name;
+    let readable_bounds: Vec<proc_macro2::TokenStream> = generic_names
+        .iter()
+        .map(|f| quote!(#f: smart_lock::Readable))
+        .collect();
+    let clone_bounds: Vec<proc_macro2::TokenStream> = parsed
+        .fields
+        .iter()
+        .map(|field| deferred_bound(&field.ty, quote!(::core::clone::Clone)))
+        .collect();
+    let clone_where = parsed.where_clause_with(&clone_bounds);
+    let cloned_fields: Vec<proc_macro2::TokenStream> = parsed
+        .fields
+        .iter()
+        .map(|field| {
+            let name = &field.name;
+            let value = if field.no_lock {
+                quote!(::core::clone::Clone::clone(self.#name))
+            } else if field.boxed {
+                quote!(::std::boxed::Box::new(::core::clone::Clone::clone(&*self.#name)))
+            } else {
+                quote!(::core::clone::Clone::clone(&*self.#name))
+            };
+            quote! { #name: #value, }
+        })
+        .collect();
+    let to_owned_doc = format!(
+        "Clone every field into a plain [`{}`], the read-side mirror of `into_inner()`.\n\n\
+         Available when the guard can read every field (e.g. from `lock_all()`) and every \
+         field is `Clone`. The guard keeps its locks.",
+        struct_name
+    );
+    let to_owned_impl = quote! {
+        impl<'__sl, #impl_prefix #(#readable_bounds),*> #guard_name<'__sl, #bare_prefix #(#generic_names),*> #clone_where {
+            #[doc = #to_owned_doc]
+            #vis fn to_owned(&self) -> #struct_name #ty_generics {
+                #struct_name {
+                    #(#cloned_fields)*
+                }
+            }
+        }
+    };
+
+    #[cfg(feature = "serde")]
+    let serialize_impl = {
+        let struct_name_str = parsed.name.to_string();
+        let field_count = parsed.fields.len();
+        let readable_bounds: Vec<proc_macro2::TokenStream> = generic_names
+            .iter()
+            .map(|f| quote!(#f: smart_lock::Readable))
+            .collect();
+        let ser_bounds: Vec<proc_macro2::TokenStream> = parsed
+            .fields
+            .iter()
+            .map(|field| deferred_bound(&field.ty, quote!(smart_lock::__private::serde::Serialize)))
+            .collect();
+        let ser_where = parsed.where_clause_with(&ser_bounds);
+        let ser_fields: Vec<proc_macro2::TokenStream> = parsed
+            .fields
+            .iter()
+            .map(|field| {
+                let name = &field.name;
+                let name_str = name.unraw().to_string();
+                if field.no_lock {
+                    quote! { state.serialize_field(#name_str, self.#name)?; }
+                } else {
+                    quote! { state.serialize_field(#name_str, &*self.#name)?; }
+                }
+            })
+            .collect();
+
+        quote! {
+            /// Serializes a guard that holds every field readable (e.g. from `lock_all()`),
+            /// using the original field names. The locks are already held, so the output
+            /// is a consistent snapshot and nothing blocks.
+            impl<'__sl, #impl_prefix #(#readable_bounds),*> smart_lock::__private::serde::Serialize for #guard_name<'__sl, #bare_prefix #(#generic_names),*> #ser_where {
+                fn serialize<__S>(&self, serializer: __S) -> ::core::result::Result<__S::Ok, __S::Error>
+                where
+                    __S: smart_lock::__private::serde::Serializer,
+                {
+                    use smart_lock::__private::serde::ser::SerializeStruct;
+                    let mut state = serializer.serialize_struct(#struct_name_str, #field_count)?;
+                    #(#ser_fields)*
+                    state.end()
+                }
+            }
         }
     };
+    #[cfg(not(feature = "serde"))]
+    let serialize_impl = proc_macro2::TokenStream::new();
 
     quote! {
         #guard_struct
+        #aliases
         #(#transition_impls)*
+        #upgrade_all_impl
+        #upgrade_plan_impl
+        #downgrade_all_impl
         #relock_impl
+        #split_impl
+        #write_ref_impl
+        #map_field_impl
+        #map_all_impl
+        #to_owned_impl
+        #serialize_impl
     }
 }
@@ -0,0 +1,78 @@
+use crate::parse::{deferred_bound, ParsedStruct};
+use quote::{format_ident, quote};
+
+/// Standard comparison traits for `FooLock`: `PartialEq` (blocking reads) and
+/// `Eq`, each available when every field implements the trait.
+pub fn generate(parsed: &ParsedStruct) -> proc_macro2::TokenStream {
+    let lock_name = parsed.lock_name();
+
+    let impl_prefix = parsed.impl_prefix();
+    let ty_generics = parsed.ty_generics();
+
+    let bounds = |bound: proc_macro2::TokenStream| -> Vec<proc_macro2::TokenStream> {
+        parsed
+            .fields
+            .iter()
+            .map(|field| deferred_bound(&field.ty, bound.clone()))
+            .collect()
+    };
+    let eq_where = parsed.where_clause_with(&bounds(quote!(::core::cmp::PartialEq)));
+    let total_eq_where = parsed.where_clause_with(&bounds(quote!(::core::cmp::Eq)));
+
+    let guard_var = |prefix: &str, field: &syn::Ident| format_ident!("__{}_{}", prefix, field);
+    let read_locks = |prefix: &str, receiver: proc_macro2::TokenStream| {
+        let per_field: Vec<proc_macro2::TokenStream> = parsed
+            .fields
+            .iter()
+            .map(|field| {
+                let name = &field.name;
+                let var = guard_var(prefix, name);
+                if field.no_lock {
+                    quote! { let #var = &#receiver.#name; }
+                } else {
+                    quote! { let #var = #receiver.#name.read_blocking(); }
+                }
+            })
+            .collect();
+        parsed.in_acquisition_order(per_field)
+    };
+    let first_locks = read_locks("first", quote!(first));
+    let second_locks = read_locks("second", quote!(second));
+    let comparisons: Vec<proc_macro2::TokenStream> = parsed
+        .fields
+        .iter()
+        .map(|field| {
+            let first = guard_var("first", &field.name);
+            let second = guard_var("second", &field.name);
+            quote!(*#first == *#second)
+        })
+        .collect();
+
+    quote! {
+        /// Compares field by field under read locks on both sides, taken with
+        /// **blocking** reads: avoid calling it from async code that may contend with
+        /// writers, and never while the current task holds a write guard on either lock.
+        ///
+        /// Both locks are read-locked in full before comparing, so the result reflects
+        /// one consistent state of each. The lock at the lower address is locked first,
+        /// so concurrent comparisons in opposite directions can't deadlock. A lock is
+        /// always equal to itself, without locking.
+        impl<#impl_prefix> ::core::cmp::PartialEq for #lock_name #ty_generics #eq_where {
+            fn eq(&self, other: &Self) -> bool {
+                if ::core::ptr::eq(self, other) {
+                    return true;
+                }
+                let (first, second) = if (self as *const Self) < (other as *const Self) {
+                    (self, other)
+                } else {
+                    (other, self)
+                };
+                #(#first_locks)*
+                #(#second_locks)*
+                true #(&& #comparisons)*
+            }
+        }
+
+        impl<#impl_prefix> ::core::cmp::Eq for #lock_name #ty_generics #total_eq_where {}
+    }
+}
@@ -1,16 +1,21 @@
-use crate::parse::ParsedStruct;
-use quote::{format_ident, quote};
+use crate::parse::{deferred_bound, mentions_lifetime, ParsedStruct};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
 
 pub fn generate(parsed: &ParsedStruct) -> proc_macro2::TokenStream {
     let vis = &parsed.vis;
     let struct_name = &parsed.name;
-    let lock_name = format_ident!("{}Lock", struct_name);
-    let builder_name = format_ident!("{}LockBuilder", struct_name);
-    let guard_name = format_ident!("{}LockGuard", struct_name);
+    let lock_name = parsed.lock_name();
+    let builder_name = parsed.builder_name();
+    let guard_name = parsed.guard_name();
+    let field_id_name = parsed.field_id_name();
 
     let impl_prefix = parsed.impl_prefix();
+    let poison = parsed.hook_arg(quote!('_));
     let bare_prefix = parsed.bare_prefix();
     let ty_generics = parsed.ty_generics();
+    let decl_generics = parsed.decl_generics();
     let where_clause = parsed.where_clause();
 
     let struct_name_str = struct_name.to_string();
@@ -37,10 +42,19 @@ pub fn generate(parsed: &ParsedStruct) -> proc_macro2::TokenStream {
                     #(#attrs)*
                     #name: #ty,
                 }
+            } else if parsed.poison {
+                let flag = ParsedStruct::poison_flag_name(field);
+                let storage = parsed.lock_field_ty(field);
+                quote! {
+                    #(#attrs)*
+                    #name: #storage,
+                    #flag: smart_lock::PoisonFlag,
+                }
             } else {
+                let storage = parsed.lock_field_ty(field);
                 quote! {
                     #(#attrs)*
-                    #name: smart_lock::RwLock<#ty>,
+                    #name: #storage,
                 }
             }
         })
@@ -49,100 +63,129 @@ pub fn generate(parsed: &ParsedStruct) -> proc_macro2::TokenStream {
     let new_params: Vec<proc_macro2::TokenStream> = parsed
         .fields
         .iter()
+        .filter(|field| !field.skip_new)
         .map(|field| {
             let name = &field.name;
-            let ty = &field.ty;
+            let ty = field.declared_ty();
             quote! { #name: #ty }
         })
         .collect();
 
-    let new_inits: Vec<proc_macro2::TokenStream> = parsed
+    let new_with_params: Vec<proc_macro2::TokenStream> = parsed
         .fields
         .iter()
+        .filter(|field| !field.skip_new)
         .map(|field| {
             let name = &field.name;
-            if field.no_lock {
-                quote! { #name, }
-            } else {
-                quote! { #name: smart_lock::RwLock::new(#name), }
-            }
+            let ty = field.declared_ty();
+            quote! { #name: impl FnOnce() -> #ty }
         })
         .collect();
 
-    let all_unlocked: Vec<proc_macro2::TokenStream> = (0..locked_count)
-        .map(|_| quote!(smart_lock::Unlocked))
-        .collect();
-    let all_read: Vec<proc_macro2::TokenStream> = (0..locked_count)
-        .map(|_| quote!(smart_lock::ReadLocked))
-        .collect();
-    let all_write: Vec<proc_macro2::TokenStream> = (0..locked_count)
-        .map(|_| quote!(smart_lock::WriteLocked))
-        .collect();
-
-    let lock_all_fields: Vec<proc_macro2::TokenStream> = parsed
+    let new_inits: Vec<proc_macro2::TokenStream> = parsed
         .fields
         .iter()
         .map(|field| {
             let name = &field.name;
-            let ty = &field.ty;
             if field.no_lock {
-                quote! { let #name = &self.#name; }
+                quote! { #name, }
+            } else if field.skip_new {
+                // Spanned at the type so a missing `Default` points at the field.
+                let ty = &field.ty;
+                let default =
+                    quote_spanned!(ty.span()=> <#ty as ::core::default::Default>::default());
+                let init =
+                    parsed.lock_field_from_lock(field, quote!(smart_lock::RwLock::new(#default)));
+                quote! { #name: #init, }
             } else {
-                quote! {
-                    let #name = smart_lock::FieldGuard::<'_, #ty, smart_lock::ReadLocked>::acquire(&self.#name).await;
-                }
+                let init = parsed.lock_field_init(field, quote!(#name));
+                quote! { #name: #init, }
             }
         })
         .collect();
 
-    let lock_all_mut_fields: Vec<proc_macro2::TokenStream> = parsed
+    let new_field_names: Vec<&syn::Ident> = parsed
         .fields
         .iter()
-        .map(|field| {
-            let name = &field.name;
-            let ty = &field.ty;
-            if field.no_lock {
-                quote! { let #name = &self.#name; }
-            } else {
-                quote! {
-                    let #name = smart_lock::FieldGuard::<'_, #ty, smart_lock::WriteLocked>::acquire(&self.#name).await;
-                }
-            }
-        })
+        .filter(|field| !field.skip_new)
+        .map(|field| &field.name)
         .collect();
 
-    let try_lock_all_fields: Vec<proc_macro2::TokenStream> = parsed
+    let from_parts_params: Vec<proc_macro2::TokenStream> = parsed
         .fields
         .iter()
         .map(|field| {
             let name = &field.name;
             let ty = &field.ty;
             if field.no_lock {
-                quote! { let #name = &self.#name; }
+                quote! { #name: #ty }
             } else {
-                quote! {
-                    let #name = smart_lock::FieldGuard::<'_, #ty, smart_lock::ReadLocked>::try_acquire(&self.#name)?;
-                }
+                quote! { #name: smart_lock::RwLock<#ty> }
             }
         })
         .collect();
 
-    let try_lock_all_mut_fields: Vec<proc_macro2::TokenStream> = parsed
+    let from_parts_inits: Vec<proc_macro2::TokenStream> = parsed
         .fields
         .iter()
         .map(|field| {
             let name = &field.name;
-            let ty = &field.ty;
             if field.no_lock {
-                quote! { let #name = &self.#name; }
+                quote! { #name, }
             } else {
-                quote! {
-                    let #name = smart_lock::FieldGuard::<'_, #ty, smart_lock::WriteLocked>::try_acquire(&self.#name)?;
-                }
+                let init = parsed.lock_field_from_lock(field, quote!(#name));
+                quote! { #name: #init, }
             }
         })
         .collect();
 
+    let hidden_inits = parsed.hidden_field_inits();
+
+    let all_unlocked: Vec<proc_macro2::TokenStream> = (0..locked_count)
+        .map(|_| quote!(smart_lock::Unlocked))
+        .collect();
+    let all_read: Vec<proc_macro2::TokenStream> = (0..locked_count)
+        .map(|_| quote!(smart_lock::ReadLocked))
+        .collect();
+    let all_write: Vec<proc_macro2::TokenStream> = (0..locked_count)
+        .map(|_| quote!(smart_lock::WriteLocked))
+        .collect();
+    let all_upgrade: Vec<proc_macro2::TokenStream> = (0..locked_count)
+        .map(|_| quote!(smart_lock::UpgradeLocked))
+        .collect();
+
+    // Acquisitions of every locked field in `mode`, for `lock_all*()` / `try_lock_all*()`.
+    let lock_all_acquisitions = |mode: proc_macro2::TokenStream,
+                                 try_acquire: bool|
+     -> Vec<proc_macro2::TokenStream> {
+        let per_field: Vec<proc_macro2::TokenStream> = parsed
+                .fields
+                .iter()
+                .map(|field| {
+                    let name = &field.name;
+                    let ty = &field.ty;
+                    let acquire = parsed.acquire_call(field, quote!(self), try_acquire);
+                    let finish = if try_acquire { quote!(?) } else { quote!(.await) };
+                    if field.no_lock {
+                        quote! { let #name = &self.#name; }
+                    } else {
+                        quote! {
+                            let #name = smart_lock::FieldGuard::<'_, #ty, #mode #poison>::#acquire #finish;
+                        }
+                    }
+                })
+                .collect();
+        parsed.in_acquisition_order(per_field)
+    };
+    let lock_all_fields = lock_all_acquisitions(quote!(smart_lock::ReadLocked), false);
+    let lock_all_mut_fields = lock_all_acquisitions(quote!(smart_lock::WriteLocked), false);
+    let lock_all_upgradable_fields =
+        lock_all_acquisitions(quote!(smart_lock::UpgradeLocked), false);
+    let try_lock_all_fields = lock_all_acquisitions(quote!(smart_lock::ReadLocked), true);
+    let try_lock_all_mut_fields = lock_all_acquisitions(quote!(smart_lock::WriteLocked), true);
+    let try_lock_all_upgradable_fields =
+        lock_all_acquisitions(quote!(smart_lock::UpgradeLocked), true);
+
     let field_names: Vec<&syn::Ident> = parsed.fields.iter().map(|f| &f.name).collect();
 
     let per_field_accessors: Vec<proc_macro2::TokenStream> = parsed
@@ -166,15 +209,258 @@ pub fn generate(parsed: &ParsedStruct) -> proc_macro2::TokenStream {
             let try_write_doc = format!("Try to acquire an exclusive write lock on `{}`. Returns `None` if the lock is held.", name_str);
             let upgrade_doc = format!("Acquire an upgradable read lock on `{}`. Can be atomically upgraded to a write lock later.", name_str);
             let try_upgrade_doc = format!("Try to acquire an upgradable read lock on `{}`. Returns `None` if another upgradable or write lock is held.", name_str);
+            let hook = parsed.hook(field, quote!(self));
+            let hook_arg = parsed.hook_arg(quote!('_));
+            let read_blocking_method = format_ident!("read_{}_blocking", name);
+            let write_blocking_method = format_ident!("write_{}_blocking", name);
+            let blocking_note = "For sync code such as `Drop` impls; never call it from an \
+                                 async task, which would stall its executor thread (and deadlock \
+                                 a single-threaded runtime if another task on it holds the lock).";
+            let read_blocking_doc = format!(
+                "Like [`{read_method}`](Self::{read_method}), but blocks the current thread \
+                 until the lock is acquired. {blocking_note}"
+            );
+            let write_blocking_doc = format!(
+                "Like [`{write_method}`](Self::{write_method}), but blocks the current thread \
+                 until the lock is acquired. {blocking_note}"
+            );
+            let snapshot_method = format_ident!("snapshot_{}", name);
+            let snapshot_doc = format!(
+                "Clone `{}` out under a brief read lock. The lock is released before this \
+                 returns, so the copy can be held across `.await` freely.",
+                name_str
+            );
+            let clone_bound = deferred_bound(ty, quote!(::core::clone::Clone));
+            let compare_and_set_method = format_ident!("compare_and_set_{}", name);
+            let compare_and_set_doc = if parsed.poison {
+                format!(
+                    "Write-lock `{}` and set it to `new` if it equals `expected`. Returns \
+                     whether it was set, or `Err(Poisoned)` without comparing if the field \
+                     is poisoned.",
+                    name_str
+                )
+            } else {
+                format!(
+                    "Write-lock `{}` and set it to `new` if it equals `expected`. Returns \
+                     whether it was set.",
+                    name_str
+                )
+            };
+            let eq_bound = deferred_bound(ty, quote!(::core::cmp::PartialEq));
+            let modify_method = format_ident!("modify_{}", name);
+            let fetch_modify_method = format_ident!("fetch_modify_{}", name);
+            let modify_doc = if parsed.poison {
+                format!(
+                    "Write-lock `{}` and apply `f` to it. The lock is released before this \
+                     returns.\n\n\
+                     Returns `Err(Poisoned)` without calling `f` if the field is poisoned; a \
+                     panic in `f` poisons it.",
+                    name_str
+                )
+            } else {
+                format!(
+                    "Write-lock `{}` and apply `f` to it. The lock is released before this returns.",
+                    name_str
+                )
+            };
+            let fetch_modify_doc = format!(
+                "Like [`{modify_method}`](Self::{modify_method}), but clones `{name_str}` \
+                 before applying `f` and returns that previous value."
+            );
+            // Hooked guards poison on a panic in `f` and notify subscribers on release.
+            let modify_guard = match &hook {
+                Some(hook) => quote! {
+                    smart_lock::FieldGuard::<'_, #ty, smart_lock::WriteLocked #hook_arg>::acquire_with(&self.#name, #hook).await
+                },
+                None => quote!(self.#name.write().await),
+            };
+            // Under `poison`, a poisoned field is reported instead of updated, as by
+            // `write_*()`, with the guard in the error for repairs.
+            let poisoned_ty = quote! {
+                smart_lock::Poisoned<smart_lock::FieldGuard<'_, #ty, smart_lock::WriteLocked #hook_arg>>
+            };
+            let check_poison = parsed.poison.then(|| {
+                quote! {
+                    if smart_lock::FieldGuard::is_poisoned(&guard) {
+                        return Err(smart_lock::Poisoned::new(guard));
+                    }
+                }
+            });
+            let poison_result = |ty: proc_macro2::TokenStream| {
+                if parsed.poison {
+                    quote!(Result<#ty, #poisoned_ty>)
+                } else {
+                    ty
+                }
+            };
+            let poison_ok = |value: proc_macro2::TokenStream| {
+                if parsed.poison {
+                    quote!(Ok(#value))
+                } else {
+                    value
+                }
+            };
+            // A mismatch hands the write lock back unused, so subscribers aren't told.
+            let discard_guard = hook.as_ref().map(|_| quote!(drop(guard.cancel_upgrade());));
+            let modify_ret = parsed.poison.then(|| quote!(-> Result<(), #poisoned_ty>));
+            let modified = parsed.poison.then(|| quote!(Ok(())));
+            let fetch_modify_ret = poison_result(quote!(#ty));
+            let previous = poison_ok(quote!(previous));
+            let compare_and_set_ret = poison_result(quote!(bool));
+            let set = poison_ok(quote!(true));
+            let not_set = poison_ok(quote!(false));
+            let write_accessors = if parsed.poison {
+                let flag = ParsedStruct::poison_flag_name(field);
+                let is_poisoned_method = format_ident!("is_poisoned_{}", name);
+                let clear_poison_method = format_ident!("clear_poison_{}", name);
+                let write_doc = format!(
+                    "Acquire an exclusive write lock on `{}`.\n\n\
+                     Returns `Err(Poisoned)` (still carrying the guard) if a thread panicked \
+                     while holding `{}` for writing.",
+                    name_str, name_str
+                );
+                let try_write_doc = format!(
+                    "Try to acquire an exclusive write lock on `{}`. Returns `None` if the lock \
+                     is held, and `Some(Err(Poisoned))` if the field is poisoned.",
+                    name_str
+                );
+                let is_poisoned_doc = format!("Whether a thread panicked while holding `{}` for writing.", name_str);
+                let clear_poison_doc = format!("Clear the poison flag on `{}`, e.g. after restoring it to a consistent state.", name_str);
+                quote! {
+                    #[doc = #write_doc]
+                    #vis async fn #write_method(&self) -> Result<
+                        smart_lock::FieldGuard<'_, #ty, smart_lock::WriteLocked #hook_arg>,
+                        smart_lock::Poisoned<smart_lock::FieldGuard<'_, #ty, smart_lock::WriteLocked #hook_arg>>,
+                    > {
+                        let guard = smart_lock::FieldGuard::acquire_with(&self.#name, #hook).await;
+                        if smart_lock::FieldGuard::is_poisoned(&guard) {
+                            Err(smart_lock::Poisoned::new(guard))
+                        } else {
+                            Ok(guard)
+                        }
+                    }
+
+                    #[doc = #write_blocking_doc]
+                    #vis fn #write_blocking_method(&self) -> Result<
+                        smart_lock::FieldGuard<'_, #ty, smart_lock::WriteLocked #hook_arg>,
+                        smart_lock::Poisoned<smart_lock::FieldGuard<'_, #ty, smart_lock::WriteLocked #hook_arg>>,
+                    > {
+                        let guard = smart_lock::FieldGuard::acquire_with_blocking(&self.#name, #hook);
+                        if smart_lock::FieldGuard::is_poisoned(&guard) {
+                            Err(smart_lock::Poisoned::new(guard))
+                        } else {
+                            Ok(guard)
+                        }
+                    }
+
+                    #[doc = #try_write_doc]
+                    #vis fn #try_write_method(&self) -> Option<Result<
+                        smart_lock::FieldGuard<'_, #ty, smart_lock::WriteLocked #hook_arg>,
+                        smart_lock::Poisoned<smart_lock::FieldGuard<'_, #ty, smart_lock::WriteLocked #hook_arg>>,
+                    >> {
+                        let guard = smart_lock::FieldGuard::try_acquire_with(&self.#name, #hook)?;
+                        Some(if smart_lock::FieldGuard::is_poisoned(&guard) {
+                            Err(smart_lock::Poisoned::new(guard))
+                        } else {
+                            Ok(guard)
+                        })
+                    }
+
+                    #[doc = #is_poisoned_doc]
+                    #vis fn #is_poisoned_method(&self) -> bool {
+                        self.#flag.is_poisoned()
+                    }
+
+                    #[doc = #clear_poison_doc]
+                    #vis fn #clear_poison_method(&self) {
+                        self.#flag.clear()
+                    }
+                }
+            } else if parsed.subscribe {
+                let write_doc = format!(
+                    "Acquire an exclusive write lock on `{}`. Subscribers receive \
+                     `{}::{}` when the guard is released.",
+                    name_str, field_id_name, name_str
+                );
+                let try_write_doc = format!(
+                    "Try to acquire an exclusive write lock on `{}`. Returns `None` if the lock \
+                     is held. Subscribers receive `{}::{}` when the guard is released.",
+                    name_str, field_id_name, name_str
+                );
+                quote! {
+                    #[doc = #write_doc]
+                    #vis async fn #write_method(&self) -> smart_lock::FieldGuard<'_, #ty, smart_lock::WriteLocked #hook_arg> {
+                        smart_lock::FieldGuard::acquire_with(&self.#name, #hook).await
+                    }
+
+                    #[doc = #write_blocking_doc]
+                    #vis fn #write_blocking_method(&self) -> smart_lock::FieldGuard<'_, #ty, smart_lock::WriteLocked #hook_arg> {
+                        smart_lock::FieldGuard::acquire_with_blocking(&self.#name, #hook)
+                    }
+
+                    #[doc = #try_write_doc]
+                    #vis fn #try_write_method(&self) -> Option<smart_lock::FieldGuard<'_, #ty, smart_lock::WriteLocked #hook_arg>> {
+                        smart_lock::FieldGuard::try_acquire_with(&self.#name, #hook)
+                    }
+                }
+            } else {
+                quote! {
+                    #[doc = #write_doc]
+                    #vis async fn #write_method(&self) -> smart_lock::RwLockWriteGuard<'_, #ty> {
+                        self.#name.write().await
+                    }
+
+                    #[doc = #write_blocking_doc]
+                    #vis fn #write_blocking_method(&self) -> smart_lock::RwLockWriteGuard<'_, #ty> {
+                        self.#name.write_blocking()
+                    }
+
+                    #[doc = #try_write_doc]
+                    #vis fn #try_write_method(&self) -> Option<smart_lock::RwLockWriteGuard<'_, #ty>> {
+                        self.#name.try_write()
+                    }
+                }
+            };
+
+            // Under `subscribe`, a raw upgradable guard could be upgraded without
+            // notifying, so hand out a hooked `FieldGuard` instead.
+            let upgrade_accessors = if parsed.subscribe {
+                quote! {
+                    #[doc = #upgrade_doc]
+                    #vis async fn #upgrade_method(&self) -> smart_lock::FieldGuard<'_, #ty, smart_lock::UpgradeLocked #hook_arg> {
+                        smart_lock::FieldGuard::acquire_with(&self.#name, #hook).await
+                    }
+
+                    #[doc = #try_upgrade_doc]
+                    #vis fn #try_upgrade_method(&self) -> Option<smart_lock::FieldGuard<'_, #ty, smart_lock::UpgradeLocked #hook_arg>> {
+                        smart_lock::FieldGuard::try_acquire_with(&self.#name, #hook)
+                    }
+                }
+            } else {
+                quote! {
+                    #[doc = #upgrade_doc]
+                    #vis async fn #upgrade_method(&self) -> smart_lock::RwLockUpgradableReadGuard<'_, #ty> {
+                        self.#name.upgradable_read().await
+                    }
+
+                    #[doc = #try_upgrade_doc]
+                    #vis fn #try_upgrade_method(&self) -> Option<smart_lock::RwLockUpgradableReadGuard<'_, #ty>> {
+                        self.#name.try_upgradable_read()
+                    }
+                }
+            };
+
             quote! {
                 #[doc = #read_doc]
                 #vis async fn #read_method(&self) -> smart_lock::RwLockReadGuard<'_, #ty> {
                     self.#name.read().await
                 }
 
-                #[doc = #write_doc]
-                #vis async fn #write_method(&self) -> smart_lock::RwLockWriteGuard<'_, #ty> {
-                    self.#name.write().await
+                #write_accessors
+
+                #[doc = #read_blocking_doc]
+                #vis fn #read_blocking_method(&self) -> smart_lock::RwLockReadGuard<'_, #ty> {
+                    self.#name.read_blocking()
                 }
 
                 #[doc = #try_read_doc]
@@ -182,19 +468,79 @@ pub fn generate(parsed: &ParsedStruct) -> proc_macro2::TokenStream {
                     self.#name.try_read()
                 }
 
-                #[doc = #try_write_doc]
-                #vis fn #try_write_method(&self) -> Option<smart_lock::RwLockWriteGuard<'_, #ty>> {
-                    self.#name.try_write()
+                #[doc = #snapshot_doc]
+                #vis async fn #snapshot_method(&self) -> #ty where #clone_bound {
+                    ::core::clone::Clone::clone(&*self.#name.read().await)
                 }
 
-                #[doc = #upgrade_doc]
-                #vis async fn #upgrade_method(&self) -> smart_lock::RwLockUpgradableReadGuard<'_, #ty> {
-                    self.#name.upgradable_read().await
+                #[doc = #compare_and_set_doc]
+                #vis async fn #compare_and_set_method(&self, expected: #ty, new: #ty) -> #compare_and_set_ret where #eq_bound {
+                    let mut guard = #modify_guard;
+                    #check_poison
+                    if *guard != expected {
+                        #discard_guard
+                        return #not_set;
+                    }
+                    *guard = new;
+                    #set
+                }
+
+                #[doc = #modify_doc]
+                #vis async fn #modify_method(&self, f: impl ::core::ops::FnOnce(&mut #ty)) #modify_ret {
+                    let mut guard = #modify_guard;
+                    #check_poison
+                    f(&mut *guard);
+                    #modified
                 }
 
-                #[doc = #try_upgrade_doc]
-                #vis fn #try_upgrade_method(&self) -> Option<smart_lock::RwLockUpgradableReadGuard<'_, #ty>> {
-                    self.#name.try_upgradable_read()
+                #[doc = #fetch_modify_doc]
+                #vis async fn #fetch_modify_method(&self, f: impl ::core::ops::FnOnce(&mut #ty)) -> #fetch_modify_ret where #clone_bound {
+                    let mut guard = #modify_guard;
+                    #check_poison
+                    let previous = ::core::clone::Clone::clone(&*guard);
+                    f(&mut *guard);
+                    #previous
+                }
+
+                #upgrade_accessors
+            }
+        })
+        .collect();
+
+    let raw_accessors: Vec<proc_macro2::TokenStream> = parsed
+        .fields
+        .iter()
+        .map(|field| {
+            let name = &field.name;
+            let ty = &field.ty;
+            let method = format_ident!("raw_{}", name);
+            if field.no_lock {
+                let doc = format!(
+                    "Get a shared reference to `{}` (not wrapped in `RwLock`), without \
+                     building a guard.",
+                    name
+                );
+                quote! {
+                    #[doc = #doc]
+                    #vis fn #method(&self) -> &#ty {
+                        &self.#name
+                    }
+                }
+            } else {
+                let doc = format!(
+                    "Get the underlying `RwLock` for `{}`, e.g. to hand it to code that expects \
+                     the raw [`async_lock`](smart_lock::RwLock) type.\n\n\
+                     **Warning:** locking through this reference bypasses the builder, so it \
+                     forfeits the fixed acquisition order that makes multi-field locking \
+                     deadlock-free. Don't hold a lock taken here while acquiring other fields \
+                     of this struct.",
+                    name
+                );
+                quote! {
+                    #[doc = #doc]
+                    #vis fn #method(&self) -> &smart_lock::RwLock<#ty> {
+                        &self.#name
+                    }
                 }
             }
         })
@@ -208,19 +554,168 @@ pub fn generate(parsed: &ParsedStruct) -> proc_macro2::TokenStream {
             if field.no_lock {
                 quote! { #name: self.#name, }
             } else {
-                quote! { #name: self.#name.into_inner(), }
+                let value = parsed.lock_field_into_inner(field, quote!(self));
+                quote! { #name: #value, }
             }
         })
         .collect();
 
     let into_inner_doc = format!(
         "Consume the lock and return the original [`{name}`] with all field values.\n\n\
-         When the lock is behind an `Arc`, unwrap it first:\n\
+         When the lock is behind an `Arc`, use [`{lock}::try_unwrap_inner`].",
+        name = struct_name_str,
+        lock = lock_name,
+    );
+    let try_unwrap_inner_doc = format!(
+        "Unwrap an `Arc<{lock}>` and return the original [`{name}`], or give the \
+         `Arc` back if other references to the lock still exist.\n\n\
          ```ignore\n\
-         let inner = Arc::try_unwrap(arc).expect(\"other refs exist\").into_inner();\n\
+         let inner = match {lock}::try_unwrap_inner(arc) {{\n    \
+             Ok(inner) => inner,\n    \
+             Err(arc) => return retry_later(arc),\n\
+         }};\n\
          ```",
-        name = struct_name_str
+        name = struct_name_str,
+        lock = lock_name,
+    );
+
+    // snapshot_all(): clone every field out under one lock_all(), via the guard's to_owned().
+    let snapshot_all_bounds: Vec<proc_macro2::TokenStream> = parsed
+        .fields
+        .iter()
+        .map(|field| deferred_bound(&field.ty, quote!(::core::clone::Clone)))
+        .collect();
+    let snapshot_all_where = if snapshot_all_bounds.is_empty() {
+        quote!()
+    } else {
+        quote!(where #(#snapshot_all_bounds),*)
+    };
+    // Snapshot: the same clone from a Send future. Fields that mention a lifetime are
+    // proven Send + Sync from their concrete types (see mentions_lifetime), which only
+    // the send assertion guarantees, so no_send_assert locks with one get no impl.
+    let snapshot_trait = !parsed.no_send_assert
+        || !parsed
+            .fields
+            .iter()
+            .any(|field| mentions_lifetime(field.ty.to_token_stream()));
+    let snapshot_trait_bounds: Vec<proc_macro2::TokenStream> = parsed
+        .fields
+        .iter()
+        .map(|field| {
+            if mentions_lifetime(field.ty.to_token_stream()) {
+                deferred_bound(&field.ty, quote!(::core::clone::Clone))
+            } else {
+                deferred_bound(&field.ty, quote!(::core::clone::Clone + Send + Sync))
+            }
+        })
+        .collect();
+    let snapshot_trait_where = parsed.where_clause_with(&snapshot_trait_bounds);
+    let snapshot_impl = if snapshot_trait {
+        quote! {
+            impl<#impl_prefix> smart_lock::Snapshot for #lock_name #ty_generics #snapshot_trait_where {
+                #[inline]
+                fn snapshot(&self) -> impl ::core::future::Future<Output = Self::Inner> + Send {
+                    #lock_name::snapshot_all(self)
+                }
+            }
+        }
+    } else {
+        quote!()
+    };
+
+    // hash_snapshot(): hash every field in declaration order under one lock_all().
+    let hash_snapshot_fields: Vec<proc_macro2::TokenStream> = parsed
+        .fields
+        .iter()
+        .map(|field| {
+            let name = &field.name;
+            if field.no_lock {
+                quote! { ::core::hash::Hash::hash(guard.#name, state); }
+            } else {
+                quote! { ::core::hash::Hash::hash(&*guard.#name, state); }
+            }
+        })
+        .collect();
+    let hash_snapshot_bounds: Vec<proc_macro2::TokenStream> = parsed
+        .fields
+        .iter()
+        .map(|field| deferred_bound(&field.ty, quote!(::core::hash::Hash)))
+        .collect();
+    let hash_snapshot_where = if hash_snapshot_bounds.is_empty() {
+        quote!()
+    } else {
+        quote!(where #(#hash_snapshot_bounds),*)
+    };
+
+    // debug_blocking(): format every field under blocking reads, in acquisition order.
+    let debug_blocking_locks: Vec<proc_macro2::TokenStream> = parsed.in_acquisition_order(
+        parsed
+            .fields
+            .iter()
+            .map(|field| {
+                let name = &field.name;
+                let var = format_ident!("__debug_{}", name);
+                if field.no_lock {
+                    quote! { let #var = &self.#name; }
+                } else {
+                    quote! { let #var = self.#name.read_blocking(); }
+                }
+            })
+            .collect(),
     );
+    let debug_blocking_fields: Vec<proc_macro2::TokenStream> = parsed
+        .fields
+        .iter()
+        .map(|field| {
+            let name = &field.name;
+            let var = format_ident!("__debug_{}", name);
+            let name_str = name.unraw().to_string();
+            quote! { debug.field(#name_str, &*#var); }
+        })
+        .collect();
+    let debug_blocking_bounds: Vec<proc_macro2::TokenStream> = parsed
+        .fields
+        .iter()
+        .map(|field| deferred_bound(&field.ty, quote!(::core::fmt::Debug)))
+        .collect();
+    let debug_blocking_where = if debug_blocking_bounds.is_empty() {
+        quote!()
+    } else {
+        quote!(where #(#debug_blocking_bounds),*)
+    };
+
+    // replace_all(): swap every field for `new`'s under one lock_all_mut(). Not
+    // generated with #[no_lock] fields, which can't be replaced through `&self`.
+    let replace_all_method = if parsed.fields.iter().any(|f| f.no_lock) {
+        quote!()
+    } else {
+        let replace_all_fields: Vec<proc_macro2::TokenStream> = parsed
+            .fields
+            .iter()
+            .map(|field| {
+                let name = &field.name;
+                let value = if field.boxed {
+                    quote!(::std::boxed::Box::new(::std::mem::replace(&mut *guard.#name, *new.#name)))
+                } else {
+                    quote!(::std::mem::replace(&mut *guard.#name, new.#name))
+                };
+                quote! { #name: #value, }
+            })
+            .collect();
+        quote! {
+            /// Replace every field with the one in `new` under a single
+            /// [`lock_all_mut()`](Self::lock_all_mut), returning the old values.
+            ///
+            /// No reader sees a mix of old and new fields. Not generated for structs
+            /// with `#[no_lock]` fields, which can't be replaced through `&self`.
+            #vis async fn replace_all(&self, new: #struct_name #ty_generics) -> #struct_name #ty_generics {
+                let mut guard = self.lock_all_mut().await;
+                #struct_name {
+                    #(#replace_all_fields)*
+                }
+            }
+        }
+    };
 
     let get_mut_accessors: Vec<proc_macro2::TokenStream> = parsed
         .fields
@@ -230,46 +725,365 @@ pub fn generate(parsed: &ParsedStruct) -> proc_macro2::TokenStream {
             let ty = &field.ty;
             let name_str = name.to_string();
             let method = format_ident!("get_mut_{}", name);
+            let take_method = format_ident!("take_{}", name);
+            let replace_method = format_ident!("replace_{}", name);
+            let take_doc = format!("Take `{}`, leaving `Default::default()` in its place. Requires `&mut self`, so no locking is needed.", name_str);
+            let replace_doc = format!("Replace `{}` with `value`, returning the old value. Requires `&mut self`, so no locking is needed.", name_str);
+            let default_bound = deferred_bound(ty, quote!(::core::default::Default));
+            let (get_mut_doc, place) = if field.no_lock {
+                (
+                    format!("Get a mutable reference to `{}` (not wrapped in `RwLock`).", name_str),
+                    quote!(&mut self.#name),
+                )
+            } else {
+                (
+                    format!("Get a mutable reference to `{}` without locking. Requires `&mut self`, guaranteeing exclusive access.", name_str),
+                    parsed.lock_field_get_mut(field, quote!(self)),
+                )
+            };
+            quote! {
+                #[doc = #get_mut_doc]
+                #vis fn #method(&mut self) -> &mut #ty {
+                    #place
+                }
+
+                #[doc = #take_doc]
+                #vis fn #take_method(&mut self) -> #ty where #default_bound {
+                    std::mem::take(#place)
+                }
+
+                #[doc = #replace_doc]
+                #vis fn #replace_method(&mut self, value: #ty) -> #ty {
+                    std::mem::replace(#place, value)
+                }
+            }
+        })
+        .collect();
+
+    // get_all_mut(): every field's `&mut` at once, as a `FooMut`.
+    let mut_name = parsed.mut_name();
+    let mut_name_str = mut_name.to_string();
+    let mut_doc = format!(
+        "Mutable references to every field of a [`{lock_name}`], returned by \
+         [`{lock_name}::get_all_mut`]. Borrowing them all at once lets several fields \
+         be mutated together without locking."
+    );
+    let mut mut_fields: Vec<proc_macro2::TokenStream> = parsed
+        .fields
+        .iter()
+        .map(|field| {
+            let name = &field.name;
+            let ty = &field.ty;
+            quote! { pub #name: &'__sl mut #ty, }
+        })
+        .collect();
+    let mut mut_places: Vec<proc_macro2::TokenStream> = parsed
+        .fields
+        .iter()
+        .map(|field| {
+            let name = &field.name;
             if field.no_lock {
-                let get_mut_doc = format!("Get a mutable reference to `{}` (not wrapped in `RwLock`).", name_str);
+                quote! { #name: &mut self.#name, }
+            } else {
+                let place = parsed.lock_field_get_mut(field, quote!(self));
+                quote! { #name: #place, }
+            }
+        })
+        .collect();
+    if parsed.fields.is_empty() {
+        // Nothing else uses `'__sl`.
+        mut_fields.push(quote! {
+            #[doc(hidden)]
+            _lock: ::core::marker::PhantomData<&'__sl mut ()>,
+        });
+        mut_places.push(quote! { _lock: ::core::marker::PhantomData, });
+    }
+    let get_all_mut_doc = if parsed.fields.iter().any(|f| parsed.is_owned(f)) {
+        "Mutable references to every field at once, without locking. Requires \
+         `&mut self`, guaranteeing exclusive access.\n\n\
+         # Panics\n\n\
+         If an owned guard on any locked field is still alive."
+    } else {
+        "Mutable references to every field at once, without locking. Requires \
+         `&mut self`, guaranteeing exclusive access."
+    };
+
+    // swap_a_b for every `#[swap(a, b)]` pair.
+    let acquisition_position: Vec<usize> = {
+        let mut position = vec![0; parsed.fields.len()];
+        for (pos, i) in parsed.acquisition_order().into_iter().enumerate() {
+            position[i] = pos;
+        }
+        position
+    };
+    let swap_methods: Vec<proc_macro2::TokenStream> = parsed
+        .swaps
+        .iter()
+        .map(|&(i, j)| {
+            let (a, b) = (&parsed.fields[i], &parsed.fields[j]);
+            let (first, second) = if acquisition_position[i] < acquisition_position[j] {
+                (&a.name, &b.name)
+            } else {
+                (&b.name, &a.name)
+            };
+            let method = format_ident!("swap_{}_{}", a.name, b.name);
+            let doc = format!(
+                "Write-lock `{}` and `{}` (in acquisition order) and swap their values.",
+                a.name, b.name
+            );
+            let (a_name, b_name) = (&a.name, &b.name);
+            let notify = parsed.subscribe.then(|| {
                 quote! {
-                    #[doc = #get_mut_doc]
-                    #vis fn #method(&mut self) -> &mut #ty {
-                        &mut self.#name
+                    self.__smart_lock_changes.send(#field_id_name::#a_name);
+                    self.__smart_lock_changes.send(#field_id_name::#b_name);
+                }
+            });
+            quote! {
+                #[doc = #doc]
+                #vis async fn #method(&self) {
+                    let mut __first = self.#first.write().await;
+                    let mut __second = self.#second.write().await;
+                    std::mem::swap(&mut *__first, &mut *__second);
+                    #notify
+                }
+            }
+        })
+        .collect();
+
+    // Bulk dynamic update, only for locks whose fields all share one type.
+    let (apply_updates, extend_impl) = match parsed.homogeneous_type() {
+        Some(elem_ty) => {
+            let field_count = parsed.fields.len();
+            let pending_names: Vec<syn::Ident> = field_names
+                .iter()
+                .map(|n| format_ident!("__pending_{}", n))
+                .collect();
+            let guard_names: Vec<syn::Ident> = field_names
+                .iter()
+                .map(|n| format_ident!("__guard_{}", n))
+                .collect();
+            let acquire_pending = parsed.in_acquisition_order(pending_names.clone());
+            let acquire_guards = parsed.in_acquisition_order(guard_names.clone());
+            let acquire_fields = parsed.in_acquisition_order(field_names.clone());
+            let notify: Vec<Option<proc_macro2::TokenStream>> = field_names
+                .iter()
+                .map(|name| {
+                    parsed
+                        .subscribe
+                        .then(|| quote!(self.__smart_lock_changes.send(#field_id_name::#name);))
+                })
+                .collect();
+
+            let apply_updates = quote! {
+                /// Write-lock and assign every field listed in `updates`.
+                ///
+                /// The listed fields are all locked before any is assigned, in acquisition
+                /// order regardless of the order of `updates`, so the update is deadlock-free
+                /// and observed atomically by readers of those fields. Unlisted fields are
+                /// not locked. If a field id appears more than once, the last value wins.
+                #vis async fn apply_updates(
+                    &self,
+                    updates: impl IntoIterator<Item = (#field_id_name, #elem_ty)>,
+                ) {
+                    let mut pending: [Option<#elem_ty>; #field_count] = std::array::from_fn(|_| None);
+                    for (id, value) in updates {
+                        pending[id.index()] = Some(value);
                     }
+                    let [#(#pending_names),*] = pending;
+                    #(
+                        let #acquire_guards = match #acquire_pending {
+                            Some(value) => Some((self.#acquire_fields.write().await, value)),
+                            None => None,
+                        };
+                    )*
+                    #(
+                        if let Some((mut guard, value)) = #guard_names {
+                            *guard = value;
+                            #notify
+                        }
+                    )*
                 }
-            } else {
-                let get_mut_doc = format!("Get a mutable reference to `{}` without locking. Requires `&mut self`, guaranteeing exclusive access.", name_str);
-                quote! {
-                    #[doc = #get_mut_doc]
-                    #vis fn #method(&mut self) -> &mut #ty {
-                        self.#name.get_mut()
+            };
+
+            let get_mut_places: Vec<proc_macro2::TokenStream> = parsed
+                .fields
+                .iter()
+                .map(|field| parsed.lock_field_get_mut(field, quote!(self)))
+                .collect();
+            let extend_impl = quote! {
+                impl<#impl_prefix> Extend<(#field_id_name, #elem_ty)> for #lock_name #ty_generics #where_clause {
+                    /// Assign each `(id, value)` pair in order. Takes `&mut self`, so no locking is needed.
+                    fn extend<__I: IntoIterator<Item = (#field_id_name, #elem_ty)>>(&mut self, iter: __I) {
+                        for (id, value) in iter {
+                            match id {
+                                #(#field_id_name::#field_names => *#get_mut_places = value,)*
+                            }
+                        }
                     }
                 }
+            };
+
+            (apply_updates, extend_impl)
+        }
+        None => (quote!(), quote!()),
+    };
+
+    // Static assertion that the Lock type is Send + Sync, checked per field and
+    // spanned at the field's type so a failure points at the offending field.
+    // The struct is taken as an argument so its implied bounds (`T: 'b` for a
+    // `&'b T` field) apply.
+    let send_sync_assert = if parsed.no_send_assert {
+        quote!()
+    } else {
+        let assert_name = format_ident!("_assert_{}_send_sync", lock_name);
+        let field_asserts: Vec<proc_macro2::TokenStream> = parsed
+            .fields
+            .iter()
+            .map(|field| {
+                let ty = &field.ty;
+                quote_spanned!(ty.span()=> _require_send_sync::<#ty>();)
+            })
+            .collect();
+        quote! {
+            #[doc(hidden)]
+            #[allow(non_snake_case)]
+            const _: () = {
+                fn #assert_name<#impl_prefix>(_: &#struct_name #ty_generics) #where_clause {
+                    fn _require_send_sync<T: ?Sized + smart_lock::__private::FieldSendSync>() {}
+                    #(#field_asserts)*
+                }
+            };
+        }
+    };
+
+    let lock_name_str_debug = lock_name.to_string();
+
+    let changed_methods: Vec<proc_macro2::TokenStream> = parsed
+        .fields
+        .iter()
+        .filter(|f| !f.no_lock)
+        .map(|field| {
+            let name = &field.name;
+            let method = format_ident!("changed_{}", name);
+            let doc = format!(
+                "Wait until `{}` is next written: resolves when a write guard on it is \
+                 released (dropped or downgraded) after this call, the same events \
+                 [`subscribe()`](Self::subscribe) reports.\n\n\
+                 The wait starts when `{}()` is called, not when the future is first \
+                 polled, so a write in between is not missed. Reads never wake it.",
+                name, method
+            );
+            quote! {
+                #[doc = #doc]
+                #vis fn #method(&self) -> impl ::core::future::Future<Output = ()> + Send + 'static {
+                    self.__smart_lock_changes.changed(#field_id_name::#name)
+                }
             }
         })
         .collect();
 
-    // Static assertion that the Lock type is Send + Sync.
-    let assert_name = format_ident!("_assert_{}_send_sync", lock_name);
+    let (changes_field, subscribe_method) = if parsed.subscribe {
+        (
+            quote! {
+                #[doc(hidden)]
+                __smart_lock_changes: smart_lock::__private::ChangeHub<#field_id_name>,
+            },
+            quote! {
+                /// Subscribe to writes: the receiver yields the id of each field whose
+                /// write guard is released (dropped or downgraded) from now on, in order.
+                ///
+                /// Writes through `swap_*()` and `apply_updates()` are reported too;
+                /// `&mut self` access (`get_mut_*`, `take_*`, `replace_*`, `Extend`) is not.
+                #vis fn subscribe(&self) -> smart_lock::ChangeReceiver<#field_id_name> {
+                    self.__smart_lock_changes.subscribe()
+                }
 
-    let lock_name_str_debug = lock_name.to_string();
+                #(#changed_methods)*
+            },
+        )
+    } else {
+        (quote!(), quote!())
+    };
+
+    let (contention_field, contention_method) = if parsed.metrics {
+        let field_count = parsed.fields.len();
+        let contention_doc = format!(
+            "How many times each field's lock was contended, in declaration order (index \
+             with [`{field_id_name}::index()`]): a builder or `lock_all*()` acquisition that \
+             had to wait, or a `try_` acquisition that failed.\n\n\
+             Per-field accessors and owned guards aren't counted, and `#[no_lock]` fields \
+             stay at 0. Counts only grow; diff two readings for a rate."
+        );
+        (
+            quote! {
+                #[doc(hidden)]
+                __smart_lock_contention: smart_lock::__private::ContentionCounters<#field_count>,
+            },
+            quote! {
+                #[doc = #contention_doc]
+                #vis fn contention_counts(&self) -> [u64; #field_count] {
+                    self.__smart_lock_contention.counts()
+                }
+            },
+        )
+    } else {
+        (quote!(), quote!())
+    };
 
     quote! {
         #[doc = #lock_doc]
-        #vis struct #lock_name #ty_generics #where_clause {
+        #vis struct #lock_name #decl_generics #where_clause {
             #(#lock_fields)*
+            #[doc(hidden)]
+            __smart_lock_instance: smart_lock::__private::InstanceId,
+            #changes_field
+            #contention_field
+        }
+
+        #[doc = #mut_doc]
+        #vis struct #mut_name<'__sl, #impl_prefix> #where_clause {
+            #(#mut_fields)*
         }
 
-        #[doc(hidden)]
-        #[allow(non_snake_case)]
-        const _: () = {
-            fn #assert_name<#impl_prefix>() #where_clause {
-                fn _require_send_sync<T: Send + Sync>() {}
-                _require_send_sync::<#lock_name #ty_generics>();
+        impl<'__sl, #impl_prefix> std::fmt::Debug for #mut_name<'__sl, #bare_prefix> #where_clause {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_struct(#mut_name_str).finish_non_exhaustive()
+            }
+        }
+
+        #send_sync_assert
+
+        impl<#impl_prefix> smart_lock::__private::LockInstance for #lock_name #ty_generics #where_clause {
+            fn lock_instance_id(&self) -> u64 {
+                self.__smart_lock_instance.get()
+            }
+        }
+
+        impl<#impl_prefix> smart_lock::Lockable for #lock_name #ty_generics #where_clause {
+            type Inner = #struct_name #ty_generics;
+            type ReadGuard<'__sl> = #guard_name<'__sl, #bare_prefix #(#all_read),*> where Self: '__sl;
+            type WriteGuard<'__sl> = #guard_name<'__sl, #bare_prefix #(#all_write),*> where Self: '__sl;
+
+            #[inline]
+            fn lock_all(&self) -> impl ::core::future::Future<Output = Self::ReadGuard<'_>> {
+                #lock_name::lock_all(self)
+            }
+
+            #[inline]
+            fn lock_all_mut(&self) -> impl ::core::future::Future<Output = Self::WriteGuard<'_>> {
+                #lock_name::lock_all_mut(self)
             }
-        };
 
+            #[inline]
+            fn into_inner(self) -> Self::Inner {
+                #lock_name::into_inner(self)
+            }
+        }
+
+        #snapshot_impl
+
+        /// Never locks, so it can't block or deadlock, and shows no field values:
+        /// `FooLock { .. }`. Format a guard, or call `debug_blocking()`, to see them.
         impl<#impl_prefix> std::fmt::Debug for #lock_name #ty_generics #where_clause {
             fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
                 f.debug_struct(#lock_name_str_debug).finish_non_exhaustive()
@@ -278,17 +1092,41 @@ pub fn generate(parsed: &ParsedStruct) -> proc_macro2::TokenStream {
 
         impl<#impl_prefix> #lock_name #ty_generics #where_clause {
             /// Create a new lock wrapping each field in an `RwLock`.
-            #[allow(clippy::too_many_arguments)]
+            // `#[boxed]` fields take the declared `Box<T>` and move out of it.
+            #[allow(clippy::too_many_arguments, clippy::boxed_local)]
             #vis fn new(#(#new_params),*) -> Self {
                 Self {
                     #(#new_inits)*
+                    #(#hidden_inits)*
+                    __smart_lock_instance: smart_lock::__private::InstanceId::new(),
+                }
+            }
+
+            /// Like [`new`](Self::new), but each field's value comes from a closure,
+            /// called once in field declaration order while constructing the lock.
+            #[allow(clippy::too_many_arguments)]
+            #vis fn new_with(#(#new_with_params),*) -> Self {
+                Self::new(#(#new_field_names()),*)
+            }
+
+            /// Like [`new`](Self::new), but from `RwLock`s built elsewhere: each locked
+            /// field takes its `RwLock` as is, `#[no_lock]` fields take their value.
+            ///
+            /// Under `#[smart_lock(poison)]` every field starts unpoisoned.
+            #[allow(clippy::too_many_arguments)]
+            #vis fn from_parts(#(#from_parts_params),*) -> Self {
+                Self {
+                    #(#from_parts_inits)*
+                    #(#hidden_inits)*
+                    __smart_lock_instance: smart_lock::__private::InstanceId::new(),
                 }
             }
 
             /// Start building a lock request. Chain `.read_field()`, `.write_field()`,
             /// or `.upgrade_field()` calls, then `.lock().await` to acquire.
             ///
-            /// Locks are acquired in field declaration order to prevent deadlocks.
+            /// Locks are acquired in a fixed order (field declaration order, unless
+            /// overridden with `#[lock(order = N)]`) to prevent deadlocks.
             #vis fn builder(&self) -> #builder_name<'_, #bare_prefix #(#all_unlocked),*> {
                 #builder_name { lock: self, _marker: std::marker::PhantomData }
             }
@@ -299,12 +1137,86 @@ pub fn generate(parsed: &ParsedStruct) -> proc_macro2::TokenStream {
                 #guard_name { lock: self, #(#field_names),* }
             }
 
+            /// Clone every field out under a single [`lock_all()`](Self::lock_all) and
+            /// release the locks before returning, giving an owned copy that is
+            /// consistent across fields.
+            #vis async fn snapshot_all(&self) -> #struct_name #ty_generics #snapshot_all_where {
+                self.lock_all().await.to_owned()
+            }
+
+            /// Feed every field, in declaration order, to `state` under a single
+            /// [`lock_all()`](Self::lock_all), so the hash reflects one consistent state.
+            ///
+            /// `Hash` itself can't be implemented for the lock, since locking is async.
+            /// Locks holding equal states hash equally.
+            #vis async fn hash_snapshot<__H: ::core::hash::Hasher>(&self, state: &mut __H) #hash_snapshot_where {
+                let guard = self.lock_all().await;
+                #(#hash_snapshot_fields)*
+            }
+
+            /// Format every field's value, like a derived `Debug`, under read locks on
+            /// all of them, so the output reflects one consistent state.
+            ///
+            /// The locks are taken with **blocking** reads, waiting out any writer: avoid
+            /// calling it from async code that may contend with writers, and never while
+            /// the current task holds a write guard on this lock. The `Debug` impl never
+            /// locks and shows no values.
+            #vis fn debug_blocking(&self) -> ::std::string::String #debug_blocking_where {
+                struct DebugWith<F>(F);
+                impl<F: Fn(&mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result> ::core::fmt::Debug for DebugWith<F> {
+                    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                        (self.0)(f)
+                    }
+                }
+
+                #(#debug_blocking_locks)*
+                ::std::format!("{:?}", DebugWith(|f: &mut ::core::fmt::Formatter<'_>| {
+                    let mut debug = f.debug_struct(#lock_name_str_debug);
+                    #(#debug_blocking_fields)*
+                    debug.finish()
+                }))
+            }
+
+            #replace_all_method
+
             /// Write-lock all fields. Convenience for `builder().write_a().write_b()...lock().await`.
             #vis async fn lock_all_mut(&self) -> #guard_name<'_, #bare_prefix #(#all_write),*> {
                 #(#lock_all_mut_fields)*
                 #guard_name { lock: self, #(#field_names),* }
             }
 
+            /// Upgradable-read-lock all fields. Convenience for
+            /// `builder().upgrade_a().upgrade_b()...lock().await`: read everything, then
+            /// upgrade only the fields you end up writing.
+            ///
+            /// Only one upgradable lock per field can be held at a time, so this
+            /// excludes other `lock_all_upgradable()` callers while admitting readers.
+            #vis async fn lock_all_upgradable(&self) -> #guard_name<'_, #bare_prefix #(#all_upgrade),*> {
+                #(#lock_all_upgradable_fields)*
+                #guard_name { lock: self, #(#field_names),* }
+            }
+
+            /// Like [`lock_all()`](Self::lock_all), but gives up and returns `None` once
+            /// `give_up` completes first, e.g. `tokio::time::sleep(timeout)` during a
+            /// graceful shutdown. Fields locked before then are released.
+            #vis async fn lock_all_or(&self, give_up: impl ::core::future::Future) -> Option<#guard_name<'_, #bare_prefix #(#all_read),*>> {
+                smart_lock::__private::acquire_or(self.lock_all(), give_up).await
+            }
+
+            /// Like [`lock_all_mut()`](Self::lock_all_mut), but gives up and returns
+            /// `None` once `give_up` completes first, e.g. `tokio::time::sleep(timeout)`
+            /// during a graceful shutdown. Fields locked before then are released.
+            #vis async fn lock_all_mut_or(&self, give_up: impl ::core::future::Future) -> Option<#guard_name<'_, #bare_prefix #(#all_write),*>> {
+                smart_lock::__private::acquire_or(self.lock_all_mut(), give_up).await
+            }
+
+            /// Like [`lock_all_upgradable()`](Self::lock_all_upgradable), but gives up
+            /// and returns `None` once `give_up` completes first. Fields locked before
+            /// then are released.
+            #vis async fn lock_all_upgradable_or(&self, give_up: impl ::core::future::Future) -> Option<#guard_name<'_, #bare_prefix #(#all_upgrade),*>> {
+                smart_lock::__private::acquire_or(self.lock_all_upgradable(), give_up).await
+            }
+
             /// Try to read-lock all fields without blocking.
             /// Returns `None` if any field is currently write-locked.
             #vis fn try_lock_all(&self) -> Option<#guard_name<'_, #bare_prefix #(#all_read),*>> {
@@ -319,6 +1231,40 @@ pub fn generate(parsed: &ParsedStruct) -> proc_macro2::TokenStream {
                 Some(#guard_name { lock: self, #(#field_names),* })
             }
 
+            /// Try to upgradable-read-lock all fields without blocking.
+            /// Returns `None` if any field is write-locked or already upgradable-locked.
+            #vis fn try_lock_all_upgradable(&self) -> Option<#guard_name<'_, #bare_prefix #(#all_upgrade),*>> {
+                #(#try_lock_all_upgradable_fields)*
+                Some(#guard_name { lock: self, #(#field_names),* })
+            }
+
+            /// Alias for [`lock_all()`](Self::lock_all), matching `RwLock::read` for code
+            /// migrating from an `RwLock` around the whole struct.
+            #[inline]
+            #vis async fn read(&self) -> #guard_name<'_, #bare_prefix #(#all_read),*> {
+                self.lock_all().await
+            }
+
+            /// Alias for [`lock_all_mut()`](Self::lock_all_mut), matching `RwLock::write`
+            /// for code migrating from an `RwLock` around the whole struct.
+            #[inline]
+            #vis async fn write(&self) -> #guard_name<'_, #bare_prefix #(#all_write),*> {
+                self.lock_all_mut().await
+            }
+
+            /// Alias for [`try_lock_all()`](Self::try_lock_all), matching `RwLock::try_read`.
+            #[inline]
+            #vis fn try_read(&self) -> Option<#guard_name<'_, #bare_prefix #(#all_read),*>> {
+                self.try_lock_all()
+            }
+
+            /// Alias for [`try_lock_all_mut()`](Self::try_lock_all_mut), matching
+            /// `RwLock::try_write`.
+            #[inline]
+            #vis fn try_write(&self) -> Option<#guard_name<'_, #bare_prefix #(#all_write),*>> {
+                self.try_lock_all_mut()
+            }
+
             #[doc = #into_inner_doc]
             #vis fn into_inner(self) -> #struct_name #ty_generics {
                 #struct_name {
@@ -326,9 +1272,32 @@ pub fn generate(parsed: &ParsedStruct) -> proc_macro2::TokenStream {
                 }
             }
 
+            #[doc = #try_unwrap_inner_doc]
+            #vis fn try_unwrap_inner(
+                arc: ::std::sync::Arc<Self>,
+            ) -> ::core::result::Result<#struct_name #ty_generics, ::std::sync::Arc<Self>> {
+                ::std::sync::Arc::try_unwrap(arc).map(Self::into_inner)
+            }
+
+            #subscribe_method
+            #contention_method
+
+            #apply_updates
+
+            #(#swap_methods)*
+
             #(#per_field_accessors)*
 
             #(#get_mut_accessors)*
+
+            #[doc = #get_all_mut_doc]
+            #vis fn get_all_mut(&mut self) -> #mut_name<'_, #bare_prefix> {
+                #mut_name { #(#mut_places)* }
+            }
+
+            #(#raw_accessors)*
         }
+
+        #extend_impl
     }
 }
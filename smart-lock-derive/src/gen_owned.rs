@@ -0,0 +1,245 @@
+use crate::parse::{deferred_bound, guard_debug_body, ParsedStruct};
+use quote::{format_ident, quote};
+
+/// Owned (`'static`) guards: per-field `*_owned` accessors and `field_arc_*()` for
+/// every owned field, and under `#[smart_lock(owned)]` also `FooLockOwnedGuard` and the
+/// builder's `lock_owned()`. Under `#[smart_lock(actor)]`, also per-field `spawn_actor_*()`.
+pub fn generate(parsed: &ParsedStruct) -> proc_macro2::TokenStream {
+    if !parsed.owned && !parsed.fields.iter().any(|f| f.owned) {
+        return quote!();
+    }
+
+    let vis = &parsed.vis;
+    let lock_name = parsed.lock_name();
+    let builder_name = parsed.builder_name();
+    let owned_guard_name = parsed.owned_guard_name();
+
+    let impl_prefix = parsed.impl_prefix();
+    let bare_prefix = parsed.bare_prefix();
+    let ty_generics = parsed.ty_generics();
+    let where_clause = parsed.where_clause();
+
+    let locked: Vec<_> = parsed.fields.iter().filter(|f| !f.no_lock).collect();
+    let generic_names: Vec<syn::Ident> =
+        (0..locked.len()).map(|i| format_ident!("F{}", i)).collect();
+    let locked_names: Vec<&syn::Ident> = locked.iter().map(|f| &f.name).collect();
+
+    // --- Per-field owned accessors ---
+    let accessors: Vec<proc_macro2::TokenStream> = locked
+        .iter()
+        .filter(|field| parsed.is_owned(field))
+        .map(|field| {
+            let name = &field.name;
+            let ty = &field.ty;
+            let read_method = format_ident!("read_{}_owned", name);
+            let write_method = format_ident!("write_{}_owned", name);
+            let upgrade_method = format_ident!("upgrade_{}_owned", name);
+            let try_read_method = format_ident!("try_read_{}_owned", name);
+            let try_write_method = format_ident!("try_write_{}_owned", name);
+            let try_upgrade_method = format_ident!("try_upgrade_{}_owned", name);
+            let field_arc_method = format_ident!("field_arc_{}", name);
+            let read_doc = format!("Acquire an owned shared read lock on `{}`.", name);
+            let write_doc = format!("Acquire an owned exclusive write lock on `{}`.", name);
+            let upgrade_doc = format!("Acquire an owned upgradable read lock on `{}`.", name);
+            let try_read_doc = format!(
+                "Try to acquire an owned shared read lock on `{}`. Returns `None` if the lock is held exclusively.",
+                name
+            );
+            let try_write_doc = format!(
+                "Try to acquire an owned exclusive write lock on `{}`. Returns `None` if the lock is held.",
+                name
+            );
+            let try_upgrade_doc = format!(
+                "Try to acquire an owned upgradable read lock on `{}`. Returns `None` if another upgradable or write lock is held.",
+                name
+            );
+            let field_arc_doc = format!(
+                "Clone the `Arc` around `{}`'s lock, to share just this field with code that \
+                 doesn't need the rest of the struct. Writes through either handle are seen by both.\n\n\
+                 **Warning:** locking through the returned handle bypasses the builder's \
+                 acquisition order, as with `raw_{}()`. While a clone is alive, `get_mut_{}()`, \
+                 `get_all_mut()`, and `into_inner()` panic, as they do for owned guards.",
+                name, name, name
+            );
+            quote! {
+                #[doc = #field_arc_doc]
+                #vis fn #field_arc_method(&self) -> ::std::sync::Arc<smart_lock::RwLock<#ty>> {
+                    ::std::sync::Arc::clone(&self.#name)
+                }
+
+                #[doc = #read_doc]
+                #vis async fn #read_method(&self) -> smart_lock::RwLockReadGuardArc<#ty> {
+                    self.#name.read_arc().await
+                }
+
+                #[doc = #write_doc]
+                #vis async fn #write_method(&self) -> smart_lock::RwLockWriteGuardArc<#ty> {
+                    self.#name.write_arc().await
+                }
+
+                #[doc = #upgrade_doc]
+                #vis async fn #upgrade_method(&self) -> smart_lock::RwLockUpgradableReadGuardArc<#ty> {
+                    self.#name.upgradable_read_arc().await
+                }
+
+                #[doc = #try_read_doc]
+                #vis fn #try_read_method(&self) -> Option<smart_lock::RwLockReadGuardArc<#ty>> {
+                    self.#name.try_read_arc()
+                }
+
+                #[doc = #try_write_doc]
+                #vis fn #try_write_method(&self) -> Option<smart_lock::RwLockWriteGuardArc<#ty>> {
+                    self.#name.try_write_arc()
+                }
+
+                #[doc = #try_upgrade_doc]
+                #vis fn #try_upgrade_method(&self) -> Option<smart_lock::RwLockUpgradableReadGuardArc<#ty>> {
+                    self.#name.try_upgradable_read_arc()
+                }
+            }
+        })
+        .collect();
+
+    // --- Per-field actors ---
+    let actors: Vec<proc_macro2::TokenStream> = locked
+        .iter()
+        .filter(|_| parsed.actor)
+        .map(|field| {
+            let name = &field.name;
+            let ty = &field.ty;
+            let method = format_ident!("spawn_actor_{}", name);
+            let doc = format!(
+                "Spawn a task applying commands sent through the returned `FieldActor` to `{}`, one at a time, each under its write lock.",
+                name
+            );
+            let bound = deferred_bound(ty, quote!(Send + Sync + 'static));
+            quote! {
+                #[doc = #doc]
+                ///
+                /// The task holds a clone of the field's `Arc` until every handle is dropped.
+                ///
+                /// # Panics
+                ///
+                /// Panics if called outside a Tokio runtime.
+                #vis fn #method(&self) -> smart_lock::FieldActor<#ty> where #bound {
+                    smart_lock::FieldActor::spawn(::std::sync::Arc::clone(&self.#name))
+                }
+            }
+        })
+        .collect();
+
+    let accessor_impl = quote! {
+        impl<#impl_prefix> #lock_name #ty_generics #where_clause {
+            #(#accessors)*
+            #(#actors)*
+        }
+    };
+    // `#[lock(owned)]` fields on their own only get the per-field accessors.
+    if !parsed.owned {
+        return accessor_impl;
+    }
+
+    // --- Owned guard struct ---
+    let lock_name_str = lock_name.to_string();
+    let owned_guard_doc = format!(
+        "Owned guard returned by the builder's `lock_owned()` for [`{lock_name_str}`].\n\n\
+         Each locked field holds a clone of its `Arc<RwLock<T>>`, so the guard doesn't \
+         borrow the lock and is `'static` whenever the field types are. Field access \
+         works as for the borrowed guard. `#[no_lock]` fields are not included; read \
+         them from the lock itself."
+    );
+    // Declared in reverse acquisition order, like the borrowed guard's fields, so
+    // dropping the guard releases the locks in reverse.
+    let mut gi = 0;
+    let owned_fields: Vec<Option<proc_macro2::TokenStream>> = parsed
+        .fields
+        .iter()
+        .map(|field| {
+            if field.no_lock {
+                return None;
+            }
+            let name = &field.name;
+            let ty = &field.ty;
+            let f = &generic_names[gi];
+            gi += 1;
+            Some(quote! { pub #name: smart_lock::OwnedFieldGuard<#ty, #f>, })
+        })
+        .collect();
+    let owned_fields: Vec<proc_macro2::TokenStream> = parsed
+        .in_acquisition_order(owned_fields)
+        .into_iter()
+        .flatten()
+        .rev()
+        .collect();
+    let owned_guard_name_str = owned_guard_name.to_string();
+    let owned_guard_debug = guard_debug_body(&owned_guard_name_str, locked.iter().copied());
+
+    // --- Builder: lock_owned() / try_lock_owned() ---
+    let lock_bounds: Vec<proc_macro2::TokenStream> = generic_names
+        .iter()
+        .map(|f| quote!(#f: smart_lock::LockMode))
+        .collect();
+    let acquisitions = |try_acquire: bool| -> Vec<proc_macro2::TokenStream> {
+        let mut gi = 0;
+        let per_field: Vec<proc_macro2::TokenStream> = parsed
+            .fields
+            .iter()
+            .map(|field| {
+                if field.no_lock {
+                    return quote!();
+                }
+                let name = &field.name;
+                let ty = &field.ty;
+                let f = &generic_names[gi];
+                gi += 1;
+                if try_acquire {
+                    quote! {
+                        let #name = smart_lock::OwnedFieldGuard::<#ty, #f>::try_acquire(&self.lock.#name)?;
+                    }
+                } else {
+                    quote! {
+                        let #name = smart_lock::OwnedFieldGuard::<#ty, #f>::acquire(&self.lock.#name).await;
+                    }
+                }
+            })
+            .collect();
+        parsed.in_acquisition_order(per_field)
+    };
+    let lock_fields = acquisitions(false);
+    let try_lock_fields = acquisitions(true);
+
+    quote! {
+        #accessor_impl
+
+        #[doc = #owned_guard_doc]
+        #[must_use = "guard releases all locks when dropped"]
+        #vis struct #owned_guard_name<#impl_prefix #(#generic_names),*> #where_clause {
+            #(#owned_fields)*
+            #[doc(hidden)]
+            _lock: ::core::marker::PhantomData<fn() -> #lock_name #ty_generics>,
+        }
+
+        impl<#impl_prefix #(#generic_names),*> std::fmt::Debug for #owned_guard_name<#bare_prefix #(#generic_names),*> #where_clause {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                #owned_guard_debug
+            }
+        }
+
+        impl<'__sl, #impl_prefix #(#lock_bounds),*> #builder_name<'__sl, #bare_prefix #(#generic_names),*> #where_clause {
+            /// Like [`lock()`](Self::lock), but returns an owned guard that doesn't
+            /// borrow the lock, for moving into spawned tasks.
+            ///
+            /// Locks are acquired in the same order as `lock()`.
+            #vis async fn lock_owned(self) -> #owned_guard_name<#bare_prefix #(#generic_names),*> {
+                #(#lock_fields)*
+                #owned_guard_name { #(#locked_names,)* _lock: ::core::marker::PhantomData }
+            }
+
+            /// Like [`try_lock()`](Self::try_lock), but returns an owned guard.
+            #vis fn try_lock_owned(self) -> Option<#owned_guard_name<#bare_prefix #(#generic_names),*>> {
+                #(#try_lock_fields)*
+                Some(#owned_guard_name { #(#locked_names,)* _lock: ::core::marker::PhantomData })
+            }
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use crate::parse::{deferred_bound, ParsedStruct};
+use quote::quote;
+use syn::ext::IdentExt;
+
+pub fn generate(parsed: &ParsedStruct) -> proc_macro2::TokenStream {
+    let vis = &parsed.vis;
+    let struct_name = &parsed.name;
+    let lock_name = parsed.lock_name();
+    let snapshot_name = parsed.snapshot_name();
+
+    let impl_prefix = parsed.impl_prefix();
+    let bare_prefix = parsed.bare_prefix();
+    let ty_generics = parsed.ty_generics();
+    let where_clause = parsed.where_clause();
+
+    let struct_name_str = struct_name.to_string();
+    let field_count = parsed.fields.len();
+
+    let ser_bounds: Vec<proc_macro2::TokenStream> = parsed
+        .fields
+        .iter()
+        .map(|field| deferred_bound(&field.ty, quote!(smart_lock::__private::serde::Serialize)))
+        .collect();
+    let ser_where = parsed.where_clause_with(&ser_bounds);
+
+    let ser_fields: Vec<proc_macro2::TokenStream> = parsed
+        .fields
+        .iter()
+        .map(|field| {
+            let name = &field.name;
+            let name_str = name.unraw().to_string();
+            if field.no_lock {
+                quote! { state.serialize_field(#name_str, &self.#name)?; }
+            } else {
+                quote! { state.serialize_field(#name_str, &*self.#name.read_blocking())?; }
+            }
+        })
+        .collect();
+
+    let de_bounds = vec![deferred_bound(
+        quote!(#struct_name #ty_generics),
+        quote!(smart_lock::__private::serde::Deserialize<'__de>),
+    )];
+    let de_where = parsed.where_clause_with(&de_bounds);
+
+    // --- Consistent snapshot ---
+    let snapshot_doc = format!(
+        "Point-in-time copy of a [`{lock_name}`], produced by \
+         [`serialize_snapshot()`]({lock_name}::serialize_snapshot).\n\n\
+         Locked fields are owned clones taken under a single `lock_all()`, so they are \
+         mutually consistent. `#[no_lock]` fields are borrowed and read when serialized."
+    );
+
+    let snapshot_fields: Vec<proc_macro2::TokenStream> = parsed
+        .fields
+        .iter()
+        .map(|field| {
+            let name = &field.name;
+            let ty = &field.ty;
+            let field_vis = &field.vis;
+            if field.no_lock {
+                quote! { #field_vis #name: &'__sl #ty, }
+            } else {
+                quote! { #field_vis #name: #ty, }
+            }
+        })
+        .collect();
+
+    let snapshot_inits: Vec<proc_macro2::TokenStream> = parsed
+        .fields
+        .iter()
+        .map(|field| {
+            let name = &field.name;
+            if field.no_lock {
+                quote! { #name: guard.#name, }
+            } else {
+                quote! { #name: ::core::clone::Clone::clone(&*guard.#name), }
+            }
+        })
+        .collect();
+
+    let clone_bounds: Vec<proc_macro2::TokenStream> = parsed
+        .fields
+        .iter()
+        .filter(|f| !f.no_lock)
+        .map(|field| deferred_bound(&field.ty, quote!(::core::clone::Clone)))
+        .collect();
+    let clone_where = if clone_bounds.is_empty() {
+        quote!()
+    } else {
+        quote!(where #(#clone_bounds),*)
+    };
+
+    let snapshot_ser_fields: Vec<proc_macro2::TokenStream> = parsed
+        .fields
+        .iter()
+        .map(|field| {
+            let name = &field.name;
+            let name_str = name.unraw().to_string();
+            quote! { state.serialize_field(#name_str, &self.#name)?; }
+        })
+        .collect();
+
+    quote! {
+        #[doc = #snapshot_doc]
+        #vis struct #snapshot_name<'__sl, #impl_prefix> #where_clause {
+            #(#snapshot_fields)*
+            #[doc(hidden)]
+            _lock: ::core::marker::PhantomData<&'__sl #lock_name #ty_generics>,
+        }
+
+        impl<#impl_prefix> #lock_name #ty_generics #where_clause {
+            /// Read-lock every field at once, clone the values, release the locks,
+            /// and return a serializable snapshot.
+            ///
+            /// Unlike serializing the lock directly (which locks one field at a time),
+            /// the snapshot is consistent across fields: no writer can run between
+            /// reading one field and the next.
+            #vis async fn serialize_snapshot(&self) -> #snapshot_name<'_, #bare_prefix> #clone_where {
+                let guard = self.lock_all().await;
+                #snapshot_name {
+                    #(#snapshot_inits)*
+                    _lock: ::core::marker::PhantomData,
+                }
+            }
+        }
+
+        impl<'__sl, #impl_prefix> smart_lock::__private::serde::Serialize for #snapshot_name<'__sl, #bare_prefix> #ser_where {
+            fn serialize<__S>(&self, serializer: __S) -> ::core::result::Result<__S::Ok, __S::Error>
+            where
+                __S: smart_lock::__private::serde::Serializer,
+            {
+                use smart_lock::__private::serde::ser::SerializeStruct;
+                let mut state = serializer.serialize_struct(#struct_name_str, #field_count)?;
+                #(#snapshot_ser_fields)*
+                state.end()
+            }
+        }
+
+        /// Serializes each field under a short-lived blocking read lock, in declaration order.
+        ///
+        /// Fields are locked one at a time, so the output is not a point-in-time snapshot
+        /// across fields. Because the reads block the current thread, do not serialize a
+        /// lock while this thread holds a write guard on it, and avoid calling this from
+        /// a single-threaded async runtime while other tasks may hold write guards.
+        impl<#impl_prefix> smart_lock::__private::serde::Serialize for #lock_name #ty_generics #ser_where {
+            fn serialize<__S>(&self, serializer: __S) -> ::core::result::Result<__S::Ok, __S::Error>
+            where
+                __S: smart_lock::__private::serde::Serializer,
+            {
+                use smart_lock::__private::serde::ser::SerializeStruct;
+                let mut state = serializer.serialize_struct(#struct_name_str, #field_count)?;
+                #(#ser_fields)*
+                state.end()
+            }
+        }
+
+        /// Deserializes the original struct and wraps each field in a fresh `RwLock`.
+        impl<'__de, #impl_prefix> smart_lock::__private::serde::Deserialize<'__de> for #lock_name #ty_generics #de_where {
+            fn deserialize<__D>(deserializer: __D) -> ::core::result::Result<Self, __D::Error>
+            where
+                __D: smart_lock::__private::serde::Deserializer<'__de>,
+            {
+                <#struct_name #ty_generics as smart_lock::__private::serde::Deserialize<'__de>>::deserialize(deserializer)
+                    .map(Self::from)
+            }
+        }
+    }
+}
@@ -1,11 +1,21 @@
 use proc_macro::TokenStream;
 use syn::{parse_macro_input, ItemStruct};
 
+#[cfg(feature = "arbitrary")]
+mod gen_arbitrary;
 mod gen_builder;
+mod gen_field_ids;
 mod gen_from;
+mod gen_groups;
 mod gen_guard;
+mod gen_impls;
 mod gen_lock;
+mod gen_owned;
+#[cfg(feature = "serde")]
+mod gen_serde;
+mod lock_spec;
 mod parse;
+mod upgrade_subset;
 
 #[proc_macro_attribute]
 pub fn smart_lock(attr: TokenStream, item: TokenStream) -> TokenStream {
@@ -18,24 +28,61 @@ pub fn smart_lock(attr: TokenStream, item: TokenStream) -> TokenStream {
     };
 
     let mut clean_struct = item_struct.clone();
+    clean_struct
+        .attrs
+        .retain(|a| !a.path().is_ident("preset") && !a.path().is_ident("swap"));
     if let syn::Fields::Named(ref mut fields) = clean_struct.fields {
         for field in &mut fields.named {
-            field.attrs.retain(|a| !a.path().is_ident("no_lock"));
+            field.attrs.retain(|a| {
+                !a.path().is_ident("no_lock")
+                    && !a.path().is_ident("lock")
+                    && !a.path().is_ident("boxed")
+            });
         }
     }
     let original = &clean_struct;
     let lock = gen_lock::generate(&parsed);
     let guard = gen_guard::generate(&parsed);
     let builder = gen_builder::generate(&parsed);
+    let groups = gen_groups::generate(&parsed);
     let from = gen_from::generate(&parsed);
+    let impls = gen_impls::generate(&parsed);
+    let field_ids = gen_field_ids::generate(&parsed);
+    let owned = gen_owned::generate(&parsed);
+    #[cfg(feature = "serde")]
+    let serde = gen_serde::generate(&parsed);
+    #[cfg(not(feature = "serde"))]
+    let serde = proc_macro2::TokenStream::new();
+    #[cfg(feature = "arbitrary")]
+    let arbitrary = gen_arbitrary::generate(&parsed);
+    #[cfg(not(feature = "arbitrary"))]
+    let arbitrary = proc_macro2::TokenStream::new();
 
     let expanded = quote::quote! {
         #original
         #lock
         #guard
         #builder
+        #groups
         #from
+        #impls
+        #field_ids
+        #owned
+        #serde
+        #arbitrary
     };
 
     expanded.into()
 }
+
+#[proc_macro]
+pub fn try_lock_spec(input: TokenStream) -> TokenStream {
+    let spec = parse_macro_input!(input as lock_spec::LockSpec);
+    lock_spec::generate(&spec).into()
+}
+
+#[proc_macro]
+pub fn upgrade_subset(input: TokenStream) -> TokenStream {
+    let spec = parse_macro_input!(input as upgrade_subset::UpgradeSubset);
+    upgrade_subset::generate(&spec).into()
+}
@@ -0,0 +1,64 @@
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{bracketed, Expr, Ident, Token};
+
+/// `try_lock_spec!(lock, write: [a, b], read: [c], upgrade: [d])`.
+pub struct LockSpec {
+    lock: Expr,
+    /// `(mode, fields)` per group, in the order written.
+    groups: Vec<(Ident, Vec<Ident>)>,
+}
+
+impl Parse for LockSpec {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let lock: Expr = input.parse()?;
+        let mut groups = Vec::new();
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+            let mode = Ident::parse_any(input)?;
+            if !["read", "write", "upgrade"].contains(&mode.to_string().as_str()) {
+                return Err(syn::Error::new_spanned(
+                    &mode,
+                    "expected `read`, `write`, or `upgrade`. \
+                     Usage: try_lock_spec!(lock, write: [a, b], read: [c])",
+                ));
+            }
+            input.parse::<Token![:]>()?;
+            let content;
+            bracketed!(content in input);
+            let fields: Vec<Ident> = Punctuated::<Ident, Token![,]>::parse_terminated(&content)?
+                .into_iter()
+                .collect();
+            for (i, field) in fields.iter().enumerate() {
+                let mut earlier = groups.iter().flat_map(|(_, fs)| fs).chain(&fields[..i]);
+                if earlier.any(|f| f == field) {
+                    return Err(syn::Error::new_spanned(
+                        field,
+                        format!("`{}` is listed more than once", field.unraw()),
+                    ));
+                }
+            }
+            groups.push((mode, fields));
+        }
+        Ok(Self { lock, groups })
+    }
+}
+
+/// The builder chain for the spec, ending in `try_lock()`.
+pub fn generate(spec: &LockSpec) -> proc_macro2::TokenStream {
+    let lock = &spec.lock;
+    let steps = spec.groups.iter().flat_map(|(mode, fields)| {
+        fields.iter().map(move |field| {
+            let method = format_ident!("{}_{}", mode, field, span = field.span());
+            quote!(.#method())
+        })
+    });
+    quote! {
+        (#lock).builder() #(#steps)* .try_lock()
+    }
+}
@@ -1,23 +1,156 @@
 use quote::quote;
-use syn::{Attribute, Fields, Generics, Ident, ItemStruct, Type, Visibility};
+use std::collections::HashMap;
+use syn::ext::IdentExt;
+use syn::{Attribute, Fields, Generics, Ident, ItemStruct, LitStr, Type, Visibility};
 
 pub struct ParsedField {
     pub name: Ident,
+    /// The locked type: `T` for a `#[boxed]` field declared as `Box<T>`.
     pub ty: Type,
-    #[allow(dead_code)]
+    #[cfg_attr(not(feature = "serde"), allow(dead_code))]
     pub vis: Visibility,
     pub attrs: Vec<Attribute>,
     pub no_lock: bool,
+    /// Explicit acquisition order key from `#[lock(order = N)]`.
+    pub order: Option<i64>,
+    /// Group names from `#[lock(group = "name")]`, as written.
+    pub groups: Vec<LitStr>,
+    /// `#[boxed]`: declared as `Box<ty>`, stored as `Box<RwLock<ty>>`.
+    pub boxed: bool,
+    /// `#[lock(skip_new)]`: left out of `new()` / `new_with()` and initialized with
+    /// `Default::default()`.
+    pub skip_new: bool,
+    /// `#[lock(owned)]`: stored as `Arc<RwLock<ty>>` with per-field `*_owned`
+    /// accessors, as every locked field is under `#[smart_lock(owned)]`.
+    pub owned: bool,
+}
+
+impl ParsedField {
+    /// The field's type as declared on the original struct.
+    pub fn declared_ty(&self) -> proc_macro2::TokenStream {
+        let ty = &self.ty;
+        if self.boxed {
+            quote!(::std::boxed::Box<#ty>)
+        } else {
+            quote!(#ty)
+        }
+    }
+}
+
+/// Lock mode selected for a field by a preset.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum PresetMode {
+    Read,
+    Write,
+    Upgrade,
+}
+
+/// A named builder preset from `#[preset(name = "write_a, read_b")]`.
+pub struct Preset {
+    pub name: Ident,
+    pub spec: LitStr,
+    /// `(field index, mode)` for each listed field.
+    pub modes: Vec<(usize, PresetMode)>,
+}
+
+/// A named field group from `#[lock(group = "name")]` on its fields.
+pub struct Group {
+    pub name: Ident,
+    /// Indices of the group's fields, in declaration order.
+    pub fields: Vec<usize>,
 }
 
 pub struct ParsedStruct {
+    /// Visibility of the generated items: the struct's own, or the one given by
+    /// `#[smart_lock(vis = "...")]`.
     pub vis: Visibility,
     pub name: Ident,
     pub generics: Generics,
     pub fields: Vec<ParsedField>,
+    pub presets: Vec<Preset>,
+    /// Field groups, in order of first appearance, each generating `builder_<name>()`.
+    pub groups: Vec<Group>,
+    /// Field index pairs from `#[swap(a, b)]`, each generating `swap_a_b()`.
+    pub swaps: Vec<(usize, usize)>,
+    /// `#[smart_lock(poison)]`: track a poison flag per locked field.
+    pub poison: bool,
+    /// `#[smart_lock(owned)]`: store each locked field as `Arc<RwLock<T>>` so
+    /// `'static` owned guards can be handed out.
+    pub owned: bool,
+    /// `#[smart_lock(subscribe)]`: broadcast the id of each written field.
+    pub subscribe: bool,
+    /// `#[smart_lock(actor)]`: generate `spawn_actor_*()`; implies `owned`.
+    pub actor: bool,
+    /// `#[smart_lock(no_send_assert)]`: skip the compile-time check that every
+    /// field is `Send + Sync`.
+    pub no_send_assert: bool,
+    /// `#[smart_lock(metrics)]`: count contended acquisitions per field.
+    pub metrics: bool,
+    /// `#[smart_lock(suffix = "...")]`: appended to the struct name to name the
+    /// lock type and its builder and guards (`Lock` by default). Names built from
+    /// the struct alone, like `FooFieldId`, don't use it.
+    pub suffix: String,
 }
 
 impl ParsedStruct {
+    /// `FooLock`, or `Foo{suffix}` under `#[smart_lock(suffix = "...")]`.
+    pub fn lock_name(&self) -> Ident {
+        quote::format_ident!("{}{}", self.name, self.suffix)
+    }
+
+    /// `FooLockBuilder`.
+    pub fn builder_name(&self) -> Ident {
+        quote::format_ident!("{}Builder", self.lock_name())
+    }
+
+    /// `foo_field`: the module of per-field marker types taken by the builder's
+    /// `with_mode()`. Like the other names below, it comes from the struct name and
+    /// ignores `suffix`.
+    pub fn field_module_name(&self) -> Ident {
+        quote::format_ident!("{}_field", snake_case(&self.name.unraw().to_string()))
+    }
+
+    /// `FooFieldId`.
+    pub fn field_id_name(&self) -> Ident {
+        quote::format_ident!("{}FieldId", self.name)
+    }
+
+    /// `FooReadGuard`: the all-read guard alias.
+    pub fn read_guard_alias(&self) -> Ident {
+        quote::format_ident!("{}ReadGuard", self.name)
+    }
+
+    /// `FooWriteGuard`: the all-write guard alias.
+    pub fn write_guard_alias(&self) -> Ident {
+        quote::format_ident!("{}WriteGuard", self.name)
+    }
+
+    /// `FooView`.
+    pub fn view_name(&self) -> Ident {
+        quote::format_ident!("{}View", self.name)
+    }
+
+    /// `FooMut`.
+    pub fn mut_name(&self) -> Ident {
+        quote::format_ident!("{}Mut", self.name)
+    }
+
+    /// `FooSnapshot`.
+    #[cfg_attr(not(feature = "serde"), allow(dead_code))]
+    pub fn snapshot_name(&self) -> Ident {
+        quote::format_ident!("{}Snapshot", self.name)
+    }
+
+    /// `FooLockGuard`.
+    pub fn guard_name(&self) -> Ident {
+        quote::format_ident!("{}Guard", self.lock_name())
+    }
+
+    /// `FooLockOwnedGuard`.
+    pub fn owned_guard_name(&self) -> Ident {
+        quote::format_ident!("{}OwnedGuard", self.lock_name())
+    }
+
     /// Returns bare generic params (no bounds) for use in type applications.
     /// e.g. for `<T: Clone, U: Send>` returns tokens for `T, U`
     pub fn bare_generic_params(&self) -> Vec<proc_macro2::TokenStream> {
@@ -42,12 +175,109 @@ impl ParsedStruct {
     }
 
     /// Returns full generic params (with bounds) for use in impl parameter lists.
+    /// Defaults (`T = String`) are stripped, since impl headers and the other
+    /// generated structs' leading params can't have them.
     pub fn impl_generic_params(&self) -> Vec<proc_macro2::TokenStream> {
-        self.generics.params.iter().map(|p| quote!(#p)).collect()
+        self.generics
+            .params
+            .iter()
+            .map(|p| match p {
+                syn::GenericParam::Type(tp) => {
+                    let mut tp = tp.clone();
+                    tp.eq_token = None;
+                    tp.default = None;
+                    quote!(#tp)
+                }
+                syn::GenericParam::Const(cp) => {
+                    let mut cp = cp.clone();
+                    cp.eq_token = None;
+                    cp.default = None;
+                    quote!(#cp)
+                }
+                syn::GenericParam::Lifetime(_) => quote!(#p),
+            })
+            .collect()
+    }
+
+    /// Generic params for declaring `FooLock`, as on the original struct
+    /// (bounds and defaults included): `<T: Clone = String>` or empty.
+    pub fn decl_generics(&self) -> proc_macro2::TokenStream {
+        let params = &self.generics.params;
+        if params.is_empty() {
+            quote!()
+        } else {
+            quote!(<#params>)
+        }
+    }
+
+    /// Struct generic params for declaring a type alias, with trailing comma, or
+    /// empty. Bounds are dropped (aliases don't enforce them and rustc warns
+    /// about them); defaults and const parameter types are kept.
+    pub fn alias_prefix(&self) -> proc_macro2::TokenStream {
+        let params: Vec<proc_macro2::TokenStream> = self
+            .generics
+            .params
+            .iter()
+            .map(|p| match p {
+                syn::GenericParam::Type(tp) => {
+                    let ident = &tp.ident;
+                    match &tp.default {
+                        Some(default) => quote!(#ident = #default),
+                        None => quote!(#ident),
+                    }
+                }
+                syn::GenericParam::Lifetime(lp) => {
+                    let lt = &lp.lifetime;
+                    quote!(#lt)
+                }
+                syn::GenericParam::Const(cp) => {
+                    let mut cp = cp.clone();
+                    cp.attrs.clear();
+                    quote!(#cp)
+                }
+            })
+            .collect();
+        if params.is_empty() {
+            quote!()
+        } else {
+            quote!(#(#params),*,)
+        }
+    }
+
+    /// Like [`alias_prefix`](Self::alias_prefix), but without defaults, for
+    /// aliases that declare more parameters after the struct's.
+    pub fn alias_prefix_without_defaults(&self) -> proc_macro2::TokenStream {
+        let params: Vec<proc_macro2::TokenStream> = self
+            .generics
+            .params
+            .iter()
+            .map(|p| match p {
+                syn::GenericParam::Type(tp) => {
+                    let ident = &tp.ident;
+                    quote!(#ident)
+                }
+                syn::GenericParam::Lifetime(lp) => {
+                    let lt = &lp.lifetime;
+                    quote!(#lt)
+                }
+                syn::GenericParam::Const(cp) => {
+                    let mut cp = cp.clone();
+                    cp.attrs.clear();
+                    cp.eq_token = None;
+                    cp.default = None;
+                    quote!(#cp)
+                }
+            })
+            .collect();
+        if params.is_empty() {
+            quote!()
+        } else {
+            quote!(#(#params),*,)
+        }
     }
 
     /// Bare struct generic params with trailing comma, or empty.
-    /// Use in type applications: `<'a, #bare_prefix #(#field_generics),*>`
+    /// Use in type applications: `<'__sl, #bare_prefix #(#field_generics),*>`
     pub fn bare_prefix(&self) -> proc_macro2::TokenStream {
         let bare = self.bare_generic_params();
         if bare.is_empty() {
@@ -58,7 +288,7 @@ impl ParsedStruct {
     }
 
     /// Full struct generic params (with bounds) with trailing comma, or empty.
-    /// Use in impl headers: `impl<'a, #impl_prefix #(#field_generics),*>`
+    /// Use in impl headers: `impl<'__sl, #impl_prefix #(#field_generics),*>`
     pub fn impl_prefix(&self) -> proc_macro2::TokenStream {
         let params = self.impl_generic_params();
         if params.is_empty() {
@@ -82,16 +312,465 @@ impl ParsedStruct {
             quote!(<#(#bare),*>)
         }
     }
+
+    /// The shared field type if every field is locked and all fields have the
+    /// same type (compared token-for-token), or `None`.
+    pub fn homogeneous_type(&self) -> Option<&Type> {
+        let first = self.fields.first()?;
+        let first_ty = &first.ty;
+        let first_str = quote!(#first_ty).to_string();
+        self.fields
+            .iter()
+            .all(|f| {
+                let ty = &f.ty;
+                !f.no_lock && quote!(#ty).to_string() == first_str
+            })
+            .then_some(&first.ty)
+    }
+
+    /// Field indices in lock acquisition order: ascending `#[lock(order = N)]`
+    /// key (fields without one count as `0`), ties broken by declaration order.
+    pub fn acquisition_order(&self) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.fields.len()).collect();
+        order.sort_by_key(|&i| self.fields[i].order.unwrap_or(0));
+        order
+    }
+
+    /// Reorder per-field items (given in declaration order) into acquisition order.
+    pub fn in_acquisition_order<T>(&self, items: Vec<T>) -> Vec<T> {
+        let mut slots: Vec<Option<T>> = items.into_iter().map(Some).collect();
+        self.acquisition_order()
+            .into_iter()
+            .map(|i| slots[i].take().unwrap())
+            .collect()
+    }
+
+    /// Whether any field overrides declaration order with `#[lock(order = N)]`.
+    pub fn has_custom_order(&self) -> bool {
+        self.fields.iter().any(|f| f.order.is_some())
+    }
+
+    /// Name of the hidden `PoisonFlag` stored next to a locked field's `RwLock`
+    /// under `#[smart_lock(poison)]`.
+    pub fn poison_flag_name(field: &ParsedField) -> Ident {
+        quote::format_ident!("__poison_{}", field.name)
+    }
+
+    /// Method-call tail acquiring `receiver.field` as a `FieldGuard`:
+    /// `acquire(&lock)` / `try_acquire(&lock)`, or the `_with` variants passing
+    /// the field's [`hook`](Self::hook) under `#[smart_lock(poison)]` or
+    /// `#[smart_lock(subscribe)]`. Under `#[smart_lock(metrics)]`, the
+    /// `_with_counted` variants also pass the field's contention counter. With the
+    /// `tracing` feature, the `_traced` variant passing the field's `FieldSite`.
+    pub fn acquire_call(
+        &self,
+        field: &ParsedField,
+        receiver: proc_macro2::TokenStream,
+        try_acquire: bool,
+    ) -> proc_macro2::TokenStream {
+        let name = &field.name;
+        let mut hook = self.hook(field, receiver.clone());
+        // Counting goes through the `*_with_counted` variants, with `NoHook` if needed.
+        if self.metrics && hook.is_none() {
+            hook = Some(quote!(smart_lock::NoHook));
+        }
+        let method = match (hook.is_some(), try_acquire) {
+            (false, false) => "acquire",
+            (false, true) => "try_acquire",
+            (true, false) => "acquire_with",
+            (true, true) => "try_acquire_with",
+        };
+        let mut args = vec![quote!(&#receiver.#name)];
+        args.extend(hook);
+        let method = if self.metrics {
+            let index = self
+                .fields
+                .iter()
+                .position(|f| f.name == field.name)
+                .unwrap();
+            args.push(quote!(#receiver.__smart_lock_contention.field(#index)));
+            format!("{method}_counted")
+        } else {
+            method.to_string()
+        };
+        #[cfg(feature = "tracing")]
+        let method = {
+            let lock = self.lock_name().to_string();
+            let field = name.unraw().to_string();
+            args.push(quote!(&smart_lock::__private::FieldSite { lock: #lock, field: #field }));
+            format!("{method}_traced")
+        };
+        let method = quote::format_ident!("{}", method);
+        quote!(#method(#(#args),*))
+    }
+
+    /// The `GuardHook` value for a guard on `receiver.field`: the field's
+    /// `Poison` under `#[smart_lock(poison)]`, a `Notify` carrying its id under
+    /// `#[smart_lock(subscribe)]`, a pair of both, or `None`.
+    pub fn hook(
+        &self,
+        field: &ParsedField,
+        receiver: proc_macro2::TokenStream,
+    ) -> Option<proc_macro2::TokenStream> {
+        let poison = self.poison.then(|| {
+            let flag = Self::poison_flag_name(field);
+            quote!(smart_lock::Poison::new(&#receiver.#flag))
+        });
+        let notify = self.subscribe.then(|| {
+            let field_id = self.field_id_name();
+            let name = &field.name;
+            quote!(smart_lock::Notify::new(&#receiver.__smart_lock_changes, #field_id::#name))
+        });
+        match (poison, notify) {
+            (Some(poison), Some(notify)) => Some(quote!((#poison, #notify))),
+            (poison, notify) => poison.or(notify),
+        }
+    }
+
+    /// The `GuardHook` type matching [`hook`](Self::hook), with `lifetime`.
+    pub fn hook_ty(&self, lifetime: proc_macro2::TokenStream) -> Option<proc_macro2::TokenStream> {
+        let poison = self.poison.then(|| quote!(smart_lock::Poison<#lifetime>));
+        let notify = self.subscribe.then(|| {
+            let field_id = self.field_id_name();
+            quote!(smart_lock::Notify<#lifetime, #field_id>)
+        });
+        match (poison, notify) {
+            (Some(poison), Some(notify)) => Some(quote!((#poison, #notify))),
+            (poison, notify) => poison.or(notify),
+        }
+    }
+
+    /// Trailing `FieldGuard` type argument selecting the guard hook:
+    /// `, <hook_ty>` under `#[smart_lock(poison)]` or `#[smart_lock(subscribe)]`,
+    /// otherwise empty (the `NoHook` default).
+    pub fn hook_arg(&self, lifetime: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        match self.hook_ty(lifetime) {
+            Some(ty) => quote!(, #ty),
+            None => quote!(),
+        }
+    }
+
+    /// Whether a locked field is stored as `Arc<RwLock<T>>`: under
+    /// `#[smart_lock(owned)]`, or marked `#[lock(owned)]`.
+    pub fn is_owned(&self, field: &ParsedField) -> bool {
+        self.owned || field.owned
+    }
+
+    /// Storage type of a locked field in `FooLock`: `RwLock<T>`, `Box<RwLock<T>>`
+    /// for a `#[boxed]` field, or `Arc<RwLock<T>>` for an owned one.
+    pub fn lock_field_ty(&self, field: &ParsedField) -> proc_macro2::TokenStream {
+        let ty = &field.ty;
+        if self.is_owned(field) {
+            quote!(::std::sync::Arc<smart_lock::RwLock<#ty>>)
+        } else if field.boxed {
+            quote!(::std::boxed::Box<smart_lock::RwLock<#ty>>)
+        } else {
+            quote!(smart_lock::RwLock<#ty>)
+        }
+    }
+
+    /// Wrap `value`, of the field's declared type, in a locked field's storage type.
+    pub fn lock_field_init(
+        &self,
+        field: &ParsedField,
+        value: proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        let value = if field.boxed { quote!(*#value) } else { value };
+        self.lock_field_from_lock(field, quote!(smart_lock::RwLock::new(#value)))
+    }
+
+    /// Wrap `lock`, an `RwLock<T>`, in a locked field's storage type.
+    pub fn lock_field_from_lock(
+        &self,
+        field: &ParsedField,
+        lock: proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        if self.is_owned(field) {
+            quote!(::std::sync::Arc::new(#lock))
+        } else if field.boxed {
+            quote!(::std::boxed::Box::new(#lock))
+        } else {
+            lock
+        }
+    }
+
+    /// `&mut T` for a locked field through `&mut receiver`. For an owned field
+    /// this panics while an owned guard on it is alive.
+    pub fn lock_field_get_mut(
+        &self,
+        field: &ParsedField,
+        receiver: proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        let name = &field.name;
+        if self.is_owned(field) {
+            let msg = format!("`{}` is still locked by an owned guard", name.unraw());
+            quote!(::std::sync::Arc::get_mut(&mut #receiver.#name)
+                .unwrap_or_else(|| panic!(#msg))
+                .get_mut())
+        } else {
+            quote!(#receiver.#name.get_mut())
+        }
+    }
+
+    /// The value of a locked field, of its declared type, consuming `receiver`. For an
+    /// owned field this panics while an owned guard on it is alive.
+    pub fn lock_field_into_inner(
+        &self,
+        field: &ParsedField,
+        receiver: proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        let name = &field.name;
+        let value = if self.is_owned(field) {
+            let msg = format!("`{}` is still locked by an owned guard", name.unraw());
+            quote!(::std::sync::Arc::try_unwrap(#receiver.#name)
+                .unwrap_or_else(|_| panic!(#msg))
+                .into_inner())
+        } else if field.boxed {
+            quote!((*#receiver.#name).into_inner())
+        } else {
+            quote!(#receiver.#name.into_inner())
+        };
+        if field.boxed {
+            quote!(::std::boxed::Box::new(#value))
+        } else {
+            value
+        }
+    }
+
+    /// Initializers for the hidden poison flags under `#[smart_lock(poison)]` and
+    /// the change hub under `#[smart_lock(subscribe)]`.
+    pub fn hidden_field_inits(&self) -> Vec<proc_macro2::TokenStream> {
+        let mut inits = Vec::new();
+        if self.poison {
+            inits.extend(self.fields.iter().filter(|f| !f.no_lock).map(|f| {
+                let flag = Self::poison_flag_name(f);
+                quote!(#flag: smart_lock::PoisonFlag::new(),)
+            }));
+        }
+        if self.subscribe {
+            inits.push(quote!(__smart_lock_changes: smart_lock::__private::ChangeHub::new(),));
+        }
+        if self.metrics {
+            inits.push(
+                quote!(__smart_lock_contention: smart_lock::__private::ContentionCounters::new(),),
+            );
+        }
+        inits
+    }
+
+    /// The struct's where clause extended with `extra` predicates.
+    /// Always emits a `where` keyword when `extra` is non-empty.
+    pub fn where_clause_with(
+        &self,
+        extra: &[proc_macro2::TokenStream],
+    ) -> proc_macro2::TokenStream {
+        let existing: Vec<&syn::WherePredicate> = self
+            .where_clause()
+            .map(|w| w.predicates.iter().collect())
+            .unwrap_or_default();
+        if existing.is_empty() && extra.is_empty() {
+            quote!()
+        } else {
+            quote!(where #(#existing,)* #(#extra),*)
+        }
+    }
+}
+
+/// `T` from a `Box<T>` type (the last path segment must be `Box` with a single
+/// type argument).
+fn boxed_inner(ty: &Type) -> Option<Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != "Box" {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.iter().collect::<Vec<_>>()[..] {
+        [syn::GenericArgument::Type(inner)] => Some(inner.clone()),
+        _ => None,
+    }
+}
+
+/// `RwLock` or `Mutex` if `ty` is a lock, directly or behind an `Arc`, `Rc` or `Box`
+/// (matched by the last path segment, so any crate's locks count).
+fn lock_type_name(ty: &Type) -> Option<&'static str> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident == "RwLock" {
+        return Some("RwLock");
+    }
+    if segment.ident == "Mutex" {
+        return Some("Mutex");
+    }
+    if !["Arc", "Rc", "Box"].iter().any(|p| segment.ident == p) {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        syn::GenericArgument::Type(inner) => lock_type_name(inner),
+        _ => None,
+    }
+}
+
+/// A bound on a field type (or the original struct), deferred behind a vacuous
+/// `for<'__bound>` binder.
+///
+/// rustc rejects where clauses that are trivially false (e.g. `Instant: Serialize`),
+/// which would break the whole expansion for any struct with a non-conforming field.
+/// The binder makes the predicate non-trivial, so the impl or method is simply
+/// unavailable for that struct instead.
+pub fn deferred_bound(
+    ty: impl quote::ToTokens,
+    bound: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    quote!(for<'__bound> #ty: #bound)
+}
+
+/// Whether `tokens` name a lifetime anywhere, e.g. `&'a str` or `Vec<Cow<'a, str>>`.
+///
+/// Auto-trait bounds on such types (`for<'__bound> &'a str: Send`) can't be used to
+/// prove an async fn's future `Send`, so those are left to the concrete types.
+pub fn mentions_lifetime(tokens: proc_macro2::TokenStream) -> bool {
+    tokens.into_iter().any(|token| match token {
+        proc_macro2::TokenTree::Punct(punct) => punct.as_char() == '\'',
+        proc_macro2::TokenTree::Group(group) => mentions_lifetime(group.stream()),
+        _ => false,
+    })
+}
+
+/// Body of a generated guard's `Debug::fmt`: a `debug_struct` named `name` with
+/// `fields` of `self`. A field is shown if its type is known to be `Debug` (see
+/// `smart_lock::__private::DebugField`) and otherwise left out, ending in `..`.
+pub fn guard_debug_body<'f>(
+    name: &str,
+    fields: impl IntoIterator<Item = &'f ParsedField>,
+) -> proc_macro2::TokenStream {
+    let entries: Vec<proc_macro2::TokenStream> = fields
+        .into_iter()
+        .map(|field| {
+            let name = &field.name;
+            let name_str = name.unraw().to_string();
+            quote! {
+                match (&smart_lock::__private::DebugField(&self.#name)).debug_field() {
+                    Some(value) => {
+                        debug.field(#name_str, value);
+                    }
+                    None => hidden = true,
+                }
+            }
+        })
+        .collect();
+    quote! {
+        #[allow(unused_imports)]
+        use smart_lock::__private::{DebugFieldOpaque as _, DebugFieldValue as _};
+        let mut debug = f.debug_struct(#name);
+        #[allow(unused_mut)]
+        let mut hidden = false;
+        #(#entries)*
+        if hidden {
+            debug.finish_non_exhaustive()
+        } else {
+            debug.finish()
+        }
+    }
 }
 
+/// Most locked fields a struct may have. Every locked field adds a mode
+/// parameter to the builder and guard, and each per-field method spells all of
+/// them out, so expansion grows quadratically: 100 fields take a few seconds to
+/// type-check, 256 take minutes.
+const MAX_LOCKED_FIELDS: usize = 128;
+
 pub fn parse(attr: proc_macro2::TokenStream, item: &ItemStruct) -> syn::Result<ParsedStruct> {
-    // No arguments accepted
-    if !attr.is_empty() {
-        return Err(syn::Error::new_spanned(
-            &attr,
-            "smart_lock takes no arguments. Usage: #[smart_lock]",
-        ));
+    let mut poison = false;
+    let mut owned = None;
+    let mut subscribe = None;
+    let mut actor = None;
+    let mut suffix = None;
+    let mut vis = None;
+    let mut no_send_assert = false;
+    let mut metrics = false;
+    let options = syn::meta::parser(|meta| {
+        if meta.path.is_ident("poison") {
+            poison = true;
+            Ok(())
+        } else if meta.path.is_ident("owned") {
+            owned = Some(meta.path.clone());
+            Ok(())
+        } else if meta.path.is_ident("subscribe") {
+            if cfg!(feature = "subscribe") {
+                subscribe = Some(meta.path.clone());
+                Ok(())
+            } else {
+                Err(meta.error("`subscribe` requires the `subscribe` feature of smart-lock"))
+            }
+        } else if meta.path.is_ident("actor") {
+            if cfg!(feature = "actor") {
+                actor = Some(meta.path.clone());
+                Ok(())
+            } else {
+                Err(meta.error("`actor` requires the `actor` feature of smart-lock"))
+            }
+        } else if meta.path.is_ident("no_send_assert") {
+            no_send_assert = true;
+            Ok(())
+        } else if meta.path.is_ident("metrics") {
+            metrics = true;
+            Ok(())
+        } else if meta.path.is_ident("suffix") {
+            let value: LitStr = meta.value()?.parse()?;
+            let name = format!("{}{}", item.ident.unraw(), value.value());
+            if value.value().is_empty() || syn::parse_str::<Ident>(&name).is_err() {
+                return Err(syn::Error::new_spanned(
+                    &value,
+                    "`suffix` must be non-empty and form an identifier when appended to the struct name",
+                ));
+            }
+            suffix = Some(value.value());
+            Ok(())
+        } else if meta.path.is_ident("vis") {
+            let value: LitStr = meta.value()?.parse()?;
+            vis = Some(value.parse::<Visibility>().map_err(|_| {
+                syn::Error::new_spanned(
+                    &value,
+                    "`vis` must be a visibility, e.g. \"pub(crate)\", \"pub(super)\" or \"\" for private",
+                )
+            })?);
+            Ok(())
+        } else {
+            Err(meta.error(
+                "unknown smart_lock option. Usage: #[smart_lock], #[smart_lock(poison)], #[smart_lock(owned)], #[smart_lock(subscribe)], #[smart_lock(actor)], #[smart_lock(no_send_assert)], #[smart_lock(metrics)], #[smart_lock(suffix = \"Name\")], or #[smart_lock(vis = \"pub(crate)\")]",
+            ))
+        }
+    });
+    syn::parse::Parser::parse2(options, attr)?;
+    // Actors hold a clone of the field's `Arc`, so `actor` implies `owned`.
+    if let Some(path) = owned.as_ref().or(actor.as_ref()) {
+        let option = path.get_ident().map(Ident::to_string).unwrap_or_default();
+        if poison {
+            return Err(syn::Error::new_spanned(
+                path,
+                format!("`{option}` cannot be combined with `poison`: owned guards outlive the lock's poison flags"),
+            ));
+        }
+        if subscribe.is_some() {
+            return Err(syn::Error::new_spanned(
+                path,
+                format!("`{option}` cannot be combined with `subscribe`: owned guards outlive the lock's subscribers"),
+            ));
+        }
     }
+    let owned = owned.is_some() || actor.is_some();
+    let actor = actor.is_some();
+    let subscribe = subscribe.is_some();
 
     // Extract named fields only
     let named_fields = match &item.fields {
@@ -105,7 +784,11 @@ pub fn parse(attr: proc_macro2::TokenStream, item: &ItemStruct) -> syn::Result<P
         Fields::Unit => {
             return Err(syn::Error::new_spanned(
                 &item.ident,
-                "smart_lock only supports structs with named fields",
+                format!(
+                    "smart_lock only supports structs with named fields; \
+                     write `struct {} {{}}` for a struct without fields",
+                    item.ident
+                ),
             ));
         }
     };
@@ -126,26 +809,497 @@ pub fn parse(attr: proc_macro2::TokenStream, item: &ItemStruct) -> syn::Result<P
         }
 
         let no_lock = !no_lock_attrs.is_empty();
+
+        let lock_attrs: Vec<&Attribute> = f
+            .attrs
+            .iter()
+            .filter(|a| a.path().is_ident("lock"))
+            .collect();
+
+        if lock_attrs.len() > 1 {
+            return Err(syn::Error::new_spanned(
+                lock_attrs[1],
+                "duplicate #[lock(...)] attribute",
+            ));
+        }
+
+        let mut order = None;
+        let mut groups: Vec<LitStr> = Vec::new();
+        let mut nested = false;
+        let mut skip_new = false;
+        let mut owned_field = false;
+        if let Some(lock_attr) = lock_attrs.first() {
+            if no_lock {
+                return Err(syn::Error::new_spanned(
+                    lock_attr,
+                    "#[lock(...)] has no effect on a #[no_lock] field",
+                ));
+            }
+            lock_attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("order") {
+                    let value = meta.value()?;
+                    let negative = value.parse::<Option<syn::Token![-]>>()?.is_some();
+                    let lit: syn::LitInt = value.parse()?;
+                    let n: i64 = lit.base10_parse()?;
+                    order = Some(if negative { -n } else { n });
+                    Ok(())
+                } else if meta.path.is_ident("group") {
+                    let name: LitStr = meta.value()?.parse()?;
+                    if groups.iter().any(|g| g.value() == name.value()) {
+                        return Err(syn::Error::new_spanned(
+                            &name,
+                            format!("duplicate group `{}` on this field", name.value()),
+                        ));
+                    }
+                    groups.push(name);
+                    Ok(())
+                } else if meta.path.is_ident("nested") {
+                    nested = true;
+                    Ok(())
+                } else if meta.path.is_ident("skip_new") {
+                    skip_new = true;
+                    Ok(())
+                } else if meta.path.is_ident("owned") {
+                    if poison {
+                        return Err(meta.error(
+                            "`owned` cannot be combined with `poison`: owned guards outlive the lock's poison flags",
+                        ));
+                    }
+                    if subscribe {
+                        return Err(meta.error(
+                            "`owned` cannot be combined with `subscribe`: owned guards outlive the lock's subscribers",
+                        ));
+                    }
+                    owned_field = true;
+                    Ok(())
+                } else {
+                    Err(meta.error(
+                        "unknown #[lock] option. Usage: #[lock(order = N)], #[lock(group = \"name\")], #[lock(nested)], #[lock(skip_new)] or #[lock(owned)]",
+                    ))
+                }
+            })?;
+            if order.is_none() && groups.is_empty() && !nested && !skip_new && !owned_field {
+                return Err(syn::Error::new_spanned(
+                    lock_attr,
+                    "missing option. Usage: #[lock(order = N)], #[lock(group = \"name\")], #[lock(nested)], #[lock(skip_new)] or #[lock(owned)]",
+                ));
+            }
+        }
+
+        let boxed_attrs: Vec<&Attribute> = f
+            .attrs
+            .iter()
+            .filter(|a| a.path().is_ident("boxed"))
+            .collect();
+        if boxed_attrs.len() > 1 {
+            return Err(syn::Error::new_spanned(
+                boxed_attrs[1],
+                "duplicate #[boxed] attribute",
+            ));
+        }
+        let mut ty = f.ty.clone();
+        let boxed = if let Some(boxed_attr) = boxed_attrs.first() {
+            boxed_attr.meta.require_path_only()?;
+            if no_lock {
+                return Err(syn::Error::new_spanned(
+                    boxed_attr,
+                    "#[boxed] has no effect on a #[no_lock] field",
+                ));
+            }
+            ty = boxed_inner(&f.ty).ok_or_else(|| {
+                syn::Error::new_spanned(&f.ty, "#[boxed] requires a field of type `Box<T>`")
+            })?;
+            true
+        } else {
+            false
+        };
+
+        if !no_lock && !nested {
+            if let Some(kind) = lock_type_name(&ty) {
+                return Err(syn::Error::new_spanned(
+                    &f.ty,
+                    format!(
+                        "field type is already a `{kind}`; #[smart_lock] would lock it again. \
+                         Mark the field #[no_lock] if it synchronizes itself, or \
+                         #[lock(nested)] to wrap it anyway"
+                    ),
+                ));
+            }
+        }
+
         let attrs: Vec<Attribute> = f
             .attrs
             .iter()
-            .filter(|a| !a.path().is_ident("no_lock"))
+            .filter(|a| {
+                !a.path().is_ident("no_lock")
+                    && !a.path().is_ident("lock")
+                    && !a.path().is_ident("boxed")
+            })
             .cloned()
             .collect();
 
         fields.push(ParsedField {
             name: f.ident.clone().unwrap(),
-            ty: f.ty.clone(),
+            ty,
             vis: f.vis.clone(),
             attrs,
             no_lock,
+            order,
+            groups,
+            boxed,
+            skip_new,
+            owned: owned_field,
         });
     }
 
-    Ok(ParsedStruct {
-        vis: item.vis.clone(),
+    let locked = fields.iter().filter(|f| !f.no_lock).count();
+    if locked > MAX_LOCKED_FIELDS {
+        return Err(syn::Error::new_spanned(
+            &item.ident,
+            format!(
+                "#[smart_lock] supports at most {MAX_LOCKED_FIELDS} locked fields, found {locked}. \
+                 Move related fields into their own #[smart_lock] struct and hold its lock \
+                 as a #[no_lock] field; #[lock(group = \"name\")] doesn't lower the count"
+            ),
+        ));
+    }
+
+    let presets = parse_presets(&item.attrs, &fields)?;
+    let groups = collect_groups(&fields, &presets)?;
+    let swaps = parse_swaps(&item.attrs, &fields)?;
+
+    let parsed = ParsedStruct {
+        vis: vis.unwrap_or_else(|| item.vis.clone()),
         name: item.ident.clone(),
         generics: item.generics.clone(),
         fields,
-    })
+        presets,
+        groups,
+        swaps,
+        poison,
+        subscribe,
+        owned,
+        actor,
+        no_send_assert,
+        metrics,
+        suffix: suffix.unwrap_or_else(|| "Lock".to_string()),
+    };
+    check_generated_names(&parsed)?;
+    Ok(parsed)
+}
+
+/// The generated type a method is defined on; method names only clash within one.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum MethodOwner {
+    Lock,
+    Builder,
+    Guard,
+}
+
+/// Every per-field method generated for `field`, with the type it's defined on.
+fn per_field_methods(parsed: &ParsedStruct, field: &ParsedField) -> Vec<(MethodOwner, String)> {
+    let name = field.name.unraw().to_string();
+    let mut methods: Vec<(MethodOwner, String)> = ["raw", "get_mut", "take", "replace"]
+        .iter()
+        .map(|prefix| (MethodOwner::Lock, format!("{prefix}_{name}")))
+        .collect();
+    if field.no_lock {
+        return methods;
+    }
+    let mut lock_prefixes = vec![
+        "read",
+        "write",
+        "try_read",
+        "try_write",
+        "upgrade",
+        "try_upgrade",
+        "snapshot",
+        "compare_and_set",
+        "modify",
+        "fetch_modify",
+    ];
+    if parsed.poison {
+        lock_prefixes.extend(["is_poisoned", "clear_poison"]);
+    }
+    if parsed.subscribe {
+        lock_prefixes.push("changed");
+    }
+    if parsed.actor {
+        lock_prefixes.push("spawn_actor");
+    }
+    let mut guard_prefixes = vec!["upgrade", "downgrade", "try_upgrade", "write_ref"];
+    if !parsed.poison && !parsed.subscribe {
+        guard_prefixes.push("into_raw_write");
+    }
+    let prefixed = |owner, prefixes: &[&str]| -> Vec<(MethodOwner, String)> {
+        prefixes
+            .iter()
+            .map(|prefix| (owner, format!("{prefix}_{name}")))
+            .collect()
+    };
+    methods.extend(prefixed(MethodOwner::Lock, &lock_prefixes));
+    methods.extend(prefixed(
+        MethodOwner::Builder,
+        &["read", "write", "upgrade", "lock_then_upgrade"],
+    ));
+    methods.extend(prefixed(MethodOwner::Guard, &guard_prefixes));
+    methods.push((MethodOwner::Guard, format!("upgrade_{name}_if")));
+    methods.push((MethodOwner::Lock, format!("read_{name}_blocking")));
+    methods.push((MethodOwner::Lock, format!("write_{name}_blocking")));
+    methods.push((MethodOwner::Guard, format!("downgrade_{name}_to_upgrade")));
+    if parsed.is_owned(field) {
+        methods.extend(
+            [
+                "read",
+                "write",
+                "upgrade",
+                "try_read",
+                "try_write",
+                "try_upgrade",
+            ]
+            .iter()
+            .map(|mode| (MethodOwner::Lock, format!("{mode}_{name}_owned"))),
+        );
+        methods.push((MethodOwner::Lock, format!("field_arc_{name}")));
+    }
+    methods
+}
+
+/// Whole-struct methods whose names a per-field method could take: a field `all`
+/// would get `snapshot_all()` and `upgrade_all()`.
+fn whole_struct_methods(parsed: &ParsedStruct) -> Vec<(MethodOwner, String)> {
+    let mut methods = vec![(MethodOwner::Lock, "snapshot_all".to_string())];
+    if parsed.fields.iter().all(|f| !f.no_lock) {
+        methods.push((MethodOwner::Lock, "replace_all".to_string()));
+    }
+    if parsed.fields.iter().any(|f| !f.no_lock) {
+        methods.extend(
+            ["upgrade_all", "try_upgrade_all", "downgrade_all"]
+                .iter()
+                .map(|method| (MethodOwner::Guard, method.to_string())),
+        );
+    }
+    methods
+}
+
+/// Reject fields whose generated methods take a name a whole-struct method or
+/// another field's methods already have, e.g. `upgrade_x_if()` for both `x` and
+/// `x_if`. Reported at the later field.
+fn check_generated_names(parsed: &ParsedStruct) -> syn::Result<()> {
+    let mut seen: HashMap<(MethodOwner, String), Option<&Ident>> = whole_struct_methods(parsed)
+        .into_iter()
+        .map(|method| (method, None))
+        .collect();
+    for field in &parsed.fields {
+        for method in per_field_methods(parsed, field) {
+            let clash = match seen.get(&method) {
+                Some(None) => "which is also a whole-struct method".to_string(),
+                Some(Some(earlier)) if *earlier != &field.name => {
+                    format!("which field `{}` generates too", earlier.unraw())
+                }
+                _ => {
+                    seen.insert(method, Some(&field.name));
+                    continue;
+                }
+            };
+            return Err(syn::Error::new_spanned(
+                &field.name,
+                format!(
+                    "field `{}` generates `{}()`, {clash}. Rename the field",
+                    field.name.unraw(),
+                    method.1,
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Parse every `#[preset(name = "write_a, read_b", ...)]` attribute on the struct.
+fn parse_presets(attrs: &[Attribute], fields: &[ParsedField]) -> syn::Result<Vec<Preset>> {
+    let mut presets: Vec<Preset> = Vec::new();
+    for attr in attrs.iter().filter(|a| a.path().is_ident("preset")) {
+        attr.parse_nested_meta(|meta| {
+            let name = meta.path.get_ident().cloned().ok_or_else(|| {
+                meta.error("expected a preset name. Usage: #[preset(name = \"write_a, read_b\")]")
+            })?;
+            if presets.iter().any(|p| p.name == name) {
+                return Err(meta.error(format!("duplicate preset `{}`", name)));
+            }
+            let spec: LitStr = meta.value()?.parse()?;
+            let modes = parse_preset_spec(&spec, fields)?;
+            presets.push(Preset { name, spec, modes });
+            Ok(())
+        })?;
+    }
+    Ok(presets)
+}
+
+/// `snake_case` to `UpperCamelCase`, for type names derived from field or group names.
+pub fn upper_camel(name: &str) -> String {
+    name.split('_')
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+/// `MyState` → `my_state`, `HTTPState` → `http_state`.
+pub fn snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut out = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+            if prev.is_lowercase() || prev.is_ascii_digit() || (prev.is_uppercase() && next_lower) {
+                out.push('_');
+            }
+        }
+        out.extend(c.to_lowercase());
+    }
+    out
+}
+
+/// Gather the fields' `#[lock(group = "...")]` names into groups. Each group
+/// generates `builder_<name>()`, so names must be identifiers distinct from presets.
+fn collect_groups(fields: &[ParsedField], presets: &[Preset]) -> syn::Result<Vec<Group>> {
+    let mut groups: Vec<Group> = Vec::new();
+    for (index, field) in fields.iter().enumerate() {
+        for lit in &field.groups {
+            let name: Ident = syn::parse_str(&lit.value())
+                .ok()
+                .filter(|name: &Ident| name.unraw() == *name)
+                .ok_or_else(|| {
+                    syn::Error::new_spanned(lit, "group name must be an identifier, e.g. \"hot\"")
+                })?;
+            if presets.iter().any(|p| p.name == name) {
+                return Err(syn::Error::new_spanned(
+                    lit,
+                    format!(
+                        "group `{}` has the same name as a preset; both generate `builder_{}()`",
+                        name, name
+                    ),
+                ));
+            }
+            match groups.iter_mut().find(|g| g.name == name) {
+                Some(group) => group.fields.push(index),
+                None => groups.push(Group {
+                    name: Ident::new(&lit.value(), lit.span()),
+                    fields: vec![index],
+                }),
+            }
+        }
+    }
+    Ok(groups)
+}
+
+/// Parse every `#[swap(a, b)]` attribute on the struct into field index pairs.
+fn parse_swaps(attrs: &[Attribute], fields: &[ParsedField]) -> syn::Result<Vec<(usize, usize)>> {
+    let mut swaps: Vec<(usize, usize)> = Vec::new();
+    for attr in attrs.iter().filter(|a| a.path().is_ident("swap")) {
+        let names = attr.parse_args_with(
+            syn::punctuated::Punctuated::<Ident, syn::Token![,]>::parse_terminated,
+        )?;
+        let names: Vec<Ident> = names.into_iter().collect();
+        let [a, b] = names.as_slice() else {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "expected two field names. Usage: #[swap(front, back)]",
+            ));
+        };
+        let index_of = |name: &Ident| -> syn::Result<usize> {
+            let index = fields
+                .iter()
+                .position(|f| f.name.unraw() == name.unraw())
+                .ok_or_else(|| {
+                    syn::Error::new_spanned(name, format!("no field named `{}`", name))
+                })?;
+            if fields[index].no_lock {
+                return Err(syn::Error::new_spanned(
+                    name,
+                    format!("`{}` is #[no_lock] and cannot be locked", name),
+                ));
+            }
+            Ok(index)
+        };
+        let (i, j) = (index_of(a)?, index_of(b)?);
+        if i == j {
+            return Err(syn::Error::new_spanned(
+                b,
+                "#[swap] needs two different fields",
+            ));
+        }
+        let (a_ty, b_ty) = (&fields[i].ty, &fields[j].ty);
+        if quote!(#a_ty).to_string() != quote!(#b_ty).to_string() {
+            return Err(syn::Error::new_spanned(
+                attr,
+                format!(
+                    "#[swap] fields must have the same type: `{}` and `{}` differ",
+                    a, b
+                ),
+            ));
+        }
+        if swaps.contains(&(i, j)) || swaps.contains(&(j, i)) {
+            return Err(syn::Error::new_spanned(attr, "duplicate #[swap] pair"));
+        }
+        swaps.push((i, j));
+    }
+    Ok(swaps)
+}
+
+fn parse_preset_spec(
+    spec: &LitStr,
+    fields: &[ParsedField],
+) -> syn::Result<Vec<(usize, PresetMode)>> {
+    let mut modes: Vec<(usize, PresetMode)> = Vec::new();
+    for step in spec
+        .value()
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        let (mode, field_name) = if let Some(rest) = step.strip_prefix("write_") {
+            (PresetMode::Write, rest)
+        } else if let Some(rest) = step.strip_prefix("read_") {
+            (PresetMode::Read, rest)
+        } else if let Some(rest) = step.strip_prefix("upgrade_") {
+            (PresetMode::Upgrade, rest)
+        } else {
+            return Err(syn::Error::new_spanned(
+                spec,
+                format!("invalid preset step `{}`: expected `write_<field>`, `read_<field>`, or `upgrade_<field>`", step),
+            ));
+        };
+        let index = fields
+            .iter()
+            .position(|f| f.name.unraw() == field_name)
+            .ok_or_else(|| {
+                syn::Error::new_spanned(
+                    spec,
+                    format!("preset step `{}`: no field named `{}`", step, field_name),
+                )
+            })?;
+        if fields[index].no_lock {
+            return Err(syn::Error::new_spanned(
+                spec,
+                format!(
+                    "preset step `{}`: `{}` is #[no_lock] and cannot be locked",
+                    step, field_name
+                ),
+            ));
+        }
+        if modes.iter().any(|(i, _)| *i == index) {
+            return Err(syn::Error::new_spanned(
+                spec,
+                format!("preset selects `{}` more than once", field_name),
+            ));
+        }
+        modes.push((index, mode));
+    }
+    Ok(modes)
 }
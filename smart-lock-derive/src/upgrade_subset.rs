@@ -0,0 +1,51 @@
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{Expr, Ident, Token};
+
+/// `upgrade_subset!(guard, a, c)`.
+pub struct UpgradeSubset {
+    guard: Expr,
+    fields: Vec<Ident>,
+}
+
+impl Parse for UpgradeSubset {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let guard: Expr = input.parse()?;
+        if input.is_empty() {
+            return Err(input
+                .error("expected at least one field. Usage: upgrade_subset!(guard, a, c).await"));
+        }
+        input.parse::<Token![,]>()?;
+        let fields: Vec<Ident> = Punctuated::<Ident, Token![,]>::parse_terminated(input)?
+            .into_iter()
+            .collect();
+        if fields.is_empty() {
+            return Err(input
+                .error("expected at least one field. Usage: upgrade_subset!(guard, a, c).await"));
+        }
+        for (i, field) in fields.iter().enumerate() {
+            if fields[..i].contains(field) {
+                return Err(syn::Error::new_spanned(
+                    field,
+                    format!("`{}` is listed more than once", field.unraw()),
+                ));
+            }
+        }
+        Ok(Self { guard, fields })
+    }
+}
+
+/// Marks each field on the guard's upgrade plan, then applies it: the future
+/// upgrades the marked fields in acquisition order.
+pub fn generate(spec: &UpgradeSubset) -> proc_macro2::TokenStream {
+    let guard = &spec.guard;
+    let steps = spec.fields.iter().map(|field| {
+        let method = format_ident!("__plan_upgrade_{}", field, span = field.span());
+        quote!(.#method())
+    });
+    quote! {
+        (#guard).__upgrade_plan() #(#steps)* .__apply()
+    }
+}
@@ -42,6 +42,12 @@ struct SmartState {
     d: u64,
 }
 
+// Single-field struct, compared against a bare `RwLock`
+#[smart_lock]
+struct SingleField {
+    value: u64,
+}
+
 const TASKS: usize = 8;
 const OPS_PER_TASK: usize = 1000;
 
@@ -338,10 +344,87 @@ fn bench_mixed_access(c: &mut Criterion) {
     group.finish();
 }
 
+// Sequential vs joined acquisition of four uncontended fields. An uncontended
+// `write()` completes on its first poll, so awaiting the locks one after another
+// doesn't wait any longer than polling them all at once.
+fn bench_uncontended(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let mut group = c.benchmark_group("uncontended_four_fields");
+
+    let manual = ManualState::new();
+    group.bench_function("manual_sequential", |b| {
+        b.to_async(&rt).iter(|| async {
+            let a = manual.a.write().await;
+            let b = manual.b.write().await;
+            let c = manual.c.write().await;
+            let d = manual.d.write().await;
+            std::hint::black_box((*a, *b, *c, *d));
+        });
+    });
+
+    group.bench_function("manual_joined", |b| {
+        b.to_async(&rt).iter(|| async {
+            let (a, b, c, d) = tokio::join!(
+                manual.a.write(),
+                manual.b.write(),
+                manual.c.write(),
+                manual.d.write()
+            );
+            std::hint::black_box((*a, *b, *c, *d));
+        });
+    });
+
+    let smart = SmartStateLock::new(0, 0, 0, 0);
+    group.bench_function("smart_lock", |b| {
+        b.to_async(&rt).iter(|| async {
+            let g = smart.lock_all_mut().await;
+            std::hint::black_box((*g.a, *g.b, *g.c, *g.d));
+        });
+    });
+
+    group.finish();
+}
+
+// Uncontended write of a one-field struct. The builder and guard are
+// zero-cost wrappers, so all three should match the bare `RwLock`.
+fn bench_single_field(c: &mut Criterion) {
+    let mut group = c.benchmark_group("single_field_write");
+
+    let bare = RwLock::new(0u64);
+    group.bench_function("bare_rwlock", |b| {
+        b.iter(|| {
+            pollster::block_on(async {
+                *bare.write().await += 1;
+            })
+        });
+    });
+
+    let smart = SingleFieldLock::new(0);
+    group.bench_function("accessor", |b| {
+        b.iter(|| {
+            pollster::block_on(async {
+                *smart.write_value().await += 1;
+            })
+        });
+    });
+
+    group.bench_function("builder", |b| {
+        b.iter(|| {
+            pollster::block_on(async {
+                *smart.builder().write_value().lock().await.value += 1;
+            })
+        });
+    });
+
+    group.finish();
+}
+
 criterion_group!(
     benches,
     bench_write_contention,
     bench_read_heavy,
-    bench_mixed_access
+    bench_mixed_access,
+    bench_uncontended,
+    bench_single_field
 );
 criterion_main!(benches);
@@ -30,21 +30,23 @@ async fn main() {
         .lock()
         .await;
 
-    if guard.items.contains(&item.to_string()) {
-        println!("'{}' already exists, no write needed", item);
+    // Atomic conditional upgrade: the predicate sees the current items, and only
+    // if it holds does the guard wait for other readers to drain and take the
+    // write lock. No gap where the lock is released.
+    match guard
+        .upgrade_items_if(|items| !items.iter().any(|i| i == item))
+        .await
+    {
+        Ok(guard) => {
+            println!("'{}' not found, upgraded to write", item);
+            let mut guard = guard.upgrade_last_modified().await;
+            guard.items.push(item.into());
+            *guard.last_modified = "added cherry".into();
+
+            println!("Added '{}', items: {:?}", item, &*guard.items);
+        }
         // Guard drops here — no write lock was ever acquired
-    } else {
-        println!("'{}' not found, upgrading to write...", item);
-
-        // Atomic upgrade: waits for other readers to drain, then grants
-        // exclusive access. No gap where the lock is released.
-        let mut guard = guard.upgrade_items().await;
-        guard.items.push(item.into());
-
-        let mut guard = guard.upgrade_last_modified().await;
-        *guard.last_modified = "added cherry".into();
-
-        println!("Added '{}', items: {:?}", item, &*guard.items);
+        Err(_) => println!("'{}' already exists, no write needed", item),
     }
 
     // Demonstrate downgrade: write first, then downgrade to read
@@ -0,0 +1,90 @@
+use async_lock::RwLock;
+use std::fmt;
+use std::sync::Arc;
+use tokio::sync::{mpsc, oneshot};
+
+type Command<T> = Box<dyn FnOnce(&mut T) + Send>;
+
+/// Commands that can be queued before `send`/`query` wait for the actor.
+const CAPACITY: usize = 64;
+
+/// Handle to a field actor spawned by a `#[smart_lock(actor)]` lock's
+/// `spawn_actor_*()` method.
+///
+/// The actor task applies commands one at a time, in the order they were sent,
+/// each under the field's write lock, so it serializes with every other guard on
+/// the field. Handles are cheap to clone; the task exits once every handle has
+/// been dropped.
+pub struct FieldActor<T> {
+    commands: mpsc::Sender<Command<T>>,
+}
+
+impl<T: Send + Sync + 'static> FieldActor<T> {
+    /// Spawn the actor task for `field` on the current Tokio runtime.
+    #[doc(hidden)]
+    pub fn spawn(field: Arc<RwLock<T>>) -> Self {
+        let (commands, mut queue) = mpsc::channel::<Command<T>>(CAPACITY);
+        tokio::spawn(async move {
+            while let Some(command) = queue.recv().await {
+                command(&mut *field.write().await);
+            }
+        });
+        Self { commands }
+    }
+
+    /// Queue `command` to run on the field, waiting only if the queue is full.
+    ///
+    /// Returns [`ActorStopped`] if the actor task is gone: a previous command
+    /// panicked, or the runtime shut down.
+    pub async fn send(
+        &self,
+        command: impl FnOnce(&mut T) + Send + 'static,
+    ) -> Result<(), ActorStopped> {
+        self.commands
+            .send(Box::new(command))
+            .await
+            .map_err(|_| ActorStopped)
+    }
+
+    /// Run `query` on the field after every previously sent command, and return
+    /// its result.
+    pub async fn query<R: Send + 'static>(
+        &self,
+        query: impl FnOnce(&T) -> R + Send + 'static,
+    ) -> Result<R, ActorStopped> {
+        let (reply, result) = oneshot::channel();
+        self.send(move |value| {
+            let _ = reply.send(query(value));
+        })
+        .await?;
+        result.await.map_err(|_| ActorStopped)
+    }
+}
+
+impl<T> Clone for FieldActor<T> {
+    fn clone(&self) -> Self {
+        Self {
+            commands: self.commands.clone(),
+        }
+    }
+}
+
+impl<T> fmt::Debug for FieldActor<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FieldActor")
+            .field("stopped", &self.commands.is_closed())
+            .finish()
+    }
+}
+
+/// Error returned by [`FieldActor`] when its task is no longer running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ActorStopped;
+
+impl fmt::Display for ActorStopped {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("field actor stopped: a command panicked or the runtime shut down")
+    }
+}
+
+impl std::error::Error for ActorStopped {}
@@ -0,0 +1,69 @@
+/// Assert at compile time that one or more types are `Send`.
+///
+/// Useful for pinning the auto-trait behavior of a specific guard shape you intend
+/// to hold across `.await` in a spawned task:
+///
+/// ```rust
+/// use smart_lock::{smart_lock, ReadLocked, Unlocked, WriteLocked};
+///
+/// #[smart_lock]
+/// struct State { counter: u64, name: String }
+///
+/// smart_lock::assert_send!(
+///     StateLockGuard<'static, WriteLocked, ReadLocked>,
+///     StateLockGuard<'static, Unlocked, WriteLocked>,
+/// );
+/// ```
+///
+/// A type that is not `Send` fails to compile:
+///
+/// ```compile_fail
+/// smart_lock::assert_send!(std::rc::Rc<u32>);
+/// ```
+#[macro_export]
+macro_rules! assert_send {
+    ($($ty:ty),+ $(,)?) => {
+        const _: fn() = || {
+            fn assert_send<T: ?Sized + ::core::marker::Send>() {}
+            $(assert_send::<$ty>();)+
+        };
+    };
+}
+
+/// Assert at compile time that one or more types are `Sync`.
+///
+/// ```rust
+/// use smart_lock::{smart_lock, ReadLocked};
+///
+/// #[smart_lock]
+/// struct State { counter: u64 }
+///
+/// smart_lock::assert_sync!(StateLock, StateLockGuard<'static, ReadLocked>);
+/// ```
+///
+/// A type that is not `Sync` fails to compile:
+///
+/// ```compile_fail
+/// smart_lock::assert_sync!(std::cell::Cell<u32>);
+/// ```
+#[macro_export]
+macro_rules! assert_sync {
+    ($($ty:ty),+ $(,)?) => {
+        const _: fn() = || {
+            fn assert_sync<T: ?Sized + ::core::marker::Sync>() {}
+            $(assert_sync::<$ty>();)+
+        };
+    };
+}
+
+/// Implemented for every `Send + Sync` type. `#[smart_lock]` requires it of each
+/// field, so a field that can't be shared between threads is reported at the field.
+#[doc(hidden)]
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be shared between threads safely, so a `#[smart_lock]` lock containing it isn't `Send + Sync`",
+    label = "this field must be `Send + Sync`",
+    note = "for a lock that stays on one thread, opt out of this check with `#[smart_lock(no_send_assert)]`"
+)]
+pub trait FieldSendSync {}
+
+impl<T: ?Sized + Send + Sync> FieldSendSync for T {}
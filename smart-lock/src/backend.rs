@@ -0,0 +1,94 @@
+use async_lock::{RwLock, RwLockReadGuard, RwLockUpgradableReadGuard, RwLockWriteGuard};
+use std::future::Future;
+
+/// The acquisitions a [`FieldGuard`](crate::FieldGuard) needs from a field's lock
+/// primitive: read, write and upgradable read, each awaited, blocking or tried.
+///
+/// `async_lock::RwLock` is the only implementation. A `parking_lot` one would
+/// block the executor thread on contention and hand out guards that aren't
+/// `Send`, so the trait stays crate-private: it keeps guard construction off the
+/// primitive's own API, not a way to swap it. Transitions between held modes
+/// (`upgrade()`, `downgrade()`) still use the `async_lock` guards directly.
+pub(crate) trait LockBackend {
+    type ReadGuard<'a>
+    where
+        Self: 'a;
+    type WriteGuard<'a>
+    where
+        Self: 'a;
+    type UpgradeGuard<'a>
+    where
+        Self: 'a;
+
+    fn read(&self) -> impl Future<Output = Self::ReadGuard<'_>>;
+    fn write(&self) -> impl Future<Output = Self::WriteGuard<'_>>;
+    fn upgradable_read(&self) -> impl Future<Output = Self::UpgradeGuard<'_>>;
+
+    fn read_blocking(&self) -> Self::ReadGuard<'_>;
+    fn write_blocking(&self) -> Self::WriteGuard<'_>;
+    fn upgradable_read_blocking(&self) -> Self::UpgradeGuard<'_>;
+
+    fn try_read(&self) -> Option<Self::ReadGuard<'_>>;
+    fn try_write(&self) -> Option<Self::WriteGuard<'_>>;
+    fn try_upgradable_read(&self) -> Option<Self::UpgradeGuard<'_>>;
+}
+
+impl<T: ?Sized> LockBackend for RwLock<T> {
+    type ReadGuard<'a>
+        = RwLockReadGuard<'a, T>
+    where
+        Self: 'a;
+    type WriteGuard<'a>
+        = RwLockWriteGuard<'a, T>
+    where
+        Self: 'a;
+    type UpgradeGuard<'a>
+        = RwLockUpgradableReadGuard<'a, T>
+    where
+        Self: 'a;
+
+    #[inline(always)]
+    fn read(&self) -> impl Future<Output = Self::ReadGuard<'_>> {
+        RwLock::read(self)
+    }
+
+    #[inline(always)]
+    fn write(&self) -> impl Future<Output = Self::WriteGuard<'_>> {
+        RwLock::write(self)
+    }
+
+    #[inline(always)]
+    fn upgradable_read(&self) -> impl Future<Output = Self::UpgradeGuard<'_>> {
+        RwLock::upgradable_read(self)
+    }
+
+    #[inline(always)]
+    fn read_blocking(&self) -> Self::ReadGuard<'_> {
+        RwLock::read_blocking(self)
+    }
+
+    #[inline(always)]
+    fn write_blocking(&self) -> Self::WriteGuard<'_> {
+        RwLock::write_blocking(self)
+    }
+
+    #[inline(always)]
+    fn upgradable_read_blocking(&self) -> Self::UpgradeGuard<'_> {
+        RwLock::upgradable_read_blocking(self)
+    }
+
+    #[inline(always)]
+    fn try_read(&self) -> Option<Self::ReadGuard<'_>> {
+        RwLock::try_read(self)
+    }
+
+    #[inline(always)]
+    fn try_write(&self) -> Option<Self::WriteGuard<'_>> {
+        RwLock::try_write(self)
+    }
+
+    #[inline(always)]
+    fn try_upgradable_read(&self) -> Option<Self::UpgradeGuard<'_>> {
+        RwLock::try_upgradable_read(self)
+    }
+}
@@ -0,0 +1,211 @@
+use event_listener::Event;
+use std::collections::VecDeque;
+use std::fmt;
+use std::future::Future;
+use std::sync::{Arc, Mutex, PoisonError, Weak};
+
+use crate::hook::{sealed, GuardHook};
+
+/// One subscriber's pending ids, shared between the hub and its receiver.
+struct Queue<I> {
+    /// Pending ids, and whether the hub has been dropped.
+    state: Mutex<(VecDeque<I>, bool)>,
+    ready: Event,
+}
+
+impl<I> Queue<I> {
+    fn push(&self, id: I) {
+        self.state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .0
+            .push_back(id);
+        self.ready.notify(usize::MAX);
+    }
+
+    fn close(&self) {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner).1 = true;
+        self.ready.notify(usize::MAX);
+    }
+
+    /// The next pending id, or `Err(closed)` if there is none.
+    fn pop(&self) -> Result<I, bool> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state.0.pop_front().ok_or(state.1)
+    }
+}
+
+/// Broadcasts the ids of written fields to every [`ChangeReceiver`].
+///
+/// Stored in the lock by `#[smart_lock(subscribe)]`; write guards send through
+/// it via their [`Notify`] hook.
+#[doc(hidden)]
+pub struct ChangeHub<I> {
+    subscribers: Mutex<Vec<Weak<Queue<I>>>>,
+}
+
+impl<I: Copy> ChangeHub<I> {
+    /// A hub with no subscribers.
+    pub fn new() -> Self {
+        Self {
+            subscribers: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// A receiver for every id sent from now on.
+    pub fn subscribe(&self) -> ChangeReceiver<I> {
+        let queue = Arc::new(Queue {
+            state: Mutex::new((VecDeque::new(), false)),
+            ready: Event::new(),
+        });
+        self.subscribers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(Arc::downgrade(&queue));
+        ChangeReceiver { queue }
+    }
+
+    /// Resolves on the next `send(id)` after this call (not after the first poll),
+    /// or when the hub is dropped.
+    pub fn changed(&self, id: I) -> impl Future<Output = ()>
+    where
+        I: PartialEq,
+    {
+        let mut receiver = self.subscribe();
+        async move {
+            while let Some(sent) = receiver.recv().await {
+                if sent == id {
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Queue `id` for every live receiver, forgetting dropped ones.
+    pub fn send(&self, id: I) {
+        self.subscribers
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .retain(|queue| match queue.upgrade() {
+                Some(queue) => {
+                    queue.push(id);
+                    true
+                }
+                None => false,
+            });
+    }
+}
+
+impl<I: Copy> Default for ChangeHub<I> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<I> Drop for ChangeHub<I> {
+    fn drop(&mut self) {
+        let subscribers = self
+            .subscribers
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner);
+        for queue in subscribers.drain(..).filter_map(|q| q.upgrade()) {
+            queue.close();
+        }
+    }
+}
+
+/// Receives the `FooFieldId` of each field written through a
+/// `#[smart_lock(subscribe)]` lock. Returned by the generated `subscribe()`.
+///
+/// Every receiver sees every id sent after it subscribed, in order. Ids queue
+/// up until received, so drop receivers you no longer read from.
+pub struct ChangeReceiver<I> {
+    queue: Arc<Queue<I>>,
+}
+
+impl<I> ChangeReceiver<I> {
+    /// Wait for the next written field's id.
+    ///
+    /// Returns `None` once the lock has been dropped and all queued ids have
+    /// been received.
+    pub async fn recv(&mut self) -> Option<I> {
+        loop {
+            match self.queue.pop() {
+                Ok(id) => return Some(id),
+                Err(true) => return None,
+                Err(false) => {}
+            }
+            let listener = self.queue.ready.listen();
+            // Re-check: a send between `pop` and `listen` doesn't wake `listener`.
+            match self.queue.pop() {
+                Ok(id) => return Some(id),
+                Err(true) => return None,
+                Err(false) => listener.await,
+            }
+        }
+    }
+
+    /// The next queued id, or `None` if none is queued right now.
+    pub fn try_recv(&mut self) -> Option<I> {
+        self.queue.pop().ok()
+    }
+}
+
+impl<I> fmt::Debug for ChangeReceiver<I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChangeReceiver").finish_non_exhaustive()
+    }
+}
+
+/// Change notification for `#[smart_lock(subscribe)]` guards: sends the field's
+/// id to the lock's subscribers when the guard stops being `WriteLocked`, by
+/// drop or by downgrade.
+///
+/// The id is sent just before the write lock is released, so a subscriber that
+/// reads the field after receiving it sees the write.
+pub struct Notify<'a, I: Copy> {
+    hub: &'a ChangeHub<I>,
+    id: I,
+    armed: bool,
+}
+
+impl<'a, I: Copy> Notify<'a, I> {
+    /// A disarmed hook sending `id` to `hub`; acquiring a `WriteLocked` guard arms it.
+    #[doc(hidden)]
+    #[inline(always)]
+    pub fn new(hub: &'a ChangeHub<I>, id: I) -> Self {
+        Self {
+            hub,
+            id,
+            armed: false,
+        }
+    }
+}
+
+impl<I: Copy> sealed::Sealed for Notify<'_, I> {}
+
+impl<I: Copy> GuardHook for Notify<'_, I> {
+    #[inline(always)]
+    fn rearm(mut self, armed: bool) -> Self {
+        if self.armed && !armed {
+            self.hub.send(self.id);
+        }
+        self.armed = armed;
+        self
+    }
+
+    #[inline(always)]
+    fn cancel(mut self) -> Self {
+        self.armed = false;
+        self
+    }
+}
+
+impl<I: Copy> Drop for Notify<'_, I> {
+    #[inline(always)]
+    fn drop(&mut self) {
+        if self.armed {
+            self.hub.send(self.id);
+        }
+    }
+}
@@ -1,10 +1,18 @@
 use async_lock::{RwLock, RwLockReadGuard, RwLockUpgradableReadGuard, RwLockWriteGuard};
+use std::convert::Infallible;
 use std::fmt;
+use std::future::Future;
 use std::marker::PhantomData;
 use std::ops::{Deref, DerefMut};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::task::{Context, Poll};
 
+use crate::backend::LockBackend;
+use crate::hook::{GuardHook, NoHook};
 use crate::mode::{
-    LockMode, LockModeKind, ReadLocked, Readable, UpgradeLocked, Writable, WriteLocked,
+    LockMode, LockModeKind, ReadLocked, Readable, Unlocked, Upgradable, UpgradeLocked, UpgradeTo,
+    Writable, WriteLocked,
 };
 
 enum FieldGuardInner<'a, T> {
@@ -14,6 +22,220 @@ enum FieldGuardInner<'a, T> {
     None,
 }
 
+impl<'a, T> FieldGuardInner<'a, T> {
+    /// Take the lock selected by `M::MODE`.
+    #[inline(always)]
+    async fn acquire<M: LockMode>(lock: &'a RwLock<T>) -> Self {
+        match M::MODE {
+            LockModeKind::Write => Self::Write(LockBackend::write(lock).await),
+            LockModeKind::Read => Self::Read(LockBackend::read(lock).await),
+            LockModeKind::Upgrade => Self::Upgrade(LockBackend::upgradable_read(lock).await),
+            LockModeKind::None => Self::None,
+        }
+    }
+
+    /// Take the lock selected by `M::MODE`, blocking the current thread.
+    #[inline(always)]
+    fn acquire_blocking<M: LockMode>(lock: &'a RwLock<T>) -> Self {
+        match M::MODE {
+            LockModeKind::Write => Self::Write(LockBackend::write_blocking(lock)),
+            LockModeKind::Read => Self::Read(LockBackend::read_blocking(lock)),
+            LockModeKind::Upgrade => Self::Upgrade(LockBackend::upgradable_read_blocking(lock)),
+            LockModeKind::None => Self::None,
+        }
+    }
+
+    /// Take the lock selected by `M::MODE` if it is immediately available.
+    #[inline(always)]
+    fn try_acquire<M: LockMode>(lock: &'a RwLock<T>) -> Option<Self> {
+        Some(match M::MODE {
+            LockModeKind::Write => Self::Write(LockBackend::try_write(lock)?),
+            LockModeKind::Read => Self::Read(LockBackend::try_read(lock)?),
+            LockModeKind::Upgrade => Self::Upgrade(LockBackend::try_upgradable_read(lock)?),
+            LockModeKind::None => Self::None,
+        })
+    }
+
+    /// [`acquire`](Self::acquire), counting in `contended` an acquisition that
+    /// has to wait: tries the lock first and only waits if that fails.
+    #[inline(always)]
+    async fn acquire_counted<M: LockMode>(lock: &'a RwLock<T>, contended: &AtomicU64) -> Self {
+        if let Some(inner) = Self::try_acquire::<M>(lock) {
+            return inner;
+        }
+        contended.fetch_add(1, Ordering::Relaxed);
+        Self::acquire::<M>(lock).await
+    }
+
+    /// [`try_acquire`](Self::try_acquire), counting a failure in `contended`.
+    #[inline(always)]
+    fn try_acquire_counted<M: LockMode>(
+        lock: &'a RwLock<T>,
+        contended: &AtomicU64,
+    ) -> Option<Self> {
+        let inner = Self::try_acquire::<M>(lock);
+        if inner.is_none() {
+            contended.fetch_add(1, Ordering::Relaxed);
+        }
+        inner
+    }
+
+    /// [`acquire`](Self::acquire) inside a `smart_lock::acquire` span. Tries the
+    /// lock first; only if that fails does it wait (counted in `contended`, if
+    /// given), then records the wait in the span's `wait_us` field and emits a
+    /// `wait_time` event.
+    #[cfg(feature = "tracing")]
+    async fn acquire_traced<M: LockMode>(
+        lock: &'a RwLock<T>,
+        site: &'static FieldSite,
+        contended: Option<&AtomicU64>,
+    ) -> Self {
+        use tracing::Instrument;
+
+        if M::MODE == LockModeKind::None {
+            return Self::None;
+        }
+        let span = site.span(M::MODE);
+        async {
+            if let Some(inner) = Self::try_acquire::<M>(lock) {
+                return inner;
+            }
+            if let Some(contended) = contended {
+                contended.fetch_add(1, Ordering::Relaxed);
+            }
+            let start = std::time::Instant::now();
+            let inner = Self::acquire::<M>(lock).await;
+            let wait_us = u64::try_from(start.elapsed().as_micros()).unwrap_or(u64::MAX);
+            tracing::Span::current().record("wait_us", wait_us);
+            tracing::debug!(wait_us, "wait_time");
+            inner
+        }
+        .instrument(span)
+        .await
+    }
+
+    /// [`try_acquire`](Self::try_acquire) inside a `smart_lock::acquire` span,
+    /// emitting a `contended` event (and counting it in `contended`, if given)
+    /// if the lock is unavailable.
+    #[cfg(feature = "tracing")]
+    fn try_acquire_traced<M: LockMode>(
+        lock: &'a RwLock<T>,
+        site: &'static FieldSite,
+        contended: Option<&AtomicU64>,
+    ) -> Option<Self> {
+        if M::MODE == LockModeKind::None {
+            return Some(Self::None);
+        }
+        let _entered = site.span(M::MODE).entered();
+        let inner = Self::try_acquire::<M>(lock);
+        if inner.is_none() {
+            if let Some(contended) = contended {
+                contended.fetch_add(1, Ordering::Relaxed);
+            }
+            tracing::debug!("contended");
+        }
+        inner
+    }
+}
+
+/// Names a locked field in `tracing` spans. The derive passes one per field to
+/// the `*_traced` acquisition functions when the `tracing` feature is enabled.
+#[cfg(feature = "tracing")]
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct FieldSite {
+    pub lock: &'static str,
+    pub field: &'static str,
+}
+
+#[cfg(feature = "tracing")]
+impl FieldSite {
+    fn span(&self, mode: LockModeKind) -> tracing::Span {
+        tracing::trace_span!(
+            "smart_lock::acquire",
+            lock = self.lock,
+            field = self.field,
+            mode = ?mode,
+            wait_us = tracing::field::Empty,
+        )
+    }
+}
+
+/// Check, before a guard of `lock` waits to upgrade `field`, that it holds none
+/// of the fields acquired after it (`later`, with their modes).
+///
+/// Waiting on a field while holding a later one inverts the acquisition order:
+/// a task reading `field` that then waits for the later field blocks forever, and
+/// so does this guard. Panics on such an upgrade instead of letting it hang once
+/// the other task shows up. No-op in release builds.
+#[cfg(feature = "debug-deadlock")]
+#[doc(hidden)]
+#[track_caller]
+pub fn check_upgrade_order(
+    lock: &'static str,
+    field: &'static str,
+    later: &[(&'static str, LockModeKind)],
+) {
+    if !cfg!(debug_assertions) {
+        return;
+    }
+    if let Some((held, _)) = later.iter().find(|(_, mode)| *mode != LockModeKind::None) {
+        panic!(
+            "potential deadlock: a `{lock}` guard waits to upgrade `{field}` while holding \
+             `{held}`, which is acquired after it; a task reading `{field}` and waiting for \
+             `{held}` would block both forever. Lock `{field}` for write up front, use \
+             `try_upgrade_{field}()`, or `relock()`"
+        );
+    }
+}
+
+/// Yield once to the executor: the first poll wakes the task and returns
+/// `Pending`, the second completes. Runtime-agnostic, for the generated
+/// `yield_then_relock()`.
+#[doc(hidden)]
+#[derive(Debug)]
+#[must_use = "futures do nothing unless awaited"]
+pub struct YieldNow(bool);
+
+#[doc(hidden)]
+pub fn yield_now() -> YieldNow {
+    YieldNow(false)
+}
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            Poll::Ready(())
+        } else {
+            self.0 = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+/// Drive `acquire` until it completes, or until `give_up` does first, in which
+/// case `acquire` is dropped (releasing any locks it already holds) and the
+/// result is `None`. `acquire` is polled first, so a tie goes to it.
+/// Runtime-agnostic, for the generated `lock_all*_or()`.
+#[doc(hidden)]
+pub async fn acquire_or<T>(acquire: impl Future<Output = T>, give_up: impl Future) -> Option<T> {
+    let mut acquire = std::pin::pin!(acquire);
+    let mut give_up = std::pin::pin!(give_up);
+    std::future::poll_fn(|cx| {
+        if let Poll::Ready(value) = acquire.as_mut().poll(cx) {
+            Poll::Ready(Some(value))
+        } else if give_up.as_mut().poll(cx).is_ready() {
+            Poll::Ready(None)
+        } else {
+            Poll::Pending
+        }
+    })
+    .await
+}
+
 /// A field guard whose access level is encoded in the type parameter `M`.
 ///
 /// - `FieldGuard<'a, T, WriteLocked>` — `Deref` + `DerefMut`
@@ -21,10 +243,40 @@ enum FieldGuardInner<'a, T> {
 /// - `FieldGuard<'a, T, UpgradeLocked>` — `Deref` only, can `.upgrade().await` to `WriteLocked`
 /// - `FieldGuard<'a, T, Unlocked>` — no access (compile error on dereference)
 ///
+/// # Transitions
+///
+/// Every way to obtain a guard keeps the inner lock in step with `M`, which is
+/// why the `unreachable!()` arms below can't be hit from safe public code:
+///
+/// | From | Method | To |
+/// |------|--------|----|
+/// | — | `acquire` / `try_acquire` | `M` (lock taken per [`LockMode::MODE`]) |
+/// | — | `unlocked` | `Unlocked` |
+/// | `UpgradeLocked` | `upgrade().await` / `try_upgrade()` | `WriteLocked` (or `Err(self)`) |
+/// | `UpgradeLocked` | `downgrade()` | `ReadLocked` |
+/// | `WriteLocked` | `downgrade()` | `ReadLocked` |
+/// | `WriteLocked` | `downgrade_to_upgradable()` | `UpgradeLocked` |
+/// | `ReadLocked`, `UpgradeLocked` | `map(f)` | same mode, [`MappedFieldGuard`] |
+/// | `WriteLocked` | `map(f, f_mut)` | `WriteLocked`, [`MappedFieldGuard`] |
+/// | `ReadLocked`, `UpgradeLocked`, `WriteLocked` | `into_inner_guard()` | the `async_lock` guard |
+///
+/// The mode traits are sealed, so no other marker can pair a mismatched
+/// [`LockMode::MODE`] with `Readable`/`Writable`. The matrix is exercised under
+/// miri in `tests/miri.rs`.
+///
+/// # Hooks
+///
+/// `P` is [`NoHook`] (zero-sized) by default. Guards of `#[smart_lock(poison)]`
+/// structs use [`Poison`](crate::Poison), which marks the field poisoned if the
+/// guard is dropped during a panic while `WriteLocked`; see [`GuardHook`] for
+/// the others.
+///
 /// `FieldGuard` is `Send + Sync` when `T: Send + Sync` (inherited from the
 /// underlying [`async_lock`] guards). The generated lock struct includes a
 /// compile-time assertion that it is `Send + Sync`.
-pub struct FieldGuard<'a, T, M> {
+pub struct FieldGuard<'a, T, M, P = NoHook> {
+    // Declared before `inner` so the hook runs before the lock is released.
+    hook: P,
     inner: FieldGuardInner<'a, T>,
     _mode: PhantomData<M>,
 }
@@ -40,14 +292,9 @@ impl<'a, T, M> FieldGuard<'a, T, M> {
     where
         M: LockMode,
     {
-        let inner = match M::MODE {
-            LockModeKind::Write => FieldGuardInner::Write(lock.write().await),
-            LockModeKind::Read => FieldGuardInner::Read(lock.read().await),
-            LockModeKind::Upgrade => FieldGuardInner::Upgrade(lock.upgradable_read().await),
-            LockModeKind::None => FieldGuardInner::None,
-        };
         Self {
-            inner,
+            hook: NoHook,
+            inner: FieldGuardInner::acquire::<M>(lock).await,
             _mode: PhantomData,
         }
     }
@@ -61,19 +308,251 @@ impl<'a, T, M> FieldGuard<'a, T, M> {
     where
         M: LockMode,
     {
-        let inner = match M::MODE {
-            LockModeKind::Write => FieldGuardInner::Write(lock.try_write()?),
-            LockModeKind::Read => FieldGuardInner::Read(lock.try_read()?),
-            LockModeKind::Upgrade => FieldGuardInner::Upgrade(lock.try_upgradable_read()?),
-            LockModeKind::None => FieldGuardInner::None,
-        };
         Some(Self {
+            hook: NoHook,
+            inner: FieldGuardInner::try_acquire::<M>(lock)?,
+            _mode: PhantomData,
+        })
+    }
+
+    /// Like [`acquire`](Self::acquire), recording a `tracing` span for `site`.
+    #[cfg(feature = "tracing")]
+    #[doc(hidden)]
+    #[inline(always)]
+    pub async fn acquire_traced(lock: &'a RwLock<T>, site: &'static FieldSite) -> Self
+    where
+        M: LockMode,
+    {
+        Self {
+            hook: NoHook,
+            inner: FieldGuardInner::acquire_traced::<M>(lock, site, None).await,
+            _mode: PhantomData,
+        }
+    }
+
+    /// Like [`try_acquire`](Self::try_acquire), recording a `tracing` span for `site`.
+    #[cfg(feature = "tracing")]
+    #[doc(hidden)]
+    #[inline(always)]
+    pub fn try_acquire_traced(lock: &'a RwLock<T>, site: &'static FieldSite) -> Option<Self>
+    where
+        M: LockMode,
+    {
+        Some(Self {
+            hook: NoHook,
+            inner: FieldGuardInner::try_acquire_traced::<M>(lock, site, None)?,
+            _mode: PhantomData,
+        })
+    }
+}
+
+impl<'a, T, M, P: GuardHook> FieldGuard<'a, T, M, P> {
+    /// Like [`acquire`](FieldGuard::acquire), carrying `hook`, which is armed
+    /// if `M` is `WriteLocked`. Used by `#[smart_lock(poison)]` and
+    /// `#[smart_lock(subscribe)]`.
+    #[doc(hidden)]
+    #[inline(always)]
+    pub async fn acquire_with(lock: &'a RwLock<T>, hook: P) -> Self
+    where
+        M: LockMode,
+    {
+        let inner = FieldGuardInner::acquire::<M>(lock).await;
+        Self {
+            hook: hook.rearm(M::MODE == LockModeKind::Write),
+            inner,
+            _mode: PhantomData,
+        }
+    }
+
+    /// Like [`acquire_with`](Self::acquire_with), blocking the current thread
+    /// instead of awaiting. Used by the generated `*_blocking()` accessors.
+    #[doc(hidden)]
+    #[inline(always)]
+    pub fn acquire_with_blocking(lock: &'a RwLock<T>, hook: P) -> Self
+    where
+        M: LockMode,
+    {
+        let inner = FieldGuardInner::acquire_blocking::<M>(lock);
+        Self {
+            hook: hook.rearm(M::MODE == LockModeKind::Write),
+            inner,
+            _mode: PhantomData,
+        }
+    }
+
+    /// Like [`try_acquire`](FieldGuard::try_acquire), carrying `hook`, which is
+    /// armed if `M` is `WriteLocked`.
+    #[doc(hidden)]
+    #[inline(always)]
+    pub fn try_acquire_with(lock: &'a RwLock<T>, hook: P) -> Option<Self>
+    where
+        M: LockMode,
+    {
+        let inner = FieldGuardInner::try_acquire::<M>(lock)?;
+        Some(Self {
+            hook: hook.rearm(M::MODE == LockModeKind::Write),
+            inner,
+            _mode: PhantomData,
+        })
+    }
+
+    /// Like [`acquire_with`](Self::acquire_with), recording a `tracing` span for `site`.
+    #[cfg(feature = "tracing")]
+    #[doc(hidden)]
+    #[inline(always)]
+    pub async fn acquire_with_traced(lock: &'a RwLock<T>, hook: P, site: &'static FieldSite) -> Self
+    where
+        M: LockMode,
+    {
+        let inner = FieldGuardInner::acquire_traced::<M>(lock, site, None).await;
+        Self {
+            hook: hook.rearm(M::MODE == LockModeKind::Write),
+            inner,
+            _mode: PhantomData,
+        }
+    }
+
+    /// Like [`try_acquire_with`](Self::try_acquire_with), recording a `tracing`
+    /// span for `site`.
+    #[cfg(feature = "tracing")]
+    #[doc(hidden)]
+    #[inline(always)]
+    pub fn try_acquire_with_traced(
+        lock: &'a RwLock<T>,
+        hook: P,
+        site: &'static FieldSite,
+    ) -> Option<Self>
+    where
+        M: LockMode,
+    {
+        let inner = FieldGuardInner::try_acquire_traced::<M>(lock, site, None)?;
+        Some(Self {
+            hook: hook.rearm(M::MODE == LockModeKind::Write),
+            inner,
+            _mode: PhantomData,
+        })
+    }
+
+    /// Like [`acquire_with`](Self::acquire_with), counting in `contended` an
+    /// acquisition that has to wait. Used by `#[smart_lock(metrics)]`.
+    #[doc(hidden)]
+    #[inline(always)]
+    pub async fn acquire_with_counted(lock: &'a RwLock<T>, hook: P, contended: &AtomicU64) -> Self
+    where
+        M: LockMode,
+    {
+        let inner = FieldGuardInner::acquire_counted::<M>(lock, contended).await;
+        Self {
+            hook: hook.rearm(M::MODE == LockModeKind::Write),
+            inner,
+            _mode: PhantomData,
+        }
+    }
+
+    /// Like [`try_acquire_with`](Self::try_acquire_with), counting a failure in
+    /// `contended`. Used by `#[smart_lock(metrics)]`.
+    #[doc(hidden)]
+    #[inline(always)]
+    pub fn try_acquire_with_counted(
+        lock: &'a RwLock<T>,
+        hook: P,
+        contended: &AtomicU64,
+    ) -> Option<Self>
+    where
+        M: LockMode,
+    {
+        let inner = FieldGuardInner::try_acquire_counted::<M>(lock, contended)?;
+        Some(Self {
+            hook: hook.rearm(M::MODE == LockModeKind::Write),
+            inner,
+            _mode: PhantomData,
+        })
+    }
+
+    /// [`acquire_with_counted`](Self::acquire_with_counted) with a `tracing` span
+    /// for `site`.
+    #[cfg(feature = "tracing")]
+    #[doc(hidden)]
+    #[inline(always)]
+    pub async fn acquire_with_counted_traced(
+        lock: &'a RwLock<T>,
+        hook: P,
+        contended: &AtomicU64,
+        site: &'static FieldSite,
+    ) -> Self
+    where
+        M: LockMode,
+    {
+        let inner = FieldGuardInner::acquire_traced::<M>(lock, site, Some(contended)).await;
+        Self {
+            hook: hook.rearm(M::MODE == LockModeKind::Write),
+            inner,
+            _mode: PhantomData,
+        }
+    }
+
+    /// [`try_acquire_with_counted`](Self::try_acquire_with_counted) with a
+    /// `tracing` span for `site`.
+    #[cfg(feature = "tracing")]
+    #[doc(hidden)]
+    #[inline(always)]
+    pub fn try_acquire_with_counted_traced(
+        lock: &'a RwLock<T>,
+        hook: P,
+        contended: &AtomicU64,
+        site: &'static FieldSite,
+    ) -> Option<Self>
+    where
+        M: LockMode,
+    {
+        let inner = FieldGuardInner::try_acquire_traced::<M>(lock, site, Some(contended))?;
+        Some(Self {
+            hook: hook.rearm(M::MODE == LockModeKind::Write),
             inner,
             _mode: PhantomData,
         })
     }
 
-    /// Create a no-op guard for [`Unlocked`](crate::Unlocked) fields.
+    /// Whether the field's poison flag is set. Always `false` for guards
+    /// without poison tracking.
+    ///
+    /// An associated function (`FieldGuard::is_poisoned(&guard)`) so it doesn't
+    /// shadow an `is_poisoned` method on `T`, e.g. a `Mutex` field.
+    #[inline(always)]
+    pub fn is_poisoned(this: &Self) -> bool {
+        this.hook.is_poisoned()
+    }
+}
+
+impl<T, M, P> FieldGuard<'_, T, M, P> {
+    /// The guarded value if this guard holds any lock, or `None` for `Unlocked`.
+    ///
+    /// A runtime counterpart to `Deref` for code that is generic over `M`, such as
+    /// the generated `map_field`. It is an associated function so it doesn't
+    /// shadow methods on `T`.
+    #[inline(always)]
+    pub fn try_get(this: &Self) -> Option<&T> {
+        match &this.inner {
+            FieldGuardInner::Read(g) => Some(g),
+            FieldGuardInner::Write(g) => Some(g),
+            FieldGuardInner::Upgrade(g) => Some(g),
+            FieldGuardInner::None => None,
+        }
+    }
+
+    /// The guarded value mutably if this guard holds the write lock, otherwise
+    /// `None`. The mutable counterpart of [`try_get`](Self::try_get).
+    #[inline(always)]
+    pub fn try_get_mut(this: &mut Self) -> Option<&mut T> {
+        match &mut this.inner {
+            FieldGuardInner::Write(g) => Some(&mut *g),
+            _ => None,
+        }
+    }
+}
+
+impl<T> FieldGuard<'_, T, Unlocked> {
+    /// Create a no-op guard for [`Unlocked`] fields.
     ///
     /// Zero-cost: no lock is acquired. Attempting to dereference an unlocked
     /// guard is a compile error (neither [`Deref`] nor [`DerefMut`] is implemented
@@ -81,6 +560,7 @@ impl<'a, T, M> FieldGuard<'a, T, M> {
     #[inline(always)]
     pub fn unlocked() -> Self {
         Self {
+            hook: NoHook,
             inner: FieldGuardInner::None,
             _mode: PhantomData,
         }
@@ -88,14 +568,15 @@ impl<'a, T, M> FieldGuard<'a, T, M> {
 }
 
 // --- Upgrade: UpgradeLocked → WriteLocked (async, waits for readers to drain) ---
-impl<'a, T> FieldGuard<'a, T, UpgradeLocked> {
+impl<'a, T, M: Upgradable, P: GuardHook> FieldGuard<'a, T, M, P> {
     /// Atomically upgrade from upgradable read to exclusive write.
     ///
     /// Waits for all other readers to drain before granting write access.
     #[inline(always)]
-    pub async fn upgrade(self) -> FieldGuard<'a, T, WriteLocked> {
+    pub async fn upgrade(self) -> FieldGuard<'a, T, WriteLocked, P> {
         match self.inner {
             FieldGuardInner::Upgrade(g) => FieldGuard {
+                hook: self.hook.rearm(true),
                 inner: FieldGuardInner::Write(RwLockUpgradableReadGuard::upgrade(g).await),
                 _mode: PhantomData,
             },
@@ -104,19 +585,46 @@ impl<'a, T> FieldGuard<'a, T, UpgradeLocked> {
     }
 }
 
+// --- Upgrade to a target mode: as-is, or UpgradeLocked → WriteLocked ---
+impl<'a, T, M: LockMode, P: GuardHook> FieldGuard<'a, T, M, P> {
+    /// Move to mode `N`: unchanged when `N` is `M`, else [`upgrade`](FieldGuard::upgrade).
+    /// Used by `upgrade_subset!`.
+    #[doc(hidden)]
+    #[inline(always)]
+    pub async fn upgrade_to<N: LockMode>(self) -> FieldGuard<'a, T, N, P>
+    where
+        M: UpgradeTo<N>,
+    {
+        match self.inner {
+            FieldGuardInner::Upgrade(g) if N::MODE == LockModeKind::Write => FieldGuard {
+                hook: self.hook.rearm(true),
+                inner: FieldGuardInner::Write(RwLockUpgradableReadGuard::upgrade(g).await),
+                _mode: PhantomData,
+            },
+            inner => FieldGuard {
+                hook: self.hook,
+                inner,
+                _mode: PhantomData,
+            },
+        }
+    }
+}
+
 // --- Try upgrade: UpgradeLocked → WriteLocked (sync, non-blocking) ---
-impl<'a, T> FieldGuard<'a, T, UpgradeLocked> {
+impl<'a, T, M: Upgradable, P: GuardHook> FieldGuard<'a, T, M, P> {
     /// Try to upgrade from upgradable read to exclusive write without blocking.
     /// Returns `Ok(WriteLocked)` on success, `Err(self)` if readers are active.
     #[inline(always)]
-    pub fn try_upgrade(self) -> Result<FieldGuard<'a, T, WriteLocked>, Self> {
+    pub fn try_upgrade(self) -> Result<FieldGuard<'a, T, WriteLocked, P>, Self> {
         match self.inner {
             FieldGuardInner::Upgrade(g) => match RwLockUpgradableReadGuard::try_upgrade(g) {
                 Ok(write_guard) => Ok(FieldGuard {
+                    hook: self.hook.rearm(true),
                     inner: FieldGuardInner::Write(write_guard),
                     _mode: PhantomData,
                 }),
                 Err(upgrade_guard) => Err(FieldGuard {
+                    hook: self.hook,
                     inner: FieldGuardInner::Upgrade(upgrade_guard),
                     _mode: PhantomData,
                 }),
@@ -127,32 +635,66 @@ impl<'a, T> FieldGuard<'a, T, UpgradeLocked> {
 }
 
 // --- Downgrade: WriteLocked → ReadLocked (sync, atomic) ---
-impl<'a, T> FieldGuard<'a, T, WriteLocked> {
+impl<'a, T, P: GuardHook> FieldGuard<'a, T, WriteLocked, P> {
     /// Atomically downgrade from exclusive write to shared read.
     ///
     /// Immediately allows other readers. Synchronous — no `.await` needed.
     #[inline(always)]
-    pub fn downgrade(self) -> FieldGuard<'a, T, ReadLocked> {
+    pub fn downgrade(self) -> FieldGuard<'a, T, ReadLocked, P> {
         match self.inner {
             FieldGuardInner::Write(g) => FieldGuard {
+                hook: self.hook.rearm(false),
                 inner: FieldGuardInner::Read(RwLockWriteGuard::downgrade(g)),
                 _mode: PhantomData,
             },
             _ => unreachable!(),
         }
     }
+
+    /// Atomically downgrade from exclusive write back to upgradable read.
+    ///
+    /// Allows other readers while keeping the upgrade slot, so the guard can be
+    /// upgraded again later. Synchronous — no `.await` needed.
+    #[inline(always)]
+    pub fn downgrade_to_upgradable(self) -> FieldGuard<'a, T, UpgradeLocked, P> {
+        match self.inner {
+            FieldGuardInner::Write(g) => FieldGuard {
+                hook: self.hook.rearm(false),
+                inner: FieldGuardInner::Upgrade(RwLockWriteGuard::downgrade_to_upgradable(g)),
+                _mode: PhantomData,
+            },
+            _ => unreachable!(),
+        }
+    }
+
+    /// Undo an upgrade that wasn't used for writing: like
+    /// [`downgrade_to_upgradable`](Self::downgrade_to_upgradable), but without
+    /// reporting a write to the guard's hook.
+    #[doc(hidden)]
+    #[inline(always)]
+    pub fn cancel_upgrade(self) -> FieldGuard<'a, T, UpgradeLocked, P> {
+        match self.inner {
+            FieldGuardInner::Write(g) => FieldGuard {
+                hook: self.hook.cancel(),
+                inner: FieldGuardInner::Upgrade(RwLockWriteGuard::downgrade_to_upgradable(g)),
+                _mode: PhantomData,
+            },
+            _ => unreachable!(),
+        }
+    }
 }
 
 // --- Downgrade: UpgradeLocked → ReadLocked (sync, atomic) ---
-impl<'a, T> FieldGuard<'a, T, UpgradeLocked> {
+impl<'a, T, P: GuardHook> FieldGuard<'a, T, UpgradeLocked, P> {
     /// Atomically downgrade from upgradable read to shared read.
     ///
     /// Releases the upgrade slot, allowing other tasks to acquire upgradable locks.
     /// Synchronous — no `.await` needed.
     #[inline(always)]
-    pub fn downgrade(self) -> FieldGuard<'a, T, ReadLocked> {
+    pub fn downgrade(self) -> FieldGuard<'a, T, ReadLocked, P> {
         match self.inner {
             FieldGuardInner::Upgrade(g) => FieldGuard {
+                hook: self.hook.rearm(false),
                 inner: FieldGuardInner::Read(RwLockUpgradableReadGuard::downgrade(g)),
                 _mode: PhantomData,
             },
@@ -161,9 +703,134 @@ impl<'a, T> FieldGuard<'a, T, UpgradeLocked> {
     }
 }
 
+// --- Into the underlying async-lock guard ---
+//
+// Only for guards without a hook: the raw guard can't carry a `Poison` or
+// `Notify` hook, so converting a hooked guard would silently drop it.
+
+impl<'a, T> FieldGuard<'a, T, ReadLocked> {
+    /// Unwrap into the underlying [`RwLockReadGuard`], for APIs that expect the
+    /// native `async_lock` type. The read lock stays held.
+    #[inline(always)]
+    pub fn into_inner_guard(self) -> RwLockReadGuard<'a, T> {
+        match self.inner {
+            FieldGuardInner::Read(g) => g,
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl<'a, T> FieldGuard<'a, T, UpgradeLocked> {
+    /// Unwrap into the underlying [`RwLockUpgradableReadGuard`]. The upgradable
+    /// lock stays held and can be upgraded with `async_lock`'s own API.
+    #[inline(always)]
+    pub fn into_inner_guard(self) -> RwLockUpgradableReadGuard<'a, T> {
+        match self.inner {
+            FieldGuardInner::Upgrade(g) => g,
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl<'a, T> FieldGuard<'a, T, WriteLocked> {
+    /// Unwrap into the underlying [`RwLockWriteGuard`]. The write lock stays held.
+    #[inline(always)]
+    pub fn into_inner_guard(self) -> RwLockWriteGuard<'a, T> {
+        match self.inner {
+            FieldGuardInner::Write(g) => g,
+            _ => unreachable!(),
+        }
+    }
+}
+
+// --- Map: project a held guard into part of its value ---
+//
+// The mapped guard stores the projection as a function pointer and re-applies it
+// on each access. Storing the projected `&U` next to the lock guard (as
+// `MappedRwLockWriteGuard` does) would make the guard self-referential, which
+// needs `unsafe`; for the same reason a write mapping takes a shared and a
+// mutable projection rather than one `FnOnce(&mut T) -> &mut U`. Function
+// pointers can't capture, so the projection can't depend on outside state.
+// `try_map` keeps its fallible projections and re-applies those, which is why
+// `MappedFieldGuard` carries their error type `E`.
+//
+// `map` and `try_map` are associated functions so they don't shadow methods on `T`,
+// and `map` is a single one over every readable mode, since per-mode impls would
+// make the path `FieldGuard::map` ambiguous; `Readable::MapWith` picks its argument.
+
+impl<'a, T, M: Readable, P: GuardHook> FieldGuard<'a, T, M, P> {
+    /// Project the guard into a part of the value (an element, a nested member, ...)
+    /// without re-locking. The lock is held, in the same mode, until the mapped guard
+    /// is dropped; a mapped upgradable guard can't be upgraded.
+    ///
+    /// Read and upgradable guards take one non-capturing closure such as `|v| &v[3]`
+    /// or `|s| &s.inner`. Write guards take a shared and a mutable projection of the
+    /// same place, `(|v| &v[3], |v| &mut v[3])`, used for `Deref` and `DerefMut`
+    /// respectively. Projections are re-applied on each dereference.
+    ///
+    /// This is an associated function, called as `FieldGuard::map(guard, ...)`, so it
+    /// doesn't shadow a `map` method on `T`.
+    #[inline(always)]
+    pub fn map<U: ?Sized>(this: Self, f: M::MapWith<T, U>) -> MappedFieldGuard<'a, T, U, M, P> {
+        MappedFieldGuard {
+            guard: this,
+            project: M::projection(f),
+        }
+    }
+}
+
+impl<'a, T, P: GuardHook> FieldGuard<'a, T, WriteLocked, P> {
+    /// Like [`map`](Self::map), for projections that may fail, e.g. into one
+    /// variant of an enum: `|v| v.as_ref().ok_or(Missing)` and
+    /// `|v| v.as_mut().ok_or(Missing)`.
+    ///
+    /// Applies `f` and then `f_mut` once; if either fails, returns this guard,
+    /// still holding the write lock, together with the error. The mapped guard
+    /// re-applies the projections on each access and panics if they fail after
+    /// succeeding here, so they must not depend on anything but the guarded value.
+    ///
+    /// Called as `FieldGuard::try_map(guard, f, f_mut)`, like `map`.
+    #[inline(always)]
+    #[allow(clippy::type_complexity)]
+    pub fn try_map<U: ?Sized, E>(
+        mut this: Self,
+        f: fn(&T) -> Result<&U, E>,
+        f_mut: fn(&mut T) -> Result<&mut U, E>,
+    ) -> Result<MappedFieldGuard<'a, T, U, WriteLocked, P, E>, (Self, E)> {
+        if let Err(e) = f(&this) {
+            return Err((this, e));
+        }
+        match f_mut(&mut this).map(|_| ()) {
+            Ok(()) => Ok(MappedFieldGuard {
+                guard: this,
+                project: WriteProjection::Fallible(f, f_mut),
+            }),
+            Err(e) => Err((this, e)),
+        }
+    }
+}
+
+/// Projections of a mapped `WriteLocked` guard: from [`FieldGuard::map`], or
+/// fallible ones from [`FieldGuard::try_map`] that already succeeded once.
+#[doc(hidden)]
+pub enum WriteProjection<T, U: ?Sized, E> {
+    Infallible(fn(&T) -> &U, fn(&mut T) -> &mut U),
+    Fallible(fn(&T) -> Result<&U, E>, fn(&mut T) -> Result<&mut U, E>),
+}
+
+impl<T, U: ?Sized, E> Clone for WriteProjection<T, U, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, U: ?Sized, E> Copy for WriteProjection<T, U, E> {}
+
+const PROJECTION_FAILED: &str = "`try_map` projection failed after succeeding once";
+
 // --- Debug ---
 
-impl<T: fmt::Debug, M> fmt::Debug for FieldGuard<'_, T, M> {
+impl<T: fmt::Debug, M, P> fmt::Debug for FieldGuard<'_, T, M, P> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match &self.inner {
             FieldGuardInner::Read(g) => fmt::Debug::fmt(&**g, f),
@@ -174,9 +841,41 @@ impl<T: fmt::Debug, M> fmt::Debug for FieldGuard<'_, T, M> {
     }
 }
 
+// Generated guards format each field with `(&DebugField(&guard.x)).debug_field()`.
+// Method resolution tries `DebugFieldValue` first, which applies when the field's
+// type is known to be `Debug`, and otherwise autorefs to `DebugFieldOpaque`. A
+// guard is thus `Debug` whatever its fields are, and shows the fields it can.
+
+#[doc(hidden)]
+pub struct DebugField<'r, T>(pub &'r T);
+
+#[doc(hidden)]
+pub trait DebugFieldValue {
+    fn debug_field(&self) -> Option<&dyn fmt::Debug>;
+}
+
+impl<T: fmt::Debug> DebugFieldValue for DebugField<'_, T> {
+    #[inline(always)]
+    fn debug_field(&self) -> Option<&dyn fmt::Debug> {
+        Some(self.0)
+    }
+}
+
+#[doc(hidden)]
+pub trait DebugFieldOpaque {
+    fn debug_field(&self) -> Option<&dyn fmt::Debug>;
+}
+
+impl<T> DebugFieldOpaque for &DebugField<'_, T> {
+    #[inline(always)]
+    fn debug_field(&self) -> Option<&dyn fmt::Debug> {
+        None
+    }
+}
+
 // --- Deref: any Readable mode (ReadLocked, WriteLocked, UpgradeLocked) ---
 
-impl<T, M: Readable> Deref for FieldGuard<'_, T, M> {
+impl<T, M: Readable, P> Deref for FieldGuard<'_, T, M, P> {
     type Target = T;
     #[inline(always)]
     fn deref(&self) -> &T {
@@ -191,7 +890,7 @@ impl<T, M: Readable> Deref for FieldGuard<'_, T, M> {
 
 // --- DerefMut: WriteLocked only ---
 
-impl<T, M: Writable + Readable> DerefMut for FieldGuard<'_, T, M> {
+impl<T, M: Writable + Readable, P> DerefMut for FieldGuard<'_, T, M, P> {
     #[inline(always)]
     fn deref_mut(&mut self) -> &mut T {
         match &mut self.inner {
@@ -203,7 +902,7 @@ impl<T, M: Writable + Readable> DerefMut for FieldGuard<'_, T, M> {
 
 // --- Display: any Readable mode, forwards to T ---
 
-impl<T: fmt::Display, M: Readable> fmt::Display for FieldGuard<'_, T, M> {
+impl<T: fmt::Display, M: Readable, P> fmt::Display for FieldGuard<'_, T, M, P> {
     #[inline(always)]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         fmt::Display::fmt(&**self, f)
@@ -212,7 +911,7 @@ impl<T: fmt::Display, M: Readable> fmt::Display for FieldGuard<'_, T, M> {
 
 // --- AsRef: any Readable mode ---
 
-impl<T, M: Readable> AsRef<T> for FieldGuard<'_, T, M> {
+impl<T, M: Readable, P> AsRef<T> for FieldGuard<'_, T, M, P> {
     #[inline(always)]
     fn as_ref(&self) -> &T {
         self
@@ -225,7 +924,7 @@ impl<T, M: Readable> AsRef<T> for FieldGuard<'_, T, M> {
 // work — `String: PartialEq<&str>` is leveraged automatically.
 // Guard-to-guard comparison uses `*guard.x == *other_guard.x` via Deref.
 
-impl<T, U: ?Sized, M: Readable> PartialEq<U> for FieldGuard<'_, T, M>
+impl<T, U: ?Sized, M: Readable, P> PartialEq<U> for FieldGuard<'_, T, M, P>
 where
     T: PartialEq<U>,
 {
@@ -237,7 +936,7 @@ where
 
 // --- PartialOrd: forwards to T ---
 
-impl<T, U: ?Sized, M: Readable> PartialOrd<U> for FieldGuard<'_, T, M>
+impl<T, U: ?Sized, M: Readable, P> PartialOrd<U> for FieldGuard<'_, T, M, P>
 where
     T: PartialOrd<U>,
 {
@@ -249,9 +948,85 @@ where
 
 // --- Hash: forwards to T ---
 
-impl<T: std::hash::Hash, M: Readable> std::hash::Hash for FieldGuard<'_, T, M> {
+impl<T: std::hash::Hash, M: Readable, P> std::hash::Hash for FieldGuard<'_, T, M, P> {
     #[inline(always)]
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         (**self).hash(state)
     }
 }
+
+// --- MappedFieldGuard ---
+
+/// A [`FieldGuard`] projected into part of its value by [`FieldGuard::map`] or
+/// [`FieldGuard::try_map`].
+///
+/// Keeps the original lock held and preserves the mode marker `M`: a mapped
+/// `ReadLocked` or `UpgradeLocked` guard only implements `Deref`, a mapped
+/// `WriteLocked` guard also implements `DerefMut`. `E` is the error type of a
+/// `try_map` projection.
+pub struct MappedFieldGuard<'a, T, U: ?Sized, M: Readable, P = NoHook, E = Infallible> {
+    guard: FieldGuard<'a, T, M, P>,
+    project: M::Projection<T, U, E>,
+}
+
+impl<T, U: ?Sized, P> Deref for MappedFieldGuard<'_, T, U, ReadLocked, P> {
+    type Target = U;
+    #[inline(always)]
+    fn deref(&self) -> &U {
+        (self.project)(&self.guard)
+    }
+}
+
+impl<T, U: ?Sized, P> Deref for MappedFieldGuard<'_, T, U, UpgradeLocked, P> {
+    type Target = U;
+    #[inline(always)]
+    fn deref(&self) -> &U {
+        (self.project)(&self.guard)
+    }
+}
+
+impl<T, U: ?Sized, P, E> Deref for MappedFieldGuard<'_, T, U, WriteLocked, P, E> {
+    type Target = U;
+    #[inline(always)]
+    fn deref(&self) -> &U {
+        match self.project {
+            WriteProjection::Infallible(f, _) => f(&self.guard),
+            WriteProjection::Fallible(f, _) => {
+                f(&self.guard).unwrap_or_else(|_| panic!("{PROJECTION_FAILED}"))
+            }
+        }
+    }
+}
+
+impl<T, U: ?Sized, P, E> DerefMut for MappedFieldGuard<'_, T, U, WriteLocked, P, E> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut U {
+        match self.project {
+            WriteProjection::Infallible(_, f_mut) => f_mut(&mut self.guard),
+            WriteProjection::Fallible(_, f_mut) => {
+                f_mut(&mut self.guard).unwrap_or_else(|_| panic!("{PROJECTION_FAILED}"))
+            }
+        }
+    }
+}
+
+impl<T, U: ?Sized + fmt::Debug, M: Readable, P, E> fmt::Debug
+    for MappedFieldGuard<'_, T, U, M, P, E>
+where
+    Self: Deref<Target = U>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T, U: ?Sized + fmt::Display, M: Readable, P, E> fmt::Display
+    for MappedFieldGuard<'_, T, U, M, P, E>
+where
+    Self: Deref<Target = U>,
+{
+    #[inline(always)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
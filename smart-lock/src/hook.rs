@@ -0,0 +1,66 @@
+/// Sealing for [`GuardHook`]: each hook type implements `Sealed` in its own module.
+pub(crate) mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for super::NoHook {}
+    impl<A: Sealed, B: Sealed> Sealed for (A, B) {}
+}
+
+/// Per-guard state a [`FieldGuard`](crate::FieldGuard) carries in its `P`
+/// parameter and updates on every mode transition: [`NoHook`] (the default),
+/// [`Poison`](crate::Poison) for `#[smart_lock(poison)]`, `Notify` for
+/// `#[smart_lock(subscribe)]`, or a pair of the last two.
+///
+/// Hooks are *armed* while the guard is `WriteLocked` and act when an armed
+/// hook is dropped or disarmed. Sealed to the types above.
+pub trait GuardHook: sealed::Sealed {
+    /// The same hook, armed or disarmed for the guard's new mode.
+    #[doc(hidden)]
+    fn rearm(self, armed: bool) -> Self;
+
+    /// The same hook, disarmed without acting: for a write lock that is handed
+    /// back unused, such as a rolled-back `try_upgrade_all()`.
+    #[doc(hidden)]
+    fn cancel(self) -> Self;
+
+    /// Whether the hook tracks a poisoned field.
+    #[doc(hidden)]
+    #[inline(always)]
+    fn is_poisoned(&self) -> bool {
+        false
+    }
+}
+
+/// No per-guard state. Zero-sized, so a plain `FieldGuard` has the same layout
+/// as the underlying lock guard.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoHook;
+
+impl GuardHook for NoHook {
+    #[inline(always)]
+    fn rearm(self, _armed: bool) -> Self {
+        self
+    }
+
+    #[inline(always)]
+    fn cancel(self) -> Self {
+        self
+    }
+}
+
+impl<A: GuardHook, B: GuardHook> GuardHook for (A, B) {
+    #[inline(always)]
+    fn rearm(self, armed: bool) -> Self {
+        (self.0.rearm(armed), self.1.rearm(armed))
+    }
+
+    #[inline(always)]
+    fn cancel(self) -> Self {
+        (self.0.cancel(), self.1.cancel())
+    }
+
+    #[inline(always)]
+    fn is_poisoned(&self) -> bool {
+        self.0.is_poisoned() || self.1.is_poisoned()
+    }
+}
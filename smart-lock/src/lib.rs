@@ -6,7 +6,8 @@
 //! Annotate a struct with `#[smart_lock]` and get a type-safe builder that lets
 //! you select exactly which fields to lock and how (read, write, or upgradable).
 //! Unlocked fields produce **compile errors** on access, not runtime panics.
-//! Deadlock-free by construction — locks are always acquired in field declaration order.
+//! Deadlock-free by construction — locks are always acquired in a fixed order (field
+//! declaration order, or the keys given by `#[lock(order = N)]`).
 //!
 //! **Runtime-agnostic** — built on [`async-lock`](https://docs.rs/async-lock), works with tokio, async-std, smol,
 //! or any async runtime.
@@ -99,6 +100,32 @@
 //! # });
 //! ```
 //!
+//! # Owned guards for spawned tasks
+//!
+//! Regular guards borrow the lock. With `#[smart_lock(owned)]`, each locked field
+//! is stored as an `Arc<RwLock<T>>`, and the builder gains `lock_owned()`, which
+//! returns a `'static` guard of [`OwnedFieldGuard`]s that can be moved into
+//! `tokio::spawn`. There are also per-field `read_x_owned()` / `write_x_owned()`
+//! accessors:
+//!
+//! ```rust
+//! # use smart_lock::smart_lock;
+//! #[smart_lock(owned)]
+//! struct MyState { x: u32, y: u32 }
+//!
+//! # tokio_test::block_on(async {
+//! let state = MyStateLock::new(0, 1);
+//! let mut guard = state.builder().write_x().read_y().lock_owned().await;
+//! let task = async move {
+//!     *guard.x += *guard.y;
+//! };
+//! # task.await;
+//! # });
+//! ```
+//!
+//! Without the option, move an `Arc` clone of the lock into the task and lock
+//! inside it instead.
+//!
 //! # Self-synchronized Fields (`#[no_lock]`)
 //!
 //! Fields that are already internally synchronized don't need `RwLock` wrapping.
@@ -133,16 +160,156 @@
 //! | `FooLock` | Wrapper holding an `RwLock<T>` per field |
 //! | `FooLockBuilder` | Type-state builder for selecting lock modes |
 //! | `FooLockGuard` | Guard with per-field access encoded in the type system |
+//! | `FooReadGuard`, `FooWriteGuard`, `Foo<Field>WriteGuard` | Aliases for common guard shapes |
+//! | `FooFieldId` | Runtime identifier for each field, in declaration order |
+//!
+//! # Ordering across locks
+//!
+//! [`enforce_order!`] checks, in debug builds, that separate lock instances are
+//! always acquired in a consistent relative order, turning a cross-lock deadlock
+//! risk into a panic at test time. [`lock_all!`] goes further and acquires guards
+//! from several locks in one globally consistent (address) order.
+//!
+//! # Optional Features
+//!
+//! - **`serde`** — `Serialize` for `FooLock` (field-by-field blocking reads) and
+//!   `Deserialize` for `FooLock` whenever the original struct implements it.
+//! - **`tracing`** — a `smart_lock::acquire` trace span (lock, field, mode) around
+//!   every builder and `lock_all*` field acquisition, with a `wait_time` debug
+//!   event when the field was contended.
+//! - **`subscribe`** — enables `#[smart_lock(subscribe)]`: a `subscribe()`
+//!   method whose `ChangeReceiver` yields the `FooFieldId` of each field as its
+//!   write guard is released.
+//! - **`actor`** — enables `#[smart_lock(actor)]`: per-field `spawn_actor_*()`
+//!   methods returning a `FieldActor` whose Tokio task applies queued commands
+//!   to the field one at a time.
+//! - **`arbitrary`** — `arbitrary::Arbitrary` for `FooLock` whenever every field
+//!   implements it, for fuzzing code that takes the lock.
+//! - **`debug-deadlock`** — in debug builds, guard upgrades (`upgrade_*()`,
+//!   `upgrade_*_if()`, `lock_then_upgrade_*()`, `upgrade_all()`, `upgrade_subset!`)
+//!   panic if the guard still holds a field acquired after the one being
+//!   upgraded, the pattern behind upgrade deadlocks.
+//! - **`loom`** — for testing only: together with `RUSTFLAGS="--cfg loom"`, builds
+//!   the underlying `async-lock` primitives on [loom](https://docs.rs/loom) so the
+//!   builder's acquisition order can be model-checked (see `tests/loom.rs`).
 
+/// Per-field actor tasks for `#[smart_lock(actor)]`.
+#[cfg(feature = "actor")]
+mod actor;
+/// Compile-time `Send`/`Sync` assertion macros.
+mod assert;
+/// The lock primitive's acquisitions behind `FieldGuard` (`LockBackend`).
+mod backend;
+/// Change notifications for `#[smart_lock(subscribe)]`.
+#[cfg(feature = "subscribe")]
+mod changes;
 /// Per-field lock guard with type-level access modes.
 mod guard;
+/// Per-guard hooks run on mode transitions (`GuardHook`).
+mod hook;
+/// The `Lockable` and `Snapshot` traits implemented by generated locks.
+mod lockable;
+/// Per-field contention counters for `#[smart_lock(metrics)]`.
+mod metrics;
 /// Lock-mode marker types and traits.
 mod mode;
+/// Lock ordering across lock instances (`enforce_order!`, `lock_all!`).
+mod order;
+/// `'static` field guards for `#[smart_lock(owned)]`.
+mod owned;
+/// Opt-in poisoning for `#[smart_lock(poison)]`.
+mod poison;
 
-pub use async_lock::{RwLock, RwLockReadGuard, RwLockUpgradableReadGuard, RwLockWriteGuard};
-pub use guard::FieldGuard;
+#[cfg(feature = "actor")]
+pub use actor::{ActorStopped, FieldActor};
+pub use async_lock::{
+    RwLock, RwLockReadGuard, RwLockReadGuardArc, RwLockUpgradableReadGuard,
+    RwLockUpgradableReadGuardArc, RwLockWriteGuard, RwLockWriteGuardArc,
+};
+#[cfg(feature = "subscribe")]
+pub use changes::{ChangeReceiver, Notify};
+pub use guard::{FieldGuard, MappedFieldGuard};
+pub use hook::{GuardHook, NoHook};
+pub use lockable::{Lockable, Snapshot};
 pub use mode::{
-    DefaultRead, LockMode, LockModeKind, ReadLocked, Readable, Unlocked, UpgradeLocked, Writable,
-    WriteLocked,
+    DefaultRead, FieldView, LockMode, LockModeKind, ReadLocked, Readable, Unlocked, Upgradable,
+    UpgradeLocked, WithMode, Writable, WriteLocked,
 };
+pub use owned::OwnedFieldGuard;
+pub use poison::{Poison, PoisonFlag, Poisoned};
 pub use smart_lock_derive::smart_lock;
+/// Non-blocking, all-or-nothing lock of exactly the listed fields in the listed
+/// modes, without spelling out the builder chain.
+///
+/// `try_lock_spec!(lock, write: [a, b], read: [c], upgrade: [d])` expands to
+/// `lock.builder().write_a().write_b().read_c().upgrade_d().try_lock()`, so it
+/// returns `Option<FooLockGuard<..>>` and keeps every builder check: unknown or
+/// `#[no_lock]` fields and fields listed twice are compile errors. Groups may be
+/// given in any order, repeated, or left out.
+///
+/// ```rust
+/// use smart_lock::{smart_lock, try_lock_spec};
+///
+/// #[smart_lock]
+/// struct Wide { a: u32, b: u32, c: u32, d: u32 }
+///
+/// let state = WideLock::new(1, 2, 3, 4);
+/// let mut guard = try_lock_spec!(state, write: [a, b], read: [c]).unwrap();
+/// *guard.a += *guard.c;
+/// assert!(try_lock_spec!(&state, read: [a]).is_none());
+/// assert!(try_lock_spec!(&state, write: [d]).is_some());
+/// ```
+pub use smart_lock_derive::try_lock_spec;
+
+/// Upgrade a named subset of a guard's `UpgradeLocked` fields to `WriteLocked`,
+/// leaving every other field as it is.
+///
+/// `upgrade_subset!(guard, a, c).await` consumes the guard and upgrades the listed
+/// fields one at a time in acquisition order (declaration order, or the
+/// `#[lock(order = ..)]` order), whatever order they are listed in. Unlisted
+/// `UpgradeLocked` fields stay upgradable, so a later `upgrade_*()` can still promote
+/// them. Listing a field that is not `UpgradeLocked`, an unknown field, or a field
+/// twice is a compile error.
+///
+/// Like the generated `upgrade_all()`, each upgrade waits for the field's readers
+/// while holding every other field of the guard, so the per-field deadlock warning
+/// applies to each listed field. With the `debug-deadlock` feature, that makes
+/// this example panic: it upgrades `a` while holding `b` and `c`.
+///
+#[cfg_attr(not(feature = "debug-deadlock"), doc = "```rust")]
+#[cfg_attr(feature = "debug-deadlock", doc = "```rust,should_panic")]
+/// use smart_lock::{smart_lock, upgrade_subset};
+///
+/// #[smart_lock]
+/// struct Wide { a: u32, b: u32, c: u32 }
+///
+/// # pollster::block_on(async {
+/// let state = WideLock::new(1, 2, 3);
+/// let guard = state.lock_all_upgradable().await;
+/// let mut guard = upgrade_subset!(guard, c, a).await;
+/// *guard.a += *guard.b;
+/// *guard.c += *guard.b;
+/// assert!(state.try_read_b().is_some());
+/// # });
+/// ```
+pub use smart_lock_derive::upgrade_subset;
+
+/// Re-exports used by generated code. Not part of the public API.
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::assert::FieldSendSync;
+    #[cfg(feature = "subscribe")]
+    pub use crate::changes::ChangeHub;
+    #[cfg(feature = "debug-deadlock")]
+    pub use crate::guard::check_upgrade_order;
+    #[cfg(feature = "tracing")]
+    pub use crate::guard::FieldSite;
+    pub use crate::guard::{acquire_or, yield_now, DebugField, DebugFieldOpaque, DebugFieldValue};
+    pub use crate::metrics::ContentionCounters;
+    pub use crate::mode::{SubsetUpgradable, UpgradeTo};
+    pub use crate::order::{InstanceId, LockInstance, OrderSection};
+    #[cfg(feature = "arbitrary")]
+    pub use arbitrary;
+    #[cfg(feature = "serde")]
+    pub use serde;
+}
@@ -0,0 +1,88 @@
+use std::future::Future;
+
+/// Implemented by every generated `FooLock`, for code generic over the lock type.
+///
+/// Covers the whole-struct operations: [`lock_all`](Self::lock_all),
+/// [`lock_all_mut`](Self::lock_all_mut) and [`into_inner`](Self::into_inner). The
+/// guards are the all-read and all-write shapes (`FooReadGuard` / `FooWriteGuard`);
+/// bound them to use their contents generically:
+///
+/// ```
+/// use smart_lock::{smart_lock, Lockable};
+/// use std::fmt::Debug;
+///
+/// async fn dump<L: Lockable>(lock: &L) -> String
+/// where
+///     for<'a> L::ReadGuard<'a>: Debug,
+/// {
+///     format!("{:?}", lock.lock_all().await)
+/// }
+///
+/// #[smart_lock]
+/// struct Counter {
+///     hits: u64,
+/// }
+///
+/// # pollster::block_on(async {
+/// let lock = CounterLock::new(3);
+/// assert_eq!(dump(&lock).await, "CounterLockGuard { hits: 3 }");
+/// # });
+/// ```
+///
+/// The futures are not declared `Send`, since locks built with
+/// `#[smart_lock(no_send_assert)]` implement the trait too; bound on [`Snapshot`] for
+/// a future generic code can spawn, or spawn with the concrete lock type.
+pub trait Lockable: Sized {
+    /// The original struct, `Foo`.
+    type Inner;
+
+    /// The guard with every field `ReadLocked`.
+    type ReadGuard<'a>
+    where
+        Self: 'a;
+
+    /// The guard with every field `WriteLocked`.
+    type WriteGuard<'a>
+    where
+        Self: 'a;
+
+    /// Read-lock every field, like the generated `lock_all()`.
+    fn lock_all(&self) -> impl Future<Output = Self::ReadGuard<'_>>;
+
+    /// Write-lock every field, like the generated `lock_all_mut()`.
+    fn lock_all_mut(&self) -> impl Future<Output = Self::WriteGuard<'_>>;
+
+    /// Unwrap the lock into the original struct, like the generated `into_inner()`.
+    fn into_inner(self) -> Self::Inner;
+}
+
+/// Implemented by every generated `FooLock` whose fields are all `Clone + Send + Sync`,
+/// for taking a consistent copy from generic code that needs a `Send` future:
+///
+/// ```
+/// use smart_lock::{smart_lock, Snapshot};
+/// use std::sync::Arc;
+///
+/// async fn snapshot_in_task<L>(lock: Arc<L>) -> L::Inner
+/// where
+///     L: Snapshot + Send + Sync + 'static,
+///     L::Inner: Send + 'static,
+/// {
+///     tokio::spawn(async move { lock.snapshot().await }).await.unwrap()
+/// }
+///
+/// #[smart_lock]
+/// #[derive(Debug, PartialEq)]
+/// struct Counter {
+///     hits: u64,
+/// }
+///
+/// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+/// let lock = Arc::new(CounterLock::new(3));
+/// assert_eq!(snapshot_in_task(lock).await, Counter { hits: 3 });
+/// # });
+/// ```
+pub trait Snapshot: Lockable {
+    /// Clone every field under one `lock_all()`, like the generated `snapshot_all()`.
+    fn snapshot(&self) -> impl Future<Output = Self::Inner> + Send;
+}
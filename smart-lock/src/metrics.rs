@@ -0,0 +1,35 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Per-field contention counters stored in a lock by `#[smart_lock(metrics)]`,
+/// one per field in declaration order.
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct ContentionCounters<const N: usize>([AtomicU64; N]);
+
+impl<const N: usize> ContentionCounters<N> {
+    /// All counters at zero.
+    pub fn new() -> Self {
+        Self([(); N].map(|()| AtomicU64::new(0)))
+    }
+
+    /// The counter of field `index`, passed to the `*_counted` acquisitions.
+    #[inline(always)]
+    pub fn field(&self, index: usize) -> &AtomicU64 {
+        &self.0[index]
+    }
+
+    /// The current count of every field.
+    pub fn counts(&self) -> [u64; N] {
+        let mut counts = [0; N];
+        for (count, counter) in counts.iter_mut().zip(&self.0) {
+            *count = counter.load(Ordering::Relaxed);
+        }
+        counts
+    }
+}
+
+impl<const N: usize> Default for ContentionCounters<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
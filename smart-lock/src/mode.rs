@@ -1,3 +1,14 @@
+use crate::guard::FieldGuard;
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for super::Unlocked {}
+    impl Sealed for super::ReadLocked {}
+    impl Sealed for super::WriteLocked {}
+    impl Sealed for super::UpgradeLocked {}
+}
+
 /// Marker: field was not requested in the builder. No access available.
 pub struct Unlocked;
 
@@ -12,7 +23,8 @@ pub struct WriteLocked;
 pub struct UpgradeLocked;
 
 /// Runtime discriminant for [`LockMode`]. Used internally by [`FieldGuard`](crate::FieldGuard)
-/// to select the correct lock operation at acquire time.
+/// to select the correct lock operation at acquire time, and returned by generated
+/// guards' `mode_of()`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LockModeKind {
     /// No lock acquired.
@@ -28,9 +40,9 @@ pub enum LockModeKind {
 /// Associates a lock-mode marker type ([`Unlocked`], [`ReadLocked`], [`WriteLocked`],
 /// [`UpgradeLocked`]) with its runtime [`LockModeKind`] discriminant.
 ///
-/// This trait is sealed to the four built-in marker types and should not be
+/// This trait is sealed to the four built-in marker types and cannot be
 /// implemented by downstream crates.
-pub trait LockMode {
+pub trait LockMode: sealed::Sealed {
     /// The runtime discriminant for this lock mode.
     const MODE: LockModeKind;
 }
@@ -54,34 +66,136 @@ impl LockMode for UpgradeLocked {
 /// Implemented for lock modes that support reading (`ReadLocked`, `WriteLocked`, `UpgradeLocked`).
 ///
 /// Not implemented for `Unlocked` — accessing an unlocked field is a compile error.
+/// Sealed, like [`LockMode`].
 #[diagnostic::on_unimplemented(
     message = "cannot read from a field with `{Self}` access",
     note = "add `.read_field()` or `.write_field()` to the builder to lock this field"
 )]
-pub trait Readable {}
+pub trait Readable: sealed::Sealed {
+    /// How a [`MappedFieldGuard`](crate::MappedFieldGuard) in this mode stores its
+    /// projection: shared only, or shared and mutable (possibly fallible, from
+    /// `try_map`) for `WriteLocked`.
+    #[doc(hidden)]
+    type Projection<T, U: ?Sized, E>: Copy;
+
+    /// What [`FieldGuard::map`](crate::FieldGuard::map) takes in this mode: one
+    /// shared projection, or a `(shared, mutable)` pair for `WriteLocked`.
+    #[doc(hidden)]
+    type MapWith<T, U: ?Sized>;
+
+    #[doc(hidden)]
+    fn projection<T, U: ?Sized, E>(map_with: Self::MapWith<T, U>) -> Self::Projection<T, U, E>;
+}
 
-impl Readable for ReadLocked {}
-impl Readable for WriteLocked {}
-impl Readable for UpgradeLocked {}
+impl Readable for ReadLocked {
+    type Projection<T, U: ?Sized, E> = fn(&T) -> &U;
+    type MapWith<T, U: ?Sized> = fn(&T) -> &U;
+
+    #[inline(always)]
+    fn projection<T, U: ?Sized, E>(f: fn(&T) -> &U) -> fn(&T) -> &U {
+        f
+    }
+}
+impl Readable for WriteLocked {
+    type Projection<T, U: ?Sized, E> = crate::guard::WriteProjection<T, U, E>;
+    type MapWith<T, U: ?Sized> = (fn(&T) -> &U, fn(&mut T) -> &mut U);
+
+    #[inline(always)]
+    fn projection<T, U: ?Sized, E>(
+        (f, f_mut): (fn(&T) -> &U, fn(&mut T) -> &mut U),
+    ) -> crate::guard::WriteProjection<T, U, E> {
+        crate::guard::WriteProjection::Infallible(f, f_mut)
+    }
+}
+impl Readable for UpgradeLocked {
+    type Projection<T, U: ?Sized, E> = fn(&T) -> &U;
+    type MapWith<T, U: ?Sized> = fn(&T) -> &U;
+
+    #[inline(always)]
+    fn projection<T, U: ?Sized, E>(f: fn(&T) -> &U) -> fn(&T) -> &U {
+        f
+    }
+}
 
 /// Implemented only for `WriteLocked`.
 ///
 /// Not implemented for `ReadLocked` or `UpgradeLocked` — mutating a read-locked field is a compile error.
+/// Sealed, like [`LockMode`].
 #[diagnostic::on_unimplemented(
     message = "cannot write to a field with `{Self}` access",
     note = "use `.write_field()` instead of `.read_field()` to get mutable access"
 )]
-pub trait Writable {}
+pub trait Writable: sealed::Sealed {}
 
 impl Writable for WriteLocked {}
 
+/// Implemented only for `UpgradeLocked`: the mode a field must be locked in for
+/// `upgrade()` / `try_upgrade()`, and the generated guards' `upgrade_x()` family.
+///
+/// Not implemented for `ReadLocked` or `WriteLocked` — upgrading those is a compile
+/// error. Sealed, like [`LockMode`].
+#[diagnostic::on_unimplemented(
+    message = "cannot upgrade a field with `{Self}` access",
+    note = "only fields requested with `.upgrade_field()` in the builder can be upgraded"
+)]
+pub trait Upgradable: Readable {}
+
+impl Upgradable for UpgradeLocked {}
+
+/// What a generated guard's `map_all()` passes for a field in this mode: `&mut T`
+/// for `WriteLocked`, `&T` for `ReadLocked` and `UpgradeLocked`, and `()` for
+/// `Unlocked`.
+///
+/// Sealed, like [`LockMode`].
+pub trait FieldView: sealed::Sealed + Sized + 'static {
+    /// The field as seen by `map_all()`, borrowed for `'b`.
+    type Ref<'b, T: 'b>;
+
+    #[doc(hidden)]
+    fn view<'b, T, P>(guard: &'b mut FieldGuard<'_, T, Self, P>) -> Self::Ref<'b, T>;
+}
+
+impl FieldView for Unlocked {
+    type Ref<'b, T: 'b> = ();
+
+    #[inline(always)]
+    fn view<T, P>(_: &mut FieldGuard<'_, T, Self, P>) {}
+}
+
+impl FieldView for ReadLocked {
+    type Ref<'b, T: 'b> = &'b T;
+
+    #[inline(always)]
+    fn view<'b, T, P>(guard: &'b mut FieldGuard<'_, T, Self, P>) -> &'b T {
+        guard
+    }
+}
+
+impl FieldView for WriteLocked {
+    type Ref<'b, T: 'b> = &'b mut T;
+
+    #[inline(always)]
+    fn view<'b, T, P>(guard: &'b mut FieldGuard<'_, T, Self, P>) -> &'b mut T {
+        guard
+    }
+}
+
+impl FieldView for UpgradeLocked {
+    type Ref<'b, T: 'b> = &'b T;
+
+    #[inline(always)]
+    fn view<'b, T, P>(guard: &'b mut FieldGuard<'_, T, Self, P>) -> &'b T {
+        guard
+    }
+}
+
 /// Maps a lock mode to its "rest read" output for `lock_rest_read()`.
 ///
 /// - `Unlocked` → `ReadLocked` (fill the gap with a read lock)
 /// - `ReadLocked` → `ReadLocked` (identity)
 /// - `WriteLocked` → `WriteLocked` (preserve explicit write)
 /// - `UpgradeLocked` → `UpgradeLocked` (preserve explicit upgrade)
-pub trait DefaultRead {
+pub trait DefaultRead: sealed::Sealed {
     /// The lock mode to use when `lock_rest_read()` fills this field.
     type Output: LockMode;
 }
@@ -101,3 +215,41 @@ impl DefaultRead for WriteLocked {
 impl DefaultRead for UpgradeLocked {
     type Output = UpgradeLocked;
 }
+
+/// Implemented by generated builders for every locked field marker `Field` (from
+/// the generated `foo_field` module) whose slot is still `Unlocked`: sets that
+/// field to mode `M`. Called through the builder's `with_mode::<Field, M>()`, so
+/// code generic over the lock mode needs a single call site instead of choosing
+/// between `read_x()`, `write_x()` and `upgrade_x()`.
+#[diagnostic::on_unimplemented(
+    message = "`{Field}` cannot be set on `{Self}`",
+    note = "`with_mode` takes a locked field's marker from the generated `..._field` module, and each field can only be selected once"
+)]
+pub trait WithMode<Field, M: LockMode> {
+    /// The builder with `Field` set to `M`.
+    type Output;
+
+    #[doc(hidden)]
+    fn __with_mode(self) -> Self::Output;
+}
+
+/// `M: UpgradeTo<N>` when a field guard in mode `M` can become mode `N` by staying
+/// as it is or by upgrading (`UpgradeLocked` → `WriteLocked`). Used by
+/// [`upgrade_subset!`](crate::upgrade_subset).
+#[doc(hidden)]
+pub trait UpgradeTo<N>: LockMode {}
+
+impl<M: LockMode> UpgradeTo<M> for M {}
+impl UpgradeTo<WriteLocked> for UpgradeLocked {}
+
+/// Implemented for a field's `(current, target)` modes while
+/// [`upgrade_subset!`](crate::upgrade_subset) may still mark it for upgrade: held
+/// `UpgradeLocked` and not marked yet.
+#[doc(hidden)]
+#[diagnostic::on_unimplemented(
+    message = "`upgrade_subset!` cannot upgrade a field with `{Self}` access",
+    note = "each listed field must be locked with `.upgrade_field()` and listed once"
+)]
+pub trait SubsetUpgradable<Target>: sealed::Sealed {}
+
+impl SubsetUpgradable<UpgradeLocked> for UpgradeLocked {}
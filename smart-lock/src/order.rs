@@ -0,0 +1,308 @@
+use std::collections::{HashMap, HashSet};
+#[cfg(debug_assertions)]
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+
+/// A process-unique id for one lock instance, stored in every generated `FooLock`.
+///
+/// Ids are assigned lazily on first use by [`enforce_order!`](crate::enforce_order)
+/// and never reused, so a dropped lock can't be confused with a new one at the same
+/// address. Dropping the lock removes its node from the order graph. Zero-sized in
+/// release builds, where the check is compiled out.
+#[doc(hidden)]
+#[derive(Default)]
+pub struct InstanceId {
+    #[cfg(debug_assertions)]
+    id: AtomicU64,
+}
+
+impl InstanceId {
+    pub const fn new() -> Self {
+        Self {
+            #[cfg(debug_assertions)]
+            id: AtomicU64::new(0),
+        }
+    }
+
+    /// This instance's id, assigning the next free one on first call.
+    /// Always `0` in release builds.
+    pub fn get(&self) -> u64 {
+        #[cfg(debug_assertions)]
+        {
+            static NEXT: AtomicU64 = AtomicU64::new(1);
+            let id = self.id.load(Ordering::Relaxed);
+            if id != 0 {
+                return id;
+            }
+            let fresh = NEXT.fetch_add(1, Ordering::Relaxed);
+            match self
+                .id
+                .compare_exchange(0, fresh, Ordering::Relaxed, Ordering::Relaxed)
+            {
+                Ok(_) => fresh,
+                Err(assigned) => assigned,
+            }
+        }
+        #[cfg(not(debug_assertions))]
+        0
+    }
+}
+
+impl Drop for InstanceId {
+    fn drop(&mut self) {
+        #[cfg(debug_assertions)]
+        if *self.id.get_mut() != 0 {
+            order_graph().remove(*self.id.get_mut());
+        }
+    }
+}
+
+/// Identifies a lock instance. Implemented by every generated `FooLock`.
+///
+/// Called with method syntax from [`enforce_order!`](crate::enforce_order), so it
+/// auto-derefs through `Arc<FooLock>` and `&FooLock` to the lock itself.
+#[doc(hidden)]
+pub trait LockInstance {
+    fn lock_instance_id(&self) -> u64;
+
+    /// The lock's address: the global acquisition order used by [`lock_all!`](crate::lock_all).
+    /// Stable while the lock is borrowed, which every guard it hands out keeps it.
+    fn lock_address(&self) -> usize {
+        (self as *const Self).cast::<()>() as usize
+    }
+}
+
+/// Directed graph of "acquired before" edges between live lock instances,
+/// accumulated from every `enforce_order!` section.
+#[derive(Default)]
+struct OrderGraph {
+    after: HashMap<u64, HashSet<u64>>,
+}
+
+impl OrderGraph {
+    /// Whether `to` is reachable from `from`, i.e. some chain of earlier sections
+    /// acquired `from` before `to`.
+    fn reaches(&self, from: u64, to: u64) -> bool {
+        let mut stack = vec![from];
+        let mut seen = HashSet::new();
+        while let Some(node) = stack.pop() {
+            if node == to {
+                return true;
+            }
+            if seen.insert(node) {
+                stack.extend(self.after.get(&node).into_iter().flatten().copied());
+            }
+        }
+        false
+    }
+
+    /// Drop `id`, bridging its predecessors to its successors so orderings
+    /// established through it still hold.
+    #[cfg_attr(not(debug_assertions), allow(dead_code))]
+    fn remove(&mut self, id: u64) {
+        let successors = self.after.remove(&id).unwrap_or_default();
+        for later in self.after.values_mut() {
+            if later.remove(&id) {
+                later.extend(&successors);
+            }
+        }
+    }
+}
+
+fn order_graph() -> MutexGuard<'static, OrderGraph> {
+    static GRAPH: OnceLock<Mutex<OrderGraph>> = OnceLock::new();
+    GRAPH
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// The locks acquired so far by one `enforce_order!` section.
+#[doc(hidden)]
+#[derive(Default)]
+pub struct OrderSection {
+    held: Vec<(u64, &'static str)>,
+}
+
+impl OrderSection {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record that `name` (instance `id`) is about to be acquired after everything
+    /// already held by this section. Panics if earlier sections, directly or through
+    /// a chain of other locks, acquired it before any of them. No-op in release builds.
+    pub fn acquire(&mut self, id: u64, name: &'static str) {
+        if !cfg!(debug_assertions) {
+            return;
+        }
+        let inversion = {
+            let mut graph = order_graph();
+            let inversion = self
+                .held
+                .iter()
+                .find(|(earlier, _)| *earlier != id && graph.reaches(id, *earlier))
+                .map(|(_, earlier_name)| *earlier_name);
+            if inversion.is_none() {
+                for (earlier, _) in &self.held {
+                    if *earlier != id {
+                        graph.after.entry(*earlier).or_default().insert(id);
+                    }
+                }
+            }
+            inversion
+        };
+        if let Some(earlier_name) = inversion {
+            panic!(
+                "lock order violation: `{name}` is acquired after `{earlier_name}` here, \
+                 but earlier enforce_order! sections acquired them in the opposite order"
+            );
+        }
+        self.held.push((id, name));
+    }
+}
+
+/// Check, in debug builds, that distinct lock instances are always acquired in a
+/// consistent relative order.
+///
+/// The builder already makes acquisition within one `FooLock` deadlock-free, but
+/// nothing orders acquisitions *across* lock instances. Wrap multi-lock sections in
+/// `enforce_order!`: each acquisition is recorded process-wide per lock instance, and
+/// a section that acquires two locks in the opposite order of earlier sections panics
+/// before acquiring, turning a latent deadlock into a test failure. Orderings compose:
+/// after `a → b` and `b → c`, acquiring `a` after `c` panics too. In release builds
+/// only the acquisitions remain.
+///
+/// Each statement must be `let <pat> = <lock>.<calls>;`, where `<lock>` is an
+/// identifier or a parenthesized expression (a `FooLock`, `&FooLock`, or
+/// `Arc<FooLock>`) and `<calls>` is a method chain such as `lock_all().await` or
+/// `builder().write_x().lock().await`. The bindings stay in scope after the macro.
+///
+/// ```rust
+/// use smart_lock::smart_lock;
+///
+/// #[smart_lock]
+/// struct Account { balance: i64 }
+///
+/// # tokio_test::block_on(async {
+/// let from = AccountLock::new(100);
+/// let to = AccountLock::new(0);
+///
+/// smart_lock::enforce_order! {
+///     let mut a = from.lock_all_mut().await;
+///     let mut b = to.lock_all_mut().await;
+/// }
+/// *a.balance -= 10;
+/// *b.balance += 10;
+/// # });
+/// ```
+#[macro_export]
+macro_rules! enforce_order {
+    (@stmts $section:ident) => {};
+    (@stmts $section:ident
+        let $p:pat = $lock:tt $(. $seg:ident $(( $($args:tt)* ))?)+ ;
+        $($tail:tt)*
+    ) => {
+        $section.acquire(
+            {
+                use $crate::__private::LockInstance as _;
+                $lock.lock_instance_id()
+            },
+            ::core::stringify!($lock),
+        );
+        let $p = $lock $(. $seg $(( $($args)* ))?)+;
+        $crate::enforce_order!(@stmts $section $($tail)*);
+    };
+    (@stmts $section:ident $($invalid:tt)*) => {
+        ::core::compile_error!(
+            "enforce_order! expects statements of the form `let <pat> = <lock>.<method calls>;`"
+        );
+    };
+    ($($body:tt)*) => {
+        let mut __smart_lock_section = $crate::__private::OrderSection::new();
+        $crate::enforce_order!(@stmts __smart_lock_section $($body)*);
+    };
+}
+
+/// Acquire guards from several lock instances at once, in a globally consistent
+/// order, so concurrent `lock_all!` calls over overlapping locks can't deadlock.
+///
+/// Each entry is `(<lock> => <builder methods>)`, where `<lock>` is a reference to
+/// a `FooLock` (`&lock`, or `&arc` for an `Arc<FooLock>`) and the methods are the builder's mode selectors
+/// (`write_x`, `read_y`, `upgrade_z`, ...). Every entry becomes
+/// `<lock>.builder().<methods>().lock().await`, and the macro evaluates to a tuple
+/// of the resulting guards in entry order. Must be used in async code.
+///
+/// Locks are acquired in ascending address order regardless of the order they are
+/// listed in; within each lock the builder keeps its own field order. The same
+/// lock must not appear twice (that would wait on itself), and doing so panics.
+/// Up to 12 entries are supported.
+///
+/// ```rust
+/// use smart_lock::smart_lock;
+///
+/// #[smart_lock]
+/// struct Account { balance: i64, history: Vec<i64> }
+///
+/// #[smart_lock]
+/// struct Ledger { total: i64 }
+///
+/// # tokio_test::block_on(async {
+/// let account = AccountLock::new(100, vec![]);
+/// let ledger = LedgerLock::new(0);
+///
+/// let (mut a, mut l) = smart_lock::lock_all!(
+///     (&account => write_balance write_history),
+///     (&ledger => write_total),
+/// );
+/// *a.balance -= 10;
+/// a.history.push(-10);
+/// *l.total += 10;
+/// # });
+/// ```
+#[macro_export]
+macro_rules! lock_all {
+    // Pair each entry with the next positional index.
+    (@index [$($done:tt)*] [$idx:tt $($rest_idx:tt)*]
+        ($lock:expr => $($mode:ident)*) $(, $($tail:tt)*)?
+    ) => {
+        $crate::lock_all!(@index
+            [$($done)* ($idx, $lock, $($mode)*)]
+            [$($rest_idx)*]
+            $($($tail)*)?
+        )
+    };
+    (@index [$($done:tt)*] [] ($($entry:tt)*) $($tail:tt)*) => {
+        ::core::compile_error!("lock_all! supports at most 12 locks")
+    };
+    (@index [$(($idx:tt, $lock:expr, $($mode:ident)*))*] [$($unused:tt)*]) => {{
+        use $crate::__private::LockInstance as _;
+        let __locks = ($($lock,)*);
+        let __keys = [$(__locks.$idx.lock_address()),*];
+        let mut __order = [$($idx),*];
+        __order.sort_unstable_by_key(|&i: &usize| __keys[i]);
+        ::core::assert!(
+            __order.windows(2).all(|pair| __keys[pair[0]] != __keys[pair[1]]),
+            "lock_all! lists the same lock more than once"
+        );
+        let mut __guards = ($({ let _ = $idx; ::core::option::Option::None },)*);
+        for __next in __order {
+            $(
+                if __next == $idx {
+                    __guards.$idx = ::core::option::Option::Some(
+                        __locks.$idx.builder() $(.$mode())* .lock().await,
+                    );
+                }
+            )*
+        }
+        ($(__guards.$idx.unwrap(),)*)
+    }};
+    (@index $($invalid:tt)*) => {
+        ::core::compile_error!(
+            "lock_all! expects entries of the form `(<lock> => <builder methods>)`, e.g. `(&a => write_x read_y)`"
+        )
+    };
+    ($($entries:tt)+) => {
+        $crate::lock_all!(@index [] [0 1 2 3 4 5 6 7 8 9 10 11] $($entries)+)
+    };
+}
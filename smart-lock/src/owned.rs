@@ -0,0 +1,183 @@
+use async_lock::{RwLock, RwLockReadGuardArc, RwLockUpgradableReadGuardArc, RwLockWriteGuardArc};
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+
+use crate::mode::{
+    LockMode, LockModeKind, ReadLocked, Readable, Unlocked, UpgradeLocked, Writable, WriteLocked,
+};
+
+enum OwnedInner<T> {
+    Read(RwLockReadGuardArc<T>),
+    Write(RwLockWriteGuardArc<T>),
+    Upgrade(RwLockUpgradableReadGuardArc<T>),
+    None,
+}
+
+/// An owned counterpart of [`FieldGuard`](crate::FieldGuard) for `#[smart_lock(owned)]`.
+///
+/// Holds a clone of the field's `Arc<RwLock<T>>` instead of borrowing the lock,
+/// so it is `'static` whenever `T` is and can be moved into `tokio::spawn`. The
+/// access level is encoded in `M` exactly as for `FieldGuard`, with the same
+/// transitions (`upgrade`, `try_upgrade`, `downgrade`).
+pub struct OwnedFieldGuard<T, M> {
+    inner: OwnedInner<T>,
+    _mode: PhantomData<M>,
+}
+
+impl<T, M> OwnedFieldGuard<T, M> {
+    /// Acquire the lock selected by `M::MODE` on a clone of `lock`.
+    ///
+    /// For [`Unlocked`] fields, returns a no-op guard without touching the lock.
+    #[inline(always)]
+    pub async fn acquire(lock: &Arc<RwLock<T>>) -> Self
+    where
+        M: LockMode,
+    {
+        let inner = match M::MODE {
+            LockModeKind::Write => OwnedInner::Write(lock.write_arc().await),
+            LockModeKind::Read => OwnedInner::Read(lock.read_arc().await),
+            LockModeKind::Upgrade => OwnedInner::Upgrade(lock.upgradable_read_arc().await),
+            LockModeKind::None => OwnedInner::None,
+        };
+        Self {
+            inner,
+            _mode: PhantomData,
+        }
+    }
+
+    /// Try to acquire the lock selected by `M::MODE` without blocking.
+    ///
+    /// Returns `None` if the lock cannot be immediately acquired.
+    /// [`Unlocked`] fields always succeed (no lock touched).
+    #[inline(always)]
+    pub fn try_acquire(lock: &Arc<RwLock<T>>) -> Option<Self>
+    where
+        M: LockMode,
+    {
+        let inner = match M::MODE {
+            LockModeKind::Write => OwnedInner::Write(lock.try_write_arc()?),
+            LockModeKind::Read => OwnedInner::Read(lock.try_read_arc()?),
+            LockModeKind::Upgrade => OwnedInner::Upgrade(lock.try_upgradable_read_arc()?),
+            LockModeKind::None => OwnedInner::None,
+        };
+        Some(Self {
+            inner,
+            _mode: PhantomData,
+        })
+    }
+}
+
+impl<T> OwnedFieldGuard<T, Unlocked> {
+    /// Create a no-op guard for [`Unlocked`] fields.
+    #[inline(always)]
+    pub fn unlocked() -> Self {
+        Self {
+            inner: OwnedInner::None,
+            _mode: PhantomData,
+        }
+    }
+}
+
+impl<T> OwnedFieldGuard<T, UpgradeLocked> {
+    /// Atomically upgrade from upgradable read to exclusive write.
+    ///
+    /// Waits for all other readers to drain before granting write access.
+    #[inline(always)]
+    pub async fn upgrade(self) -> OwnedFieldGuard<T, WriteLocked> {
+        match self.inner {
+            OwnedInner::Upgrade(g) => OwnedFieldGuard {
+                inner: OwnedInner::Write(RwLockUpgradableReadGuardArc::upgrade(g).await),
+                _mode: PhantomData,
+            },
+            _ => unreachable!(),
+        }
+    }
+
+    /// Try to upgrade from upgradable read to exclusive write without blocking.
+    /// Returns `Ok(WriteLocked)` on success, `Err(self)` if readers are active.
+    #[inline(always)]
+    pub fn try_upgrade(self) -> Result<OwnedFieldGuard<T, WriteLocked>, Self> {
+        match self.inner {
+            OwnedInner::Upgrade(g) => match RwLockUpgradableReadGuardArc::try_upgrade(g) {
+                Ok(write_guard) => Ok(OwnedFieldGuard {
+                    inner: OwnedInner::Write(write_guard),
+                    _mode: PhantomData,
+                }),
+                Err(upgrade_guard) => Err(OwnedFieldGuard {
+                    inner: OwnedInner::Upgrade(upgrade_guard),
+                    _mode: PhantomData,
+                }),
+            },
+            _ => unreachable!(),
+        }
+    }
+
+    /// Atomically downgrade from upgradable read to shared read.
+    #[inline(always)]
+    pub fn downgrade(self) -> OwnedFieldGuard<T, ReadLocked> {
+        match self.inner {
+            OwnedInner::Upgrade(g) => OwnedFieldGuard {
+                inner: OwnedInner::Read(RwLockUpgradableReadGuardArc::downgrade(g)),
+                _mode: PhantomData,
+            },
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl<T> OwnedFieldGuard<T, WriteLocked> {
+    /// Atomically downgrade from exclusive write to shared read.
+    #[inline(always)]
+    pub fn downgrade(self) -> OwnedFieldGuard<T, ReadLocked> {
+        match self.inner {
+            OwnedInner::Write(g) => OwnedFieldGuard {
+                inner: OwnedInner::Read(RwLockWriteGuardArc::downgrade(g)),
+                _mode: PhantomData,
+            },
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl<T: fmt::Debug, M> fmt::Debug for OwnedFieldGuard<T, M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.inner {
+            OwnedInner::Read(g) => fmt::Debug::fmt(&**g, f),
+            OwnedInner::Write(g) => fmt::Debug::fmt(&**g, f),
+            OwnedInner::Upgrade(g) => fmt::Debug::fmt(&**g, f),
+            OwnedInner::None => f.write_str("<unlocked>"),
+        }
+    }
+}
+
+impl<T, M: Readable> Deref for OwnedFieldGuard<T, M> {
+    type Target = T;
+    #[inline(always)]
+    fn deref(&self) -> &T {
+        match &self.inner {
+            OwnedInner::Read(g) => g,
+            OwnedInner::Write(g) => g,
+            OwnedInner::Upgrade(g) => g,
+            OwnedInner::None => unreachable!(),
+        }
+    }
+}
+
+impl<T, M: Writable + Readable> DerefMut for OwnedFieldGuard<T, M> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut T {
+        match &mut self.inner {
+            OwnedInner::Write(g) => &mut *g,
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl<T: fmt::Display, M: Readable> fmt::Display for OwnedFieldGuard<T, M> {
+    #[inline(always)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
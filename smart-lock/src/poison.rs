@@ -0,0 +1,131 @@
+use std::error::Error;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::hook::{sealed, GuardHook};
+
+/// Per-field poison flag stored next to each `RwLock` by `#[smart_lock(poison)]`.
+///
+/// Set when a thread panics while holding the field's write guard, mirroring
+/// [`std::sync::RwLock`] poisoning. `async-lock` itself never poisons.
+#[derive(Debug, Default)]
+pub struct PoisonFlag(AtomicBool);
+
+impl PoisonFlag {
+    /// A fresh, unpoisoned flag.
+    pub const fn new() -> Self {
+        Self(AtomicBool::new(false))
+    }
+
+    /// Whether a write guard for this field was dropped during a panic.
+    #[inline]
+    pub fn is_poisoned(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Clear the flag, e.g. after restoring the field to a consistent state.
+    #[inline]
+    pub fn clear(&self) {
+        self.0.store(false, Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub(crate) fn poison(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Poison tracking for `#[smart_lock(poison)]` guards: sets the field's
+/// [`PoisonFlag`] if dropped during a panic while armed (i.e. while the guard
+/// is `WriteLocked`).
+///
+/// Kept as its own guard field so `FieldGuard` can still be destructured by its
+/// transitions; it is declared before the lock guard so the flag is set before
+/// the lock is released.
+pub struct Poison<'a> {
+    flag: &'a PoisonFlag,
+    armed: bool,
+}
+
+impl<'a> Poison<'a> {
+    /// A disarmed hook for `flag`; acquiring a `WriteLocked` guard arms it.
+    #[doc(hidden)]
+    #[inline(always)]
+    pub fn new(flag: &'a PoisonFlag) -> Self {
+        Self { flag, armed: false }
+    }
+}
+
+impl sealed::Sealed for Poison<'_> {}
+
+impl GuardHook for Poison<'_> {
+    #[inline(always)]
+    fn rearm(mut self, armed: bool) -> Self {
+        self.armed = armed;
+        self
+    }
+
+    #[inline(always)]
+    fn cancel(mut self) -> Self {
+        self.armed = false;
+        self
+    }
+
+    #[inline(always)]
+    fn is_poisoned(&self) -> bool {
+        self.flag.is_poisoned()
+    }
+}
+
+impl Drop for Poison<'_> {
+    #[inline(always)]
+    fn drop(&mut self) {
+        if self.armed && std::thread::panicking() {
+            self.flag.poison();
+        }
+    }
+}
+
+/// Error returned when write-locking a poisoned field with `#[smart_lock(poison)]`.
+///
+/// Like [`std::sync::PoisonError`], it carries the guard, so the caller can still
+/// inspect or repair the data with [`into_inner`](Self::into_inner).
+pub struct Poisoned<G> {
+    guard: G,
+}
+
+impl<G> Poisoned<G> {
+    /// Wrap a guard whose field is poisoned.
+    pub fn new(guard: G) -> Self {
+        Self { guard }
+    }
+
+    /// Take the guard anyway.
+    pub fn into_inner(self) -> G {
+        self.guard
+    }
+
+    /// Borrow the guard.
+    pub fn get_ref(&self) -> &G {
+        &self.guard
+    }
+
+    /// Mutably borrow the guard.
+    pub fn get_mut(&mut self) -> &mut G {
+        &mut self.guard
+    }
+}
+
+impl<G> fmt::Debug for Poisoned<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Poisoned").finish_non_exhaustive()
+    }
+}
+
+impl<G> fmt::Display for Poisoned<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("field poisoned: a thread panicked while holding its write lock")
+    }
+}
+
+impl<G> Error for Poisoned<G> {}
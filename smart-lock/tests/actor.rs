@@ -0,0 +1,69 @@
+#![cfg(feature = "actor")]
+
+use smart_lock::{smart_lock, ActorStopped};
+
+#[smart_lock(actor)]
+struct Counters {
+    hits: u64,
+    names: Vec<String>,
+    #[no_lock]
+    id: u32,
+}
+
+fn counters() -> CountersLock {
+    CountersLock::new(0, vec![], 1)
+}
+
+#[tokio::test]
+async fn commands_apply_in_order_and_queries_see_them() {
+    let state = counters();
+    let hits = state.spawn_actor_hits();
+    for _ in 0..10 {
+        hits.send(|n| *n += 1).await.unwrap();
+    }
+    assert_eq!(hits.query(|n| *n).await, Ok(10));
+    // The actor writes through the field's lock, so ordinary guards see it too.
+    assert_eq!(*state.read_hits().await, 10);
+    assert_eq!(state.id, 1);
+}
+
+#[tokio::test]
+async fn cloned_handles_share_one_actor() {
+    let state = counters();
+    let names = state.spawn_actor_names();
+    let tasks: Vec<_> = (0..4)
+        .map(|i| {
+            let names = names.clone();
+            tokio::spawn(async move { names.send(move |v| v.push(format!("t{i}"))).await })
+        })
+        .collect();
+    for task in tasks {
+        task.await.unwrap().unwrap();
+    }
+    let mut seen = names.query(Vec::clone).await.unwrap();
+    seen.sort();
+    assert_eq!(seen, ["t0", "t1", "t2", "t3"]);
+}
+
+#[tokio::test]
+async fn actor_waits_for_guards_held_elsewhere() {
+    let state = counters();
+    let hits = state.spawn_actor_hits();
+    let guard = state.builder().read_hits().lock().await;
+    hits.send(|n| *n = 5).await.unwrap();
+    tokio::task::yield_now().await;
+    assert_eq!(*guard.hits, 0);
+    drop(guard);
+    assert_eq!(hits.query(|n| *n).await, Ok(5));
+}
+
+#[tokio::test]
+async fn panicking_command_stops_the_actor() {
+    let state = counters();
+    let hits = state.spawn_actor_hits();
+    hits.send(|_| panic!("bad command")).await.unwrap();
+    assert_eq!(hits.query(|n| *n).await, Err(ActorStopped));
+    assert_eq!(hits.send(|n| *n += 1).await, Err(ActorStopped));
+    // The field itself is still usable.
+    assert_eq!(*state.read_hits().await, 0);
+}
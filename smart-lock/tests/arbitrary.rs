@@ -0,0 +1,55 @@
+#![cfg(feature = "arbitrary")]
+
+use arbitrary::{Arbitrary, Unstructured};
+use smart_lock::smart_lock;
+
+#[smart_lock]
+struct Fuzzed {
+    count: u32,
+    #[no_lock]
+    id: u8,
+    tags: Vec<u16>,
+}
+
+#[derive(Debug, PartialEq, Arbitrary)]
+struct Expected {
+    count: u32,
+    id: u8,
+    tags: Vec<u16>,
+}
+
+const BYTES: &[u8] = &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12];
+
+#[tokio::test]
+async fn generates_each_field_like_a_derived_impl() {
+    let state = FuzzedLock::arbitrary(&mut Unstructured::new(BYTES)).unwrap();
+    let expected = Expected::arbitrary(&mut Unstructured::new(BYTES)).unwrap();
+    let guard = state.lock_all().await;
+    assert_eq!(*guard.count, expected.count);
+    assert_eq!(*guard.id, expected.id);
+    assert_eq!(*guard.tags, expected.tags);
+}
+
+#[tokio::test]
+async fn take_rest_gives_the_remaining_input_to_the_last_field() {
+    let state = FuzzedLock::arbitrary_take_rest(Unstructured::new(BYTES)).unwrap();
+    let expected = Expected::arbitrary_take_rest(Unstructured::new(BYTES)).unwrap();
+    assert_eq!(*state.read_tags().await, expected.tags);
+    assert_eq!(*state.read_count().await, expected.count);
+}
+
+#[test]
+fn size_hint_matches_the_plain_struct() {
+    assert_eq!(FuzzedLock::size_hint(0), Expected::size_hint(0));
+}
+
+#[smart_lock]
+struct Generic<T: Send + Sync> {
+    value: T,
+}
+
+#[test]
+fn generic_fields_need_arbitrary() {
+    let state = GenericLock::<(u8, bool)>::arbitrary(&mut Unstructured::new(&[7, 1])).unwrap();
+    assert_eq!(state.into_inner().value, (7, true));
+}
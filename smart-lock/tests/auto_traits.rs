@@ -0,0 +1,47 @@
+//! Pins the auto-trait behavior of generated lock and guard types.
+
+use smart_lock::{smart_lock, ReadLocked, Unlocked, UpgradeLocked, WriteLocked};
+use std::sync::atomic::AtomicU32;
+
+#[smart_lock]
+struct State {
+    counter: u64,
+    name: String,
+    #[no_lock]
+    hits: AtomicU32,
+}
+
+smart_lock::assert_send!(
+    StateLockGuard<'static, ReadLocked, ReadLocked>,
+    StateLockGuard<'static, WriteLocked, WriteLocked>,
+    StateLockGuard<'static, UpgradeLocked, Unlocked>,
+);
+
+smart_lock::assert_sync!(StateLock, StateLockGuard<'static, ReadLocked, ReadLocked>);
+
+#[smart_lock(owned)]
+struct Shared<T: Send + Sync + 'static> {
+    items: Vec<T>,
+    total: u64,
+}
+
+smart_lock::assert_send!(
+    SharedLockGuard<'static, String, WriteLocked, ReadLocked>,
+    SharedLockOwnedGuard<String, ReadLocked, WriteLocked>,
+    SharedLockOwnedGuard<String, UpgradeLocked, Unlocked>,
+);
+
+// Guards of locks that aren't `Sync` must not be `Send`: see
+// `tests/ui/guard_not_send.rs`.
+
+#[tokio::test]
+async fn all_read_guard_is_send_across_spawn() {
+    let state: &'static StateLock =
+        Box::leak(Box::new(StateLock::new(1, "a".into(), AtomicU32::new(0))));
+    let handle = tokio::spawn(async move {
+        let guard = state.lock_all().await;
+        tokio::task::yield_now().await;
+        *guard.counter
+    });
+    assert_eq!(handle.await.unwrap(), 1);
+}
@@ -191,6 +191,25 @@ async fn upgrade_persists() {
     assert_eq!(*guard.counter, 99);
 }
 
+#[tokio::test]
+async fn upgrade_if_upgrades_only_when_predicate_holds() {
+    let state = MyStateLock::new(0, "x".into(), vec![]);
+    let guard = state.builder().upgrade_data().read_name().lock().await;
+
+    let guard = guard
+        .upgrade_data_if(|data| !data.is_empty())
+        .await
+        .unwrap_err();
+    // Declined: still upgradable, so other readers are admitted.
+    assert!(state.try_read_data().is_some());
+
+    let mut guard = guard.upgrade_data_if(Vec::is_empty).await.unwrap();
+    guard.data.push(1);
+    assert!(state.try_read_data().is_none());
+    drop(guard);
+    assert_eq!(*state.read_data().await, [1]);
+}
+
 #[tokio::test]
 async fn downgrade_write_to_read() {
     let state = MyStateLock::new(0, "hello".into(), vec![]);
@@ -218,6 +237,33 @@ async fn downgrade_upgrade_to_read() {
 }
 
 #[tokio::test]
+#[cfg_attr(
+    feature = "debug-deadlock",
+    ignore = "upgrades a field while holding one acquired after it"
+)]
+async fn downgrade_write_to_upgrade_keeps_the_upgrade_slot() {
+    let state = MyStateLock::new(0, "".into(), vec![]);
+
+    let mut guard = state.builder().write_counter().write_data().lock().await;
+    *guard.counter = 1;
+    let guard = guard.downgrade_counter_to_upgrade();
+    // Readers get in, but no other writer or upgradable reader does.
+    assert_eq!(*state.try_read_counter().unwrap(), 1);
+    assert!(state.try_write_counter().is_none());
+    assert!(state.builder().upgrade_counter().try_lock().is_none());
+
+    let mut guard = guard.upgrade_counter().await;
+    *guard.counter += 1;
+    guard.data.push(2);
+    drop(guard);
+    assert_eq!(*state.read_counter().await, 2);
+}
+
+#[tokio::test]
+#[cfg_attr(
+    feature = "debug-deadlock",
+    ignore = "upgrades a field while holding one acquired after it"
+)]
 async fn upgrade_with_other_fields() {
     let state = MyStateLock::new(0, "hello".into(), vec![1, 2]);
 
@@ -242,6 +288,202 @@ async fn upgrade_with_other_fields() {
     assert_eq!(*guard.data, vec![1, 2, 3]);
 }
 
+#[tokio::test]
+async fn lock_then_upgrade_combines_acquire_and_upgrade() {
+    let state = MyStateLock::new(1, "config".into(), vec![]);
+
+    let mut guard = state
+        .builder()
+        .read_name()
+        .upgrade_data()
+        .lock_then_upgrade_data()
+        .await;
+    guard.data.extend(guard.name.bytes());
+    assert_eq!(*guard.name, "config");
+    assert_eq!(*guard.data, b"config");
+
+    // Other readers can still share `name`, but `data` is exclusively held.
+    assert!(state.try_read_name().is_some());
+    assert!(state.try_read_data().is_none());
+    assert!(state.try_read_counter().is_some());
+}
+
+#[tokio::test]
+async fn lock_then_upgrade_waits_for_readers() {
+    let state = std::sync::Arc::new(MyStateLock::new(0, String::new(), vec![]));
+    let reader = state.read_counter().await;
+
+    let task = {
+        let state = state.clone();
+        tokio::spawn(async move {
+            let mut guard = state
+                .builder()
+                .upgrade_counter()
+                .lock_then_upgrade_counter()
+                .await;
+            *guard.counter += 1;
+        })
+    };
+    tokio::task::yield_now().await;
+    assert!(!task.is_finished());
+    drop(reader);
+    task.await.unwrap();
+    assert_eq!(*state.read_counter().await, 1);
+}
+
+#[tokio::test]
+#[cfg_attr(
+    feature = "debug-deadlock",
+    ignore = "upgrades a field while holding one acquired after it"
+)]
+async fn upgrade_all_write_locks_every_field() {
+    let state = MyStateLock::new(1, "a".into(), vec![]);
+    let guard = state
+        .builder()
+        .upgrade_counter()
+        .upgrade_name()
+        .upgrade_data()
+        .lock()
+        .await;
+    let mut guard = guard.upgrade_all().await;
+    *guard.counter += 1;
+    guard.name.push('b');
+    guard.data.push(3);
+    assert!(state.try_read_counter().is_none());
+    assert!(state.try_read_data().is_none());
+    drop(guard);
+    let guard = state.lock_all().await;
+    assert_eq!((*guard.counter, guard.name.as_str()), (2, "ab"));
+}
+
+#[tokio::test]
+#[cfg_attr(
+    feature = "debug-deadlock",
+    ignore = "upgrades a field while holding one acquired after it"
+)]
+async fn lock_all_upgradable_reads_then_upgrades_selectively() {
+    let state = MyStateLock::new(1, "a".into(), vec![]);
+    let guard = state.lock_all_upgradable().await;
+    assert!(state.try_read_name().is_some());
+    assert!(state.try_upgrade_name().is_none());
+    assert!(state.try_lock_all_upgradable().is_none());
+
+    let mut guard = guard.upgrade_counter().await;
+    *guard.counter += *guard.counter;
+    assert_eq!(guard.name.as_str(), "a");
+    assert!(state.try_read_counter().is_none());
+    drop(guard);
+
+    let guard = state.try_lock_all_upgradable().unwrap();
+    assert_eq!(*guard.counter, 2);
+    let reader = state.read_data().await;
+    assert!(state.try_lock_all_mut().is_none());
+    drop((guard, reader));
+}
+
+#[tokio::test]
+async fn try_upgrade_all_rolls_back_on_active_reader() {
+    let state = MyStateLock::new(0, String::new(), vec![]);
+    let guard = state
+        .builder()
+        .upgrade_counter()
+        .upgrade_name()
+        .upgrade_data()
+        .lock()
+        .await;
+    let reader = state.read_name().await;
+
+    // `counter` upgrades, `name` fails, so `counter` must be rolled back.
+    let guard = guard.try_upgrade_all().unwrap_err();
+    assert!(state.try_read_counter().is_some());
+    assert!(state.try_upgrade_counter().is_none());
+    assert_eq!(*guard.counter, 0);
+
+    drop(reader);
+    let mut guard = guard.try_upgrade_all().unwrap();
+    *guard.counter = 1;
+    assert!(state.try_read_counter().is_none());
+}
+
+#[tokio::test]
+#[cfg_attr(
+    feature = "debug-deadlock",
+    ignore = "upgrades a field while holding one acquired after it"
+)]
+async fn try_upgrade_all_keeps_no_lock_fields() {
+    let state = WithNoLockLock::new(0, AtomicU32::new(4), String::new());
+    let guard = state
+        .builder()
+        .upgrade_counter()
+        .upgrade_name()
+        .lock()
+        .await;
+    let reader = state.read_name().await;
+    let guard = guard.try_upgrade_all().unwrap_err();
+    assert_eq!(guard.synced.load(Ordering::Relaxed), 4);
+    drop(reader);
+    let mut guard = guard.upgrade_all().await;
+    guard.name.push_str("done");
+    assert_eq!(guard.synced.load(Ordering::Relaxed), 4);
+}
+
+#[tokio::test]
+#[cfg_attr(
+    feature = "debug-deadlock",
+    ignore = "upgrades a field while holding one acquired after it"
+)]
+async fn upgrade_subset_write_locks_only_the_listed_fields() {
+    let state = MyStateLock::new(1, "a".into(), vec![]);
+    let guard = state.lock_all_upgradable().await;
+    // Listed out of declaration order; upgraded in acquisition order anyway.
+    let mut guard = smart_lock::upgrade_subset!(guard, data, counter).await;
+    *guard.counter += 1;
+    guard.data.push(*guard.counter as u8);
+    assert_eq!(guard.name.as_str(), "a");
+    assert!(state.try_read_counter().is_none());
+    assert!(state.try_read_data().is_none());
+    assert!(state.try_read_name().is_some());
+    assert!(state.try_upgrade_name().is_none());
+
+    // The rest stays upgradable.
+    let mut guard = guard.upgrade_name().await;
+    guard.name.push('b');
+    drop(guard);
+    let guard = state.lock_all().await;
+    assert_eq!((*guard.counter, guard.name.as_str()), (2, "ab"));
+    assert_eq!(*guard.data, [2]);
+}
+
+#[tokio::test]
+async fn upgrade_subset_keeps_other_modes_and_no_lock_fields() {
+    let state = WithNoLockLock::new(0, AtomicU32::new(4), String::new());
+    let guard = state.builder().read_counter().upgrade_name().lock().await;
+    let mut guard: WithNoLockLockGuard<'_, smart_lock::ReadLocked, smart_lock::WriteLocked> =
+        smart_lock::upgrade_subset!(guard, name).await;
+    guard.name.push_str("done");
+    assert_eq!(*guard.counter, 0);
+    assert_eq!(guard.synced.load(Ordering::Relaxed), 4);
+    assert!(state.try_read_counter().is_some());
+}
+
+#[tokio::test]
+async fn downgrade_all_keeps_every_field_locked_for_reading() {
+    let state = WithNoLockLock::new(0, AtomicU32::new(4), String::new());
+    let mut guard = state.lock_all_mut().await;
+    *guard.counter = 1;
+    guard.name.push_str("ready");
+
+    let guard: WithNoLockReadGuard<'_> = guard.downgrade_all();
+    assert!(state.try_read_counter().is_some());
+    assert!(state.try_read_name().is_some());
+    assert!(state.try_write_counter().is_none());
+    assert!(state.try_write_name().is_none());
+    assert_eq!((*guard.counter, guard.name.as_str()), (1, "ready"));
+    assert_eq!(guard.synced.load(Ordering::Relaxed), 4);
+    drop(guard);
+    assert!(state.try_lock_all_mut().is_some());
+}
+
 #[tokio::test]
 async fn per_field_upgrade_accessor() {
     let state = MyStateLock::new(0, "test".into(), vec![]);
@@ -255,6 +497,110 @@ async fn per_field_upgrade_accessor() {
     assert!(guard.is_some());
 }
 
+// --- new_with ---
+
+#[tokio::test]
+async fn new_with_calls_closures_in_declaration_order() {
+    let calls = std::cell::RefCell::new(Vec::new());
+    let state = MyStateLock::new_with(
+        || {
+            calls.borrow_mut().push("counter");
+            1
+        },
+        || {
+            calls.borrow_mut().push("name");
+            "lazy".into()
+        },
+        || {
+            calls.borrow_mut().push("data");
+            vec![2]
+        },
+    );
+    assert_eq!(*calls.borrow(), ["counter", "name", "data"]);
+    let guard = state.lock_all().await;
+    assert_eq!((*guard.counter, guard.name.as_str()), (1, "lazy"));
+    drop(guard);
+
+    let state = WithNoLockLock::new_with(|| 1, || AtomicU32::new(2), String::new);
+    assert_eq!(state.synced.load(Ordering::Relaxed), 2);
+    let state = GenericStateLock::new_with(Vec::<u8>::new, || 3);
+    assert_eq!(*state.read_count().await, 3);
+}
+
+// --- #[lock(skip_new)] ---
+
+#[smart_lock]
+struct WithCache {
+    counter: u32,
+    #[lock(skip_new)]
+    cache: std::collections::HashMap<u32, String>,
+    #[lock(skip_new)]
+    #[boxed]
+    scratch: Box<[u8; 32]>,
+}
+
+#[tokio::test]
+async fn skip_new_fields_start_at_default_and_stay_lockable() {
+    let state = WithCacheLock::new(7);
+    {
+        let mut guard = state.lock_all_mut().await;
+        assert!(guard.cache.is_empty() && *guard.scratch == [0; 32]);
+        guard.cache.insert(*guard.counter, "seven".into());
+        guard.scratch[0] = 1;
+    }
+    assert_eq!(state.read_cache().await[&7], "seven");
+
+    let state = WithCacheLock::new_with(|| 8);
+    assert_eq!(*state.read_counter().await, 8);
+    assert!(state.read_cache().await.is_empty());
+
+    // `from_parts` still takes every locked field.
+    let state = WithCacheLock::from_parts(
+        smart_lock::RwLock::new(1),
+        smart_lock::RwLock::new([(1, "one".to_string())].into()),
+        smart_lock::RwLock::new([2; 32]),
+    );
+    let inner = state.into_inner();
+    assert_eq!((inner.cache[&1].as_str(), inner.scratch[0]), ("one", 2));
+}
+
+// --- from_parts / FromIterator ---
+
+#[tokio::test]
+async fn from_parts_takes_prebuilt_locks() {
+    use smart_lock::RwLock;
+
+    let name = RwLock::new(String::from("prebuilt"));
+    name.write().await.push('!');
+    let state = MyStateLock::from_parts(RwLock::new(1), name, RwLock::new(vec![2]));
+    let guard = state.lock_all().await;
+    assert_eq!(
+        (*guard.counter, guard.name.as_str(), guard.data.as_slice()),
+        (1, "prebuilt!", &[2][..])
+    );
+    drop(guard);
+
+    // `#[no_lock]` fields take their value, `#[boxed]` ones the unboxed lock.
+    let state = WithNoLockLock::from_parts(RwLock::new(1), AtomicU32::new(2), RwLock::default());
+    assert_eq!(state.synced.load(Ordering::Relaxed), 2);
+    let state = WithBoxedLock::from_parts(RwLock::default(), RwLock::new(Box::new(3)));
+    assert_eq!(**state.read_plain().await, 3);
+}
+
+#[smart_lock]
+struct Bag<T: Send + Sync> {
+    items: Vec<T>,
+}
+
+#[tokio::test]
+async fn single_collection_lock_collects_from_an_iterator() {
+    let bag: BagLock<u32> = (1..=3).collect();
+    assert_eq!(*bag.read_items().await, [1, 2, 3]);
+
+    let tags: BagLock<char> = "abc".chars().collect();
+    assert_eq!(tags.into_inner().items, ['a', 'b', 'c']);
+}
+
 // --- into_inner ---
 
 #[tokio::test]
@@ -282,6 +628,235 @@ async fn into_inner_after_mutation() {
     assert_eq!(original.name, "changed");
 }
 
+#[tokio::test]
+async fn try_unwrap_inner_returns_the_arc_while_shared() {
+    use std::sync::Arc;
+    let state = Arc::new(MyStateLock::new(1, "shared".into(), vec![]));
+    let other = Arc::clone(&state);
+
+    let Err(state) = MyStateLock::try_unwrap_inner(state) else {
+        panic!("unwrapped while another Arc was alive");
+    };
+    assert_eq!(*state.read_counter().await, 1);
+    drop(other);
+
+    let Ok(original) = MyStateLock::try_unwrap_inner(state) else {
+        panic!("the last Arc should unwrap");
+    };
+    assert_eq!(original.name, "shared");
+}
+
+// --- snapshot_* ---
+
+#[tokio::test]
+async fn snapshot_clones_out_and_releases_the_lock() {
+    let state = MyStateLock::new(3, "config".into(), vec![1]);
+    let name: String = state.snapshot_name().await;
+    assert_eq!(name, "config");
+    // Nothing is held, so a writer gets straight in.
+    state.try_write_name().unwrap().push('!');
+    assert_eq!(name, "config");
+
+    let copy: MyState = state.snapshot_all().await;
+    assert!(state.try_lock_all_mut().is_some());
+    assert_eq!(
+        (copy.counter, copy.name, copy.data),
+        (3, "config!".into(), vec![1])
+    );
+}
+
+#[tokio::test]
+async fn to_owned_clones_a_readable_guard_into_the_struct() {
+    let state = MyStateLock::new(5, "owned".into(), vec![2]);
+    let guard = state
+        .builder()
+        .read_counter()
+        .upgrade_name()
+        .write_data()
+        .lock()
+        .await;
+    let MyState {
+        counter,
+        name,
+        data,
+    } = guard.to_owned();
+    assert_eq!((counter, name.as_str(), data), (5, "owned", vec![2]));
+    // The guard still holds its locks.
+    assert!(state.try_write_counter().is_none());
+    drop(guard);
+    assert!(state.try_lock_all_mut().is_some());
+}
+
+#[tokio::test]
+async fn snapshot_needs_clone_only_for_that_field() {
+    // `synced` is an `AtomicU32`, which isn't `Clone`: `snapshot_all()` is
+    // unavailable, but the per-field snapshots of other fields still work.
+    let state = WithNoLockLock::new(1, AtomicU32::new(0), "n".into());
+    assert_eq!(state.snapshot_counter().await, 1);
+    assert_eq!(state.snapshot_name().await, "n");
+}
+
+#[tokio::test]
+async fn hash_snapshot_hashes_fields_in_declaration_order() {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    async fn hash_of(state: &TaggedLock) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        state.hash_snapshot(&mut hasher).await;
+        hasher.finish()
+    }
+
+    let a = TaggedLock::new("t", 1, vec!["x".into()]);
+    let b = TaggedLock::new("t", 1, vec!["x".into()]);
+    assert_eq!(hash_of(&a).await, hash_of(&b).await);
+
+    let mut expected = DefaultHasher::new();
+    ("t", 1u32, vec!["x".to_string()]).hash(&mut expected);
+    assert_eq!(hash_of(&a).await, expected.finish());
+
+    *b.write_count().await = 2;
+    assert_ne!(hash_of(&a).await, hash_of(&b).await);
+}
+
+// --- replace_all ---
+
+#[tokio::test]
+async fn replace_all_swaps_every_field_and_returns_the_old_ones() {
+    let state = MyStateLock::new(1, "old".into(), vec![1]);
+    let new = || MyState {
+        counter: 2,
+        name: "new".into(),
+        data: vec![2],
+    };
+    // Nothing is replaced until every field is write-locked: cancelled while
+    // waiting for a reader of `name`, it leaves `counter` untouched too.
+    let reader = state.read_name().await;
+    let wait = std::time::Duration::from_millis(20);
+    assert!(tokio::time::timeout(wait, state.replace_all(new()))
+        .await
+        .is_err());
+    drop(reader);
+    assert_eq!(*state.read_counter().await, 1);
+
+    let replaced = state.replace_all(new()).await;
+    assert_eq!(
+        (
+            replaced.counter,
+            replaced.name.as_str(),
+            replaced.data.as_slice()
+        ),
+        (1, "old", [1].as_slice())
+    );
+
+    let rotated = state.replace_all(replaced).await.name;
+    assert_eq!(rotated, "new");
+    assert_eq!(state.into_inner().name, "old");
+}
+
+#[tokio::test]
+async fn replace_all_unboxes_boxed_fields() {
+    let state = WithBoxedLock::new(Box::default(), Box::new(1));
+    let label = String::from("big");
+    let old = state
+        .replace_all(WithBoxed {
+            big: Box::new(Big {
+                samples: [0; 32],
+                label,
+            }),
+            plain: Box::new(2),
+        })
+        .await;
+    assert_eq!((old.big.label.as_str(), *old.plain), ("", 1));
+    assert_eq!(state.read_big().await.label, "big");
+}
+
+// --- compare_and_set_* ---
+
+#[tokio::test]
+async fn compare_and_set_sets_only_on_match() {
+    let state = MyStateLock::new(1, "a".into(), vec![]);
+    assert!(!state.compare_and_set_counter(0, 5).await);
+    assert_eq!(*state.read_counter().await, 1);
+    assert!(state.compare_and_set_counter(1, 5).await);
+    assert_eq!(*state.read_counter().await, 5);
+    assert!(state.compare_and_set_name("a".into(), "b".into()).await);
+    assert_eq!(*state.read_name().await, "b");
+}
+
+#[tokio::test]
+async fn compare_and_set_lets_one_racer_win() {
+    let state = std::sync::Arc::new(MyStateLock::new(0, String::new(), vec![]));
+    let tasks: Vec<_> = (1..=8)
+        .map(|i| {
+            let state = std::sync::Arc::clone(&state);
+            tokio::spawn(async move { state.compare_and_set_counter(0, i).await })
+        })
+        .collect();
+    let mut winners = 0;
+    for task in tasks {
+        winners += task.await.unwrap() as u32;
+    }
+    assert_eq!(winners, 1);
+    assert_ne!(*state.read_counter().await, 0);
+}
+
+// --- modify_* / fetch_modify_* ---
+
+#[tokio::test]
+async fn modify_applies_closure_under_write_lock() {
+    let state = MyStateLock::new(1, "a".into(), vec![]);
+    state.modify_counter(|c| *c += 1).await;
+    state.modify_data(|d| d.push(7)).await;
+    assert_eq!(*state.read_counter().await, 2);
+    assert_eq!(*state.read_data().await, vec![7]);
+    assert!(state.try_write_counter().is_some());
+}
+
+#[tokio::test]
+async fn fetch_modify_returns_previous_value() {
+    let state = MyStateLock::new(1, "a".into(), vec![]);
+    assert_eq!(state.fetch_modify_counter(|c| *c *= 10).await, 1);
+    assert_eq!(state.fetch_modify_name(|n| n.push('b')).await, "a");
+    assert_eq!(*state.read_counter().await, 10);
+    assert_eq!(*state.read_name().await, "ab");
+}
+
+// --- *_blocking ---
+
+struct FlushOnDrop<'a> {
+    state: &'a MyStateLock,
+}
+
+impl Drop for FlushOnDrop<'_> {
+    fn drop(&mut self) {
+        let pending = self.state.read_data_blocking().len();
+        *self.state.write_counter_blocking() += pending as u32;
+    }
+}
+
+#[test]
+fn blocking_accessors_work_outside_async() {
+    let state = MyStateLock::new(0, String::new(), vec![1, 2]);
+    drop(FlushOnDrop { state: &state });
+    assert_eq!(*state.read_counter_blocking(), 2);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn blocking_write_waits_for_async_readers() {
+    let state = std::sync::Arc::new(MyStateLock::new(0, String::new(), vec![]));
+    let reader = state.read_counter().await;
+    let writer = {
+        let state = std::sync::Arc::clone(&state);
+        std::thread::spawn(move || *state.write_counter_blocking() = 1)
+    };
+    tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    assert_eq!(*reader, 0);
+    drop(reader);
+    writer.join().unwrap();
+    assert_eq!(*state.read_counter().await, 1);
+}
+
 // --- get_mut ---
 
 #[tokio::test]
@@ -296,8 +871,117 @@ async fn get_mut_per_field() {
     assert_eq!(*guard.data, vec![1]);
 }
 
+#[tokio::test]
+async fn take_and_replace_per_field() {
+    let mut state = MyStateLock::new(7, "hello".into(), vec![1, 2]);
+
+    assert_eq!(state.take_data(), vec![1, 2]);
+    assert_eq!(state.replace_name("world".into()), "hello");
+    assert_eq!(state.take_counter(), 7);
+
+    let guard = state.lock_all().await;
+    assert_eq!(*guard.counter, 0);
+    assert_eq!(*guard.name, "world");
+    assert!(guard.data.is_empty());
+}
+
+// `take_*` needs `Default`, but only on that method: the rest still expands.
+#[smart_lock]
+struct NoDefault {
+    started: std::time::Instant,
+}
+
+#[test]
+fn replace_without_default() {
+    let start = std::time::Instant::now();
+    let mut state = NoDefaultLock::new(start);
+    let later = start + std::time::Duration::from_secs(1);
+    assert_eq!(state.replace_started(later), start);
+    assert_eq!(*state.get_mut_started(), later);
+}
+
 // --- relock ---
 
+/// Generic over the guard's modes, so it can only check write access at runtime.
+fn bump_counter<F0, F1, F2>(guard: &mut MyStateLockGuard<'_, F0, F1, F2>) -> bool
+where
+    F0: smart_lock::LockMode,
+    F1: smart_lock::LockMode,
+    F2: smart_lock::LockMode,
+{
+    match guard.write_ref_counter() {
+        Some(counter) => {
+            *counter += 1;
+            true
+        }
+        None => false,
+    }
+}
+
+#[tokio::test]
+async fn write_ref_is_some_only_for_write_locked_fields() {
+    let state = MyStateLock::new(0, "n".into(), vec![]);
+    let mut guard = state.builder().write_counter().read_name().lock().await;
+    assert!(guard.write_ref_counter().is_some());
+    assert!(guard.write_ref_name().is_none());
+    assert!(guard.write_ref_data().is_none());
+    assert!(bump_counter(&mut guard));
+    assert_eq!(*guard.counter, 1);
+
+    let mut guard = guard.relock().upgrade_counter().lock().await;
+    assert!(!bump_counter(&mut guard));
+    let mut guard = guard.upgrade_counter().await;
+    assert!(bump_counter(&mut guard));
+    assert_eq!(*guard.counter, 2);
+}
+
+/// Generic over the field's mode: works whether `name` is unlocked, read, or written.
+fn name_len<M>(name: &smart_lock::FieldGuard<'_, String, M>) -> Option<usize> {
+    smart_lock::FieldGuard::try_get(name).map(String::len)
+}
+
+#[tokio::test]
+async fn field_guard_try_get_checks_the_mode_at_runtime() {
+    use smart_lock::FieldGuard;
+
+    let state = MyStateLock::new(0, "abc".into(), vec![]);
+    let mut guard = state.builder().write_counter().lock().await;
+    assert_eq!(name_len(&guard.name), None);
+    assert!(FieldGuard::try_get_mut(&mut guard.name).is_none());
+    *FieldGuard::try_get_mut(&mut guard.counter).unwrap() = 3;
+    drop(guard);
+
+    let mut guard = state.builder().upgrade_name().lock().await;
+    assert_eq!(name_len(&guard.name), Some(3));
+    assert!(FieldGuard::try_get_mut(&mut guard.name).is_none());
+    let mut guard = guard.upgrade_name().await;
+    FieldGuard::try_get_mut(&mut guard.name).unwrap().push('d');
+    assert_eq!(name_len(&guard.name), Some(4));
+    assert_eq!(FieldGuard::try_get(&guard.counter), None);
+}
+
+async fn counter_for_update(state: &MyStateLock) -> MyStateCounterWriteGuard<'_> {
+    state.builder().write_counter().lock().await
+}
+
+fn total_len(guard: &MyStateReadGuard<'_>) -> usize {
+    guard.name.len() + guard.data.len()
+}
+
+#[tokio::test]
+async fn guard_aliases_name_common_shapes() {
+    let state = MyStateLock::new(0, "ab".into(), vec![1]);
+    let mut guard = counter_for_update(&state).await;
+    *guard.counter += 1;
+    drop(guard);
+
+    let guard: MyStateReadGuard<'_> = state.lock_all().await;
+    assert_eq!(total_len(&guard), 3);
+    drop(guard);
+    let guard: MyStateWriteGuard<'_> = state.lock_all_mut().await;
+    assert_eq!(*guard.counter, 1);
+}
+
 #[tokio::test]
 async fn relock_drops_and_rebuilds() {
     let state = MyStateLock::new(0, "hello".into(), vec![]);
@@ -384,42 +1068,248 @@ async fn generic_struct_relock() {
     assert_eq!(*guard.count, 5);
 }
 
-// --- Attribute passthrough (doc comments) ---
-
 #[smart_lock]
-struct DocStruct {
-    /// This is a documented field
-    x: u32,
-    y: u32,
+#[preset(fill = "write_data, write_len")]
+struct Buffer<const N: usize> {
+    data: [u8; N],
+    len: usize,
 }
 
 #[tokio::test]
-async fn doc_struct_compiles_with_attrs() {
-    let state = DocStructLock::new(1, 2);
-    let guard = state.lock_all().await;
-    assert_eq!(*guard.x, 1);
-    assert_eq!(*guard.y, 2);
+async fn const_generic_struct() {
+    let state = BufferLock::<16>::new([0; 16], 0);
+    let mut guard = state.builder_fill().lock().await;
+    guard.data[..3].copy_from_slice(b"abc");
+    *guard.len = 3;
+    let guard: BufferReadGuard<'_, 16> = guard.downgrade_all();
+    assert_eq!(&guard.data[..*guard.len], b"abc");
+    drop(guard);
+
+    let guard = state.builder().read_len().lock().await;
+    let guard = guard.relock().write_data().lock().await;
+    let guard: BufferDataWriteGuard<'_, 16> = guard;
+    drop(guard);
+    assert_eq!(BufferLock::<16>::FIELD_NAMES, ["data", "len"]);
+    assert!(
+        state
+            == BufferLock::from(Buffer {
+                data: *b"abc\0\0\0\0\0\0\0\0\0\0\0\0\0",
+                len: 3
+            })
+    );
+
+    let mut state = state;
+    state.get_all_mut().data[3] = b'd';
+    let inner: Buffer<16> = state.into_inner();
+    assert_eq!(&inner.data[..4], b"abcd");
 }
 
-// --- try_lock ---
+#[smart_lock]
+struct Ring<T: Send + Sync, const N: usize = 4> {
+    slots: [Option<T>; N],
+    head: usize,
+}
 
 #[tokio::test]
-async fn try_lock_succeeds_when_unlocked() {
-    let state = MyStateLock::new(42, "hello".into(), vec![1]);
+async fn const_generic_default_is_used_when_omitted() {
+    let state: RingLock<u32> = RingLock::new([None; 4], 0);
+    let mut guard: RingWriteGuard<'_, u32> = state.lock_all_mut().await;
+    guard.slots[*guard.head] = Some(7);
+    *guard.head += 1;
+    drop(guard);
+    let inner: Ring<u32> = state.into_inner();
+    assert_eq!((inner.slots[0], inner.head), (Some(7), 1));
+}
 
-    let guard = state.builder().write_counter().read_name().try_lock();
-    assert!(guard.is_some());
-    let guard = guard.unwrap();
-    assert_eq!(*guard.counter, 42);
-    assert_eq!(*guard.name, "hello");
+#[smart_lock]
+struct Cache<T: Send + Sync = String> {
+    data: Vec<T>,
+    hits: u64,
 }
 
 #[tokio::test]
-async fn try_lock_fails_when_write_held() {
-    let state = MyStateLock::new(0, "".into(), vec![]);
+async fn default_type_param_is_used_when_omitted() {
+    // `CacheLock` alone names `CacheLock<String>`.
+    let state: CacheLock = CacheLock::new(vec!["a".into()], 0);
+    let mut guard = state.builder().write_data().write_hits().lock().await;
+    guard.data.push(String::from("b"));
+    *guard.hits += 1;
+    drop(guard);
+    let inner: Cache = state.into_inner();
+    assert_eq!(inner.data, ["a", "b"]);
+    assert_eq!(inner.hits, 1);
+}
 
-    // Hold a write lock on counter
-    let _hold = state.write_counter().await;
+#[tokio::test]
+async fn guard_aliases_keep_default_type_params() {
+    let state: CacheLock = CacheLock::new(vec![], 0);
+    let guard: CacheReadGuard<'_> = state.lock_all().await;
+    drop(guard);
+    let state = CacheLock::<u8>::new(vec![], 0);
+    let mut guard: CacheHitsWriteGuard<'_, u8> = state.builder().write_hits().lock().await;
+    *guard.hits += 1;
+}
+
+#[tokio::test]
+async fn default_type_param_can_be_overridden() {
+    let state = CacheLock::<u8>::new(vec![1, 2], 0);
+    let guard = state.lock_all().await;
+    assert_eq!(*guard.data, [1, 2]);
+    drop(guard);
+    let state: CacheLock<u8> = Cache {
+        data: vec![3],
+        hits: 7,
+    }
+    .into();
+    assert_eq!(*state.read_hits().await, 7);
+}
+
+/// Associated-type projections (short and qualified), an HRTB, and a
+/// predicate on a non-parameter type, with a trailing comma.
+#[smart_lock]
+#[swap(pending, done)]
+struct Pipeline<I, F>
+where
+    I: Iterator + Send + Sync,
+    I::Item: Clone + PartialEq + Send + Sync,
+    <I as Iterator>::Item: std::fmt::Debug,
+    F: for<'x> Fn(&'x I::Item) -> bool + Send + Sync,
+    Vec<I::Item>: Default,
+{
+    source: I,
+    pending: Vec<I::Item>,
+    done: Vec<I::Item>,
+    #[boxed]
+    last: Box<Option<I::Item>>,
+    #[no_lock]
+    keep: F,
+}
+
+#[tokio::test]
+async fn where_clause_with_associated_types_and_hrtb() {
+    let state = PipelineLock::new(0..4u32, vec![], vec![], Box::new(None), |x: &u32| {
+        x % 2 == 1
+    });
+    let mut guard = state
+        .builder()
+        .write_source()
+        .write_pending()
+        .upgrade_last()
+        .lock()
+        .await;
+    let keep = &state.keep;
+    for item in guard.source.by_ref() {
+        if keep(&item) {
+            guard.pending.push(item);
+        }
+    }
+    let mut guard = guard.upgrade_last().await;
+    *guard.last = guard.pending.last().copied();
+    let guard = guard.relock().read_last().lock().await;
+    assert_eq!(*guard.last, Some(3));
+    drop(guard);
+
+    state.swap_pending_done().await;
+    assert_eq!(state.snapshot_done().await, [1, 3]);
+    let inner = state.into_inner();
+    assert_eq!((inner.pending.len(), inner.done.len()), (0, 2));
+    assert_eq!(*inner.last, Some(3));
+    let state = PipelineLock::from(inner);
+    assert_eq!(*state.read_done().await, [1, 3]);
+}
+
+/// Struct lifetimes named like the ones the generated code uses internally.
+#[smart_lock]
+#[preset(names = "write_name, read_count")]
+struct Borrowed<'a, 'b, T: Send + Sync> {
+    #[lock(group = "text")]
+    name: &'a str,
+    #[lock(group = "text")]
+    tags: Vec<&'b str>,
+    count: u32,
+    #[no_lock]
+    extra: &'b T,
+}
+
+#[tokio::test]
+#[cfg_attr(
+    feature = "debug-deadlock",
+    ignore = "upgrades a field while holding one acquired after it"
+)]
+async fn struct_lifetime_params_are_threaded_through() {
+    let (owned, tag, extra) = (String::from("first"), String::from("t"), 7u8);
+    let state = BorrowedLock::new(&owned, vec![], 0, &extra);
+
+    let mut guard = state.builder_names().lock().await;
+    *guard.name = "second";
+    assert_eq!(*guard.count, 0);
+    let mut guard = guard
+        .relock()
+        .upgrade_tags()
+        .with_mode::<borrowed_field::count, smart_lock::WriteLocked>()
+        .lock()
+        .await
+        .upgrade_tags()
+        .await;
+    guard.tags.push(&tag);
+    *guard.count += 1;
+    let total =
+        guard.map_all(|view| view.tags.len() + *view.count as usize + **view.extra as usize);
+    assert_eq!(total, 9);
+    drop(guard);
+
+    let guard: BorrowedReadGuard<'_, '_, '_, u8> = state.read().await;
+    assert_eq!(
+        (*guard.name, guard.tags.as_slice()),
+        ("second", ["t"].as_slice())
+    );
+    drop(guard);
+    let guard = state.builder_text().read_name().lock().await;
+    assert_eq!(*guard.name, "second");
+    drop(guard);
+
+    let inner: Borrowed<'_, '_, u8> = state.into_inner();
+    assert_eq!((inner.name, inner.count, *inner.extra), ("second", 1, 7));
+    let state = BorrowedLock::from(inner);
+    assert!(state == BorrowedLock::new("second", vec!["t"], 1, &7));
+}
+
+// --- Attribute passthrough (doc comments) ---
+
+#[smart_lock]
+struct DocStruct {
+    /// This is a documented field
+    x: u32,
+    y: u32,
+}
+
+#[tokio::test]
+async fn doc_struct_compiles_with_attrs() {
+    let state = DocStructLock::new(1, 2);
+    let guard = state.lock_all().await;
+    assert_eq!(*guard.x, 1);
+    assert_eq!(*guard.y, 2);
+}
+
+// --- try_lock ---
+
+#[tokio::test]
+async fn try_lock_succeeds_when_unlocked() {
+    let state = MyStateLock::new(42, "hello".into(), vec![1]);
+
+    let guard = state.builder().write_counter().read_name().try_lock();
+    assert!(guard.is_some());
+    let guard = guard.unwrap();
+    assert_eq!(*guard.counter, 42);
+    assert_eq!(*guard.name, "hello");
+}
+
+#[tokio::test]
+async fn try_lock_fails_when_write_held() {
+    let state = MyStateLock::new(0, "".into(), vec![]);
+
+    // Hold a write lock on counter
+    let _hold = state.write_counter().await;
 
     // try_lock requesting write on counter should fail
     let guard = state.builder().write_counter().try_lock();
@@ -466,6 +1356,82 @@ async fn try_lock_releases_on_partial_failure() {
     assert!(counter.is_some());
 }
 
+#[tokio::test]
+async fn try_lock_verbose_names_the_first_blocked_field() {
+    let state = MyStateLock::new(0, "".into(), vec![]);
+
+    let hold_name = state.write_name().await;
+    let _hold_data = state.write_data().await;
+    let err = state
+        .builder()
+        .write_counter()
+        .write_name()
+        .read_data()
+        .try_lock_verbose();
+    assert_eq!(err.err(), Some(MyStateFieldId::name));
+    // The counter lock taken before `name` failed was released.
+    assert!(state.try_write_counter().is_some());
+
+    drop(hold_name);
+    let err = state.builder().write_name().read_data().try_lock_verbose();
+    assert_eq!(err.err(), Some(MyStateFieldId::data));
+    let guard = state.builder().write_counter().try_lock_verbose().unwrap();
+    assert_eq!(*guard.counter, 0);
+}
+
+#[tokio::test]
+async fn try_lock_all_upgradable_releases_on_partial_failure() {
+    let state = MyStateLock::new(0, "".into(), vec![]);
+
+    // `data` is acquired last, so `counter` and `name` are taken before it fails.
+    let hold = state.upgrade_data().await;
+    assert!(state.try_lock_all_upgradable().is_none());
+    assert!(state.try_upgrade_counter().is_some());
+    assert!(state.try_upgrade_name().is_some());
+
+    // Readers don't block it, and the all-upgrade guard can still upgrade.
+    drop(hold);
+    let reader = state.read_data().await;
+    let guard = state.try_lock_all_upgradable().unwrap();
+    drop(reader);
+    let mut guard = guard.upgrade_data().await;
+    guard.data.push(1);
+}
+
+#[tokio::test]
+async fn try_lock_releases_across_skipped_unlocked_field() {
+    let state = MyStateLock::new(0, "".into(), vec![]);
+
+    // Hold write on data (field index 2); name (index 1) is left Unlocked
+    let hold = state.write_data().await;
+
+    // counter is read-locked, name skipped, then data fails
+    let guard = state.builder().read_counter().write_data().try_lock();
+    assert!(guard.is_none());
+
+    // counter's read lock was released, and name was never locked
+    assert!(state.try_write_counter().is_some());
+    assert!(state.try_write_name().is_some());
+
+    drop(hold);
+    assert!(state
+        .builder()
+        .read_counter()
+        .write_data()
+        .try_lock()
+        .is_some());
+}
+
+#[tokio::test]
+async fn try_lock_never_touches_skipped_field() {
+    let state = MyStateLock::new(0, "".into(), vec![]);
+
+    // A writer on the skipped middle field doesn't affect the request
+    let _hold = state.write_name().await;
+    let guard = state.builder().read_counter().write_data().try_lock();
+    assert!(guard.is_some());
+}
+
 #[tokio::test]
 async fn try_lock_all_unlocked_fields_returns_some() {
     let state = MyStateLock::new(10, "test".into(), vec![]);
@@ -475,6 +1441,29 @@ async fn try_lock_all_unlocked_fields_returns_some() {
     assert!(guard.is_some());
 }
 
+// --- try_lock_spec! ---
+
+#[tokio::test]
+async fn try_lock_spec_expands_to_the_builder_chain() {
+    use smart_lock::{try_lock_spec, ReadLocked, Unlocked, UpgradeLocked, WriteLocked};
+
+    let state = MyStateLock::new(1, "spec".into(), vec![]);
+    let mut guard: MyStateLockGuard<'_, WriteLocked, ReadLocked, Unlocked> =
+        try_lock_spec!(state, read: [name], write: [counter]).unwrap();
+    *guard.counter += guard.name.len() as u32;
+    assert!(try_lock_spec!(state, read: [counter]).is_none());
+    // Nothing is left locked by a failed attempt.
+    assert!(try_lock_spec!(state, upgrade: [data], write: [name]).is_none());
+    assert!(state.try_write_data().is_some());
+    drop(guard);
+
+    let guard: MyStateLockGuard<'_, UpgradeLocked, Unlocked, ReadLocked> =
+        try_lock_spec!(&state, upgrade: [counter], read: [data],).unwrap();
+    assert_eq!(*guard.counter, 5);
+    let guard: MyStateLockGuard<'_, Unlocked, Unlocked, Unlocked> = try_lock_spec!(state).unwrap();
+    drop(guard);
+}
+
 // --- try_lock_all / try_lock_all_mut ---
 
 #[tokio::test]
@@ -516,6 +1505,74 @@ async fn try_lock_all_mut_fails_when_read_held() {
     assert!(state.try_lock_all_mut().is_none());
 }
 
+// --- lock_all*_or ---
+
+#[tokio::test]
+async fn lock_all_mut_or_gives_up_and_releases_partial_locks() {
+    use std::time::Duration;
+
+    let state = MyStateLock::new(0, "".into(), vec![]);
+    let hold = state.write_data().await;
+    let guard = state
+        .lock_all_mut_or(tokio::time::sleep(Duration::from_millis(20)))
+        .await;
+    assert!(guard.is_none());
+    // `counter` and `name` were locked before `data` blocked; both are free again.
+    assert!(state.try_write_counter().is_some());
+    assert!(state.try_write_name().is_some());
+
+    drop(hold);
+    let mut guard = state
+        .lock_all_mut_or(std::future::pending::<()>())
+        .await
+        .unwrap();
+    *guard.counter = 1;
+    drop(guard);
+    let guard = state.lock_all_or(std::future::ready(())).await.unwrap();
+    assert_eq!(*guard.counter, 1);
+    drop(guard);
+
+    let _hold = state.lock_all_upgradable().await;
+    let guard = state.lock_all_upgradable_or(std::future::ready(())).await;
+    assert!(guard.is_none());
+}
+
+// --- read / write aliases ---
+
+#[tokio::test]
+async fn read_and_write_alias_lock_all() {
+    let state = MyStateLock::new(0, "".into(), vec![]);
+
+    let mut guard = state.write().await;
+    *guard.counter = 5;
+    assert!(state.try_read().is_none());
+    drop(guard);
+
+    let guard = state.read().await;
+    assert_eq!(*guard.counter, 5);
+    assert!(state.try_write().is_none());
+    assert!(state.try_read().is_some());
+}
+
+/// Per-field accessors are prefixed (`read_read`, `write_write`), so fields named
+/// `read` and `write` don't collide with the aliases.
+#[smart_lock]
+struct Named {
+    read: u32,
+    write: u32,
+}
+
+#[tokio::test]
+async fn aliases_coexist_with_fields_named_read_and_write() {
+    let state = NamedLock::new(1, 2);
+    *state.write_read().await += 10;
+    let guard = state.read().await;
+    assert_eq!((*guard.read, *guard.write), (11, 2));
+    drop(guard);
+    *state.write().await.write = 3;
+    assert_eq!(*state.read_write().await, 3);
+}
+
 // --- lock_rest_read ---
 
 #[tokio::test]
@@ -604,6 +1661,29 @@ async fn no_lock_accessible_through_guard() {
     assert_eq!(*guard.name, "hello");
 }
 
+#[smart_lock(no_send_assert)]
+struct LocalOnly<T> {
+    count: u32,
+    value: T,
+    #[no_lock]
+    shared: std::rc::Rc<String>,
+}
+
+#[tokio::test]
+async fn no_send_assert_allows_thread_local_fields() {
+    // Neither `Rc` nor the unbounded `T` is `Send + Sync`; the lock is still
+    // usable on the current thread.
+    let shared = std::rc::Rc::new(String::from("local"));
+    let state = LocalOnlyLock::new(0, std::cell::Cell::new(1), std::rc::Rc::clone(&shared));
+    let mut guard = state.builder().write_count().read_value().lock().await;
+    *guard.count += guard.value.get();
+    assert_eq!(guard.shared.as_str(), "local");
+    drop(guard);
+    assert_eq!(*state.read_count().await, 1);
+    assert_eq!(state.raw_shared().as_str(), "local");
+    assert_eq!(std::rc::Rc::strong_count(&shared), 2);
+}
+
 #[tokio::test]
 async fn no_lock_accessible_in_lock_all_mut() {
     let state = WithNoLockLock::new(0, AtomicU32::new(10), "test".into());
@@ -722,6 +1802,39 @@ async fn no_lock_get_mut() {
     assert_eq!(*guard.name, "mutated");
 }
 
+#[tokio::test]
+async fn get_all_mut_borrows_every_field_at_once() {
+    let mut state = WithNoLockLock::new(3, AtomicU32::new(0), "ab".into());
+    let WithNoLockMut {
+        counter,
+        synced,
+        name,
+    } = state.get_all_mut();
+    // Simultaneous borrows of several fields, which separate get_mut_* calls can't give.
+    name.push_str(&counter.to_string());
+    *counter += name.len() as u32;
+    *synced.get_mut() = *counter;
+
+    let guard = state.lock_all().await;
+    assert_eq!((*guard.counter, guard.name.as_str()), (6, "ab3"));
+    assert_eq!(guard.synced.load(Ordering::Relaxed), 6);
+}
+
+#[tokio::test]
+async fn no_lock_take_and_replace() {
+    let mut state = WithNoLockLock::new(1, AtomicU32::new(5), "a".into());
+    assert_eq!(state.take_synced().into_inner(), 5);
+    assert_eq!(
+        state
+            .replace_synced(AtomicU32::new(9))
+            .load(Ordering::Relaxed),
+        0
+    );
+
+    let guard = state.lock_all().await;
+    assert_eq!(guard.synced.load(Ordering::Relaxed), 9);
+}
+
 #[tokio::test]
 async fn no_lock_relock() {
     let state = WithNoLockLock::new(0, AtomicU32::new(0), "hello".into());
@@ -737,6 +1850,10 @@ async fn no_lock_relock() {
 }
 
 #[tokio::test]
+#[cfg_attr(
+    feature = "debug-deadlock",
+    ignore = "upgrades a field while holding one acquired after it"
+)]
 async fn no_lock_upgrade_downgrade() {
     let state = WithNoLockLock::new(0, AtomicU32::new(0), "test".into());
 
@@ -851,6 +1968,79 @@ async fn zst_field_into_inner() {
     assert_eq!(original.marker, ());
 }
 
+// --- Degenerate structs: no fields, one field ---
+
+#[smart_lock]
+#[derive(Debug, PartialEq)]
+struct NoFields {}
+
+#[tokio::test]
+async fn no_fields_struct_supports_the_whole_api() {
+    let mut state = NoFieldsLock::new();
+    assert!(NoFieldsLock::FIELD_NAMES.is_empty());
+    assert_eq!(NoFieldsLock::fields().count(), 0);
+
+    let guard = state.builder().lock().await;
+    let guard = guard.relock().try_lock().unwrap();
+    drop(guard);
+    // With no fields every mode combination is the same guard type.
+    let read: NoFieldsReadGuard<'_> = state.lock_all_mut().await;
+    drop(read);
+    let write: NoFieldsWriteGuard<'_> = state.lock_all_upgradable().await;
+    drop(write);
+    assert!(state.try_lock_all().is_some());
+    assert!(state.try_lock_all_mut().is_some());
+    assert!(state == NoFieldsLock::from(NoFields {}));
+
+    let _ = state.get_all_mut();
+    assert_eq!(state.into_inner(), NoFields {});
+}
+
+#[smart_lock]
+#[derive(Debug, PartialEq)]
+struct OneField {
+    value: u32,
+}
+
+#[tokio::test]
+async fn one_field_struct_supports_the_whole_api() {
+    let mut state = OneFieldLock::new(1);
+    assert_eq!(OneFieldLock::FIELD_NAMES, ["value"]);
+
+    let guard = state.lock_all_upgradable().await;
+    let mut guard = guard.upgrade_all().await;
+    *guard.value += 1;
+    let guard = guard.downgrade_all();
+    assert_eq!(*guard.value, 2);
+    drop(guard);
+
+    let guard: OneFieldWriteGuard<'_> = state.builder().write_value().lock().await;
+    let guard = guard.relock().read_value().try_lock().unwrap();
+    assert_eq!(*guard.value, 2);
+    drop(guard);
+    assert_eq!(*state.read_value().await, 2);
+    assert!(state == OneFieldLock::new(2));
+
+    *state.get_all_mut().value = 3;
+    assert_eq!(state.into_inner(), OneField { value: 3 });
+}
+
+#[smart_lock]
+struct OnlyUnlocked {
+    #[no_lock]
+    id: u32,
+}
+
+#[tokio::test]
+async fn struct_without_lockable_fields() {
+    let state = OnlyUnlockedLock::new(7);
+    let guard = state.lock_all_mut().await;
+    assert_eq!(*guard.id, 7);
+    drop(guard);
+    assert_eq!(*state.builder().try_lock().unwrap().id, 7);
+    assert_eq!(state.into_inner().id, 7);
+}
+
 // --- Display impl (forwards to T) ---
 
 #[tokio::test]
@@ -861,6 +2051,17 @@ async fn display_impl_on_field_guard() {
     assert_eq!(format!("{}", guard.name), "hello");
 }
 
+#[tokio::test]
+async fn display_forwards_format_flags_in_every_readable_mode() {
+    let state = MyStateLock::new(7, "ab".into(), vec![]);
+    let guard = state.builder().write_counter().upgrade_name().lock().await;
+    assert_eq!(
+        format!("[{:>4}|{:<4}]", guard.counter, guard.name),
+        "[   7|ab  ]"
+    );
+    assert_eq!(guard.counter.to_string(), "7");
+}
+
 // --- PartialEq / PartialOrd / Hash ---
 
 #[tokio::test]
@@ -996,6 +2197,71 @@ async fn large_struct_concurrent_different_fields() {
     assert_eq!(*guard.f9, 1000);
 }
 
+// --- #[smart_lock(suffix = "...")] ---
+
+#[smart_lock(suffix = "Sync")]
+struct Settings {
+    retries: u32,
+    #[no_lock]
+    name: &'static str,
+}
+
+#[tokio::test]
+async fn suffix_renames_the_generated_types() {
+    let state: SettingsSync = SettingsSync::new(3, "svc");
+    let builder: SettingsSyncBuilder<'_, smart_lock::Unlocked> = state.builder();
+    let mut guard: SettingsSyncGuard<'_, smart_lock::WriteLocked> =
+        builder.write_retries().lock().await;
+    *guard.retries += 1;
+    assert!(format!("{guard:?}").starts_with("SettingsSyncGuard {"));
+    drop(guard);
+    // Names derived from the struct itself keep their usual form.
+    let guard: SettingsReadGuard<'_> = state.lock_all().await;
+    assert_eq!((*guard.retries, *guard.name), (4, "svc"));
+    assert_eq!(SettingsFieldId::retries.name(), "retries");
+}
+
+// --- #[smart_lock(vis = "...")] ---
+
+mod counter_api {
+    use smart_lock::smart_lock;
+
+    /// Public data whose lock stays an implementation detail of this module.
+    #[smart_lock(vis = "pub(super)")]
+    pub struct Counter {
+        pub hits: u64,
+    }
+
+    pub struct Service {
+        state: CounterLock,
+    }
+
+    impl Service {
+        pub fn new() -> Self {
+            Service {
+                state: CounterLock::new(0),
+            }
+        }
+
+        pub async fn hit(&self) -> u64 {
+            let mut guard = self.state.write_hits().await;
+            *guard += 1;
+            *guard
+        }
+    }
+}
+
+#[tokio::test]
+async fn vis_overrides_generated_item_visibility() {
+    let service = counter_api::Service::new();
+    service.hit().await;
+    assert_eq!(service.hit().await, 2);
+    // `pub(super)` reaches this module, so the lock is usable here too.
+    let state = counter_api::CounterLock::new(7);
+    let guard: counter_api::CounterReadGuard<'_> = state.lock_all().await;
+    assert_eq!(*guard.hits, 7);
+}
+
 // --- Debug impl ---
 
 #[tokio::test]
@@ -1012,6 +2278,39 @@ async fn debug_impl_with_no_lock() {
     assert!(debug_str.contains("WithNoLockLock"));
 }
 
+#[tokio::test]
+async fn debug_impl_never_locks() {
+    let state = MyStateLock::new(42, "hello".into(), vec![]);
+    let _writer = state.write_counter().await;
+    assert_eq!(format!("{:?}", state), "MyStateLock { .. }");
+}
+
+#[test]
+fn debug_blocking_shows_every_field() {
+    let state = WithNoLockLock::new(1, AtomicU32::new(2), "test".into());
+    assert_eq!(
+        state.debug_blocking(),
+        r#"WithNoLockLock { counter: 1, synced: 2, name: "test" }"#
+    );
+}
+
+#[test]
+fn debug_blocking_waits_for_writer() {
+    let state = std::sync::Arc::new(MyStateLock::new(0, "a".into(), vec![]));
+    let writer = state.write_name_blocking();
+    let reader = {
+        let state = state.clone();
+        std::thread::spawn(move || state.debug_blocking())
+    };
+    std::thread::sleep(std::time::Duration::from_millis(20));
+    assert!(!reader.is_finished());
+    drop(writer);
+    assert_eq!(
+        reader.join().unwrap(),
+        r#"MyStateLock { counter: 0, name: "a", data: [] }"#
+    );
+}
+
 // --- Debug on guard (needed for try_upgrade Result) ---
 
 #[tokio::test]
@@ -1021,3 +2320,1050 @@ async fn guard_debug_impl() {
     let debug_str = format!("{:?}", guard);
     assert!(debug_str.contains("MyStateLockGuard"));
 }
+
+#[tokio::test]
+async fn guard_debug_shows_fields_and_modes() {
+    let state = MyStateLock::new(42, "hello".into(), vec![]);
+    let guard = state.builder().write_counter().read_name().lock().await;
+    assert_eq!(
+        format!("{guard:?}"),
+        r#"MyStateLockGuard { counter: 42, name: "hello", data: <unlocked> }"#
+    );
+}
+
+struct Opaque;
+
+#[smart_lock]
+struct PartlyDebug<T: Send + Sync> {
+    shown: u32,
+    opaque: Opaque,
+    generic: T,
+    #[no_lock]
+    id: u32,
+}
+
+#[tokio::test]
+async fn guard_debug_leaves_out_fields_that_are_not_debug() {
+    // `Opaque` has no `Debug`, and `T` isn't bounded by it, so both are elided;
+    // the guard itself is still `Debug`.
+    let state = PartlyDebugLock::new(1, Opaque, 2u8, 3);
+    let guard = state.lock_all().await;
+    assert_eq!(
+        format!("{guard:?}"),
+        "PartlyDebugLockGuard { shown: 1, id: 3, .. }"
+    );
+}
+
+// --- FieldId ---
+
+#[test]
+fn field_id_index_and_name() {
+    assert_eq!(MyStateFieldId::ALL.len(), 3);
+    assert_eq!(MyStateFieldId::counter.index(), 0);
+    assert_eq!(MyStateFieldId::data.index(), 2);
+    assert_eq!(MyStateFieldId::name.name(), "name");
+    assert!(MyStateFieldId::counter < MyStateFieldId::data);
+}
+
+#[test]
+fn field_id_includes_no_lock_fields() {
+    assert_eq!(
+        WithNoLockFieldId::ALL,
+        [
+            WithNoLockFieldId::counter,
+            WithNoLockFieldId::synced,
+            WithNoLockFieldId::name,
+        ]
+    );
+}
+
+#[test]
+fn field_metadata_for_reflection() {
+    assert_eq!(WithNoLockLock::FIELD_NAMES, ["counter", "synced", "name"]);
+    let locked: Vec<(&str, bool)> = WithNoLockLock::fields()
+        .map(|id| (id.name(), id.is_locked()))
+        .collect();
+    assert_eq!(
+        locked,
+        [("counter", true), ("synced", false), ("name", true)]
+    );
+    assert_eq!(
+        MyStateLock::fields().count(),
+        MyStateLock::FIELD_NAMES.len()
+    );
+}
+
+/// Generic over the guard's modes: reports them without knowing the guard type.
+fn describe<F0, F1>(guard: &WithNoLockLockGuard<'_, F0, F1>) -> Vec<String>
+where
+    F0: smart_lock::LockMode,
+    F1: smart_lock::LockMode,
+{
+    WithNoLockLock::fields()
+        .map(|id| format!("{}={:?}", id.name(), guard.mode_of(id)))
+        .collect()
+}
+
+#[tokio::test]
+async fn mode_of_reports_each_field_mode_at_runtime() {
+    use smart_lock::LockModeKind;
+
+    let state = WithNoLockLock::new(0, AtomicU32::new(0), String::new());
+    let guard = state.builder().upgrade_counter().lock().await;
+    assert_eq!(
+        describe(&guard),
+        ["counter=Upgrade", "synced=None", "name=None"]
+    );
+    let guard = guard.upgrade_counter().await;
+    assert_eq!(
+        guard.mode_of(WithNoLockFieldId::counter),
+        LockModeKind::Write
+    );
+    let guard = guard.relock().read_name().lock().await;
+    assert_eq!(
+        describe(&guard),
+        ["counter=None", "synced=None", "name=Read"]
+    );
+}
+
+#[tokio::test]
+async fn fields_locked_sets_a_bit_per_held_field() {
+    let bit = |id: WithNoLockFieldId| 1u64 << id.index();
+    let state = WithNoLockLock::new(0, AtomicU32::new(0), String::new());
+
+    assert_eq!(state.builder().lock().await.fields_locked(), 0);
+    let guard = state.builder().upgrade_counter().lock().await;
+    assert_eq!(guard.fields_locked(), bit(WithNoLockFieldId::counter));
+    let guard = guard.relock().read_counter().write_name().lock().await;
+    assert_eq!(
+        guard.fields_locked(),
+        bit(WithNoLockFieldId::counter) | bit(WithNoLockFieldId::name)
+    );
+    // `#[no_lock]` fields have no lock to hold.
+    assert_eq!(guard.fields_locked() & bit(WithNoLockFieldId::synced), 0);
+}
+
+#[smart_lock]
+struct Tagged {
+    #[no_lock]
+    tag: &'static str,
+    count: u32,
+    names: Vec<String>,
+}
+
+#[tokio::test]
+async fn get_dyn_clones_readable_fields_by_id() {
+    let state = TaggedLock::new("t", 3, vec!["a".into()]);
+    let guard = state.builder().read_count().lock().await;
+    let count = guard.get_dyn(TaggedFieldId::count).unwrap();
+    assert_eq!(count.downcast_ref::<u32>(), Some(&3));
+    let tag = guard.get_dyn(TaggedFieldId::tag).unwrap();
+    assert_eq!(*tag.downcast::<&str>().unwrap(), "t");
+    assert!(guard.get_dyn(TaggedFieldId::names).is_none());
+
+    let guard = guard.relock().write_names().lock().await;
+    let names: Vec<Vec<String>> = TaggedLock::fields()
+        .filter_map(|id| guard.get_dyn(id)?.downcast().ok())
+        .map(|names| *names)
+        .collect();
+    assert_eq!(names, [vec!["a".to_string()]]);
+}
+
+// --- apply_updates / Extend (homogeneous locks) ---
+
+#[smart_lock]
+#[swap(f1, f2)]
+#[swap(f2, f3)]
+#[swap(f1, f3)]
+struct Uniform {
+    f1: u32,
+    f2: u32,
+    f3: u32,
+}
+
+#[tokio::test]
+async fn apply_updates_writes_only_listed_fields() {
+    let state = UniformLock::new(1, 2, 3);
+    state
+        .apply_updates([(UniformFieldId::f1, 10), (UniformFieldId::f3, 30)])
+        .await;
+    let guard = state.lock_all().await;
+    assert_eq!(*guard.f1, 10);
+    assert_eq!(*guard.f2, 2);
+    assert_eq!(*guard.f3, 30);
+}
+
+#[tokio::test]
+async fn apply_updates_last_duplicate_wins() {
+    let state = UniformLock::new(0, 0, 0);
+    state
+        .apply_updates([
+            (UniformFieldId::f3, 1),
+            (UniformFieldId::f2, 5),
+            (UniformFieldId::f3, 2),
+        ])
+        .await;
+    let guard = state.lock_all().await;
+    assert_eq!((*guard.f1, *guard.f2, *guard.f3), (0, 5, 2));
+}
+
+#[tokio::test]
+async fn apply_updates_skips_unlisted_locks() {
+    let state = UniformLock::new(0, 0, 0);
+    let _held = state.write_f2().await;
+    // f2 is held elsewhere but not listed, so this must not wait on it.
+    state.apply_updates([(UniformFieldId::f1, 7)]).await;
+    assert_eq!(*state.read_f1().await, 7);
+}
+
+#[tokio::test]
+async fn extend_assigns_with_exclusive_access() {
+    let mut state = UniformLock::new(0, 0, 0);
+    state.extend([(UniformFieldId::f2, 4), (UniformFieldId::f1, 9)]);
+    let inner = state.into_inner();
+    assert_eq!((inner.f1, inner.f2, inner.f3), (9, 4, 0));
+}
+
+#[tokio::test]
+async fn map_field_projects_addressed_field() {
+    let state = UniformLock::new(1, 20, 3);
+    let guard = state.builder().read_f2().write_f3().lock().await;
+    assert_eq!(guard.map_field(UniformFieldId::f2, |v| v * 2), 40);
+    assert_eq!(guard.map_field(UniformFieldId::f3, u32::to_string), "3");
+    drop(guard);
+
+    let all = state.lock_all().await;
+    let labels: Vec<String> = UniformFieldId::ALL
+        .iter()
+        .map(|&id| all.map_field(id, |v| format!("{}={v}", id.name())))
+        .collect();
+    assert_eq!(labels, ["f1=1", "f2=20", "f3=3"]);
+}
+
+#[tokio::test]
+#[should_panic(expected = "map_field: field `f1` is not locked in this guard")]
+async fn map_field_panics_on_unlocked_field() {
+    let state = UniformLock::new(1, 2, 3);
+    let guard = state.builder().read_f2().lock().await;
+    guard.map_field(UniformFieldId::f1, |v| *v);
+}
+
+#[tokio::test]
+async fn map_all_borrows_each_field_by_mode() {
+    let state = WithNoLockLock::new(3, AtomicU32::new(4), "ab".into());
+    let mut guard = state.builder().write_counter().read_name().lock().await;
+    let total = guard.map_all(|view| {
+        let counter: &mut u32 = view.counter;
+        let name: &String = view.name;
+        *counter += name.len() as u32;
+        *counter + view.synced.load(Ordering::Relaxed)
+    });
+    assert_eq!(total, 9);
+    assert_eq!(*guard.counter, 5);
+    // Still locked after the closure returns.
+    assert!(state.try_read_counter().is_none());
+}
+
+#[tokio::test]
+async fn map_all_passes_unit_for_unlocked_fields() {
+    let state = MyStateLock::new(2, "name".into(), vec![1, 2]);
+    let ratio = state
+        .builder()
+        .read_counter()
+        .upgrade_data()
+        .lock()
+        .await
+        .map_all(|view| {
+            let () = view.name;
+            view.data.len() as f64 / f64::from(*view.counter)
+        });
+    assert_eq!(ratio, 1.0);
+    // The temporary guard was released at the end of the statement.
+    assert!(state.try_lock_all_mut().is_some());
+}
+
+#[tokio::test]
+async fn map_all_result_can_borrow_from_the_guard() {
+    let state = MyStateLock::new(0, "first".into(), vec![]);
+    let mut guard = state.lock_all().await;
+    let longest: &str = guard.map_all(|view| view.name.as_str());
+    assert_eq!(longest, "first");
+}
+
+// --- FieldGuard::map ---
+
+struct Inner {
+    items: Vec<u32>,
+    label: String,
+}
+
+#[smart_lock]
+struct Nested {
+    inner: Inner,
+    other: u32,
+}
+
+#[tokio::test]
+async fn map_write_guard_to_element() {
+    let state = NestedLock::new(
+        Inner {
+            items: vec![1, 2, 3],
+            label: "x".into(),
+        },
+        0,
+    );
+    {
+        let guard = state.builder().write_inner().lock().await;
+        let mut item =
+            smart_lock::FieldGuard::map(guard.inner, (|v| &v.items[1], |v| &mut v.items[1]));
+        *item += 40;
+        assert_eq!(*item, 42);
+    }
+    assert_eq!(state.read_inner().await.items, vec![1, 42, 3]);
+}
+
+#[tokio::test]
+async fn map_holds_lock_until_dropped() {
+    let state = NestedLock::new(
+        Inner {
+            items: vec![],
+            label: "x".into(),
+        },
+        0,
+    );
+    let guard = state.builder().write_inner().lock().await;
+    let label = smart_lock::FieldGuard::map(guard.inner, (|v| &v.label, |v| &mut v.label));
+    assert!(state.try_read_inner().is_none());
+    drop(label);
+    assert!(state.try_read_inner().is_some());
+}
+
+#[tokio::test]
+async fn map_read_guard_to_member() {
+    let state = NestedLock::new(
+        Inner {
+            items: vec![7],
+            label: "hello".into(),
+        },
+        5,
+    );
+    let guard = state.builder().read_inner().read_other().lock().await;
+    let label = smart_lock::FieldGuard::map(guard.inner, |v| v.label.as_str());
+    assert_eq!(&*label, "hello");
+    assert_eq!(label.to_string(), "hello");
+    // Remaining fields of the guard are still usable after moving one out.
+    assert_eq!(*guard.other, 5);
+}
+
+#[tokio::test]
+async fn map_upgrade_guard_keeps_upgrade_slot() {
+    let state = NestedLock::new(
+        Inner {
+            items: vec![9],
+            label: String::new(),
+        },
+        0,
+    );
+    let guard = state.builder().upgrade_inner().lock().await;
+    let first = smart_lock::FieldGuard::map(guard.inner, |v| &v.items[0]);
+    assert_eq!(*first, 9);
+    assert!(state.try_upgrade_inner().is_none());
+    assert!(state.try_read_inner().is_some());
+}
+
+#[smart_lock]
+struct Maybe {
+    opt: Option<i32>,
+}
+
+#[tokio::test]
+async fn field_map_is_not_shadowed_by_guard_map() {
+    let state = MaybeLock::new(Some(1));
+    let guard = state.lock_all().await;
+    let v: Option<i32> = guard.opt.map(|x| x + 1);
+    assert_eq!(v, Some(2));
+}
+
+#[tokio::test]
+async fn into_inner_guard_hands_out_async_lock_guards() {
+    fn append(mut g: smart_lock::RwLockWriteGuard<'_, Inner>, item: u32) {
+        g.items.push(item);
+    }
+
+    let state = NestedLock::new(
+        Inner {
+            items: vec![],
+            label: "x".into(),
+        },
+        0,
+    );
+    let guard = state.builder().write_inner().upgrade_other().lock().await;
+    let other = guard.other.into_inner_guard();
+    append(guard.inner.into_inner_guard(), 1);
+    assert_eq!(state.read_inner().await.items, [1]);
+
+    let mut other = smart_lock::RwLockUpgradableReadGuard::upgrade(other).await;
+    *other = 2;
+    drop(other);
+    let read = state.builder().read_other().lock().await.other;
+    assert_eq!(*read.into_inner_guard(), 2);
+}
+
+#[tokio::test]
+async fn into_raw_write_splits_one_field_out_of_the_guard() {
+    fn append(mut g: smart_lock::RwLockWriteGuard<'_, Inner>, item: u32) {
+        g.items.push(item);
+    }
+
+    let state = NestedLock::new(
+        Inner {
+            items: vec![],
+            label: String::new(),
+        },
+        0,
+    );
+    let guard = state.builder().write_inner().write_other().lock().await;
+    let (raw, mut rest) = guard.into_raw_write_inner();
+    *rest.other = 5;
+    append(raw, 1);
+    // The raw guard is gone, but `other` stays write-locked through `rest`.
+    assert_eq!(state.read_inner().await.items, [1]);
+    assert!(state.try_read_other().is_none());
+
+    let rest = rest.relock().read_inner().read_other().lock().await;
+    assert_eq!((rest.inner.items.len(), *rest.other), (1, 5));
+}
+
+#[tokio::test]
+async fn split_releases_field_guards_independently() {
+    let state = MyStateLock::new(1, "split".into(), vec![]);
+    let guard = state.builder().write_counter().read_name().lock().await;
+    let (mut counter, name, data) = guard.split();
+    *counter += 1;
+    assert!(smart_lock::FieldGuard::try_get(&data).is_none());
+
+    // Dropping the write guard lets writers of `counter` in while `name` stays read-locked.
+    drop(counter);
+    *state.write_counter().await += 1;
+    assert!(state.try_write_name().is_none());
+    assert_eq!(*name, "split");
+    drop(name);
+    assert_eq!(*state.read_counter().await, 3);
+    assert!(state.try_lock_all_mut().is_some());
+}
+
+#[smart_lock]
+struct Slot {
+    value: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+struct Empty;
+
+#[tokio::test]
+async fn try_map_projects_into_some_variant() {
+    let state = SlotLock::new(Some("a".into()));
+    {
+        let guard = state.builder().write_value().lock().await;
+        let mut value = smart_lock::FieldGuard::try_map(
+            guard.value,
+            |v| v.as_ref().ok_or(Empty),
+            |v| v.as_mut().ok_or(Empty),
+        )
+        .unwrap();
+        value.push('b');
+        assert_eq!(&*value, "ab");
+        assert!(state.try_read_value().is_none());
+    }
+    assert_eq!(state.read_value().await.as_deref(), Some("ab"));
+}
+
+#[tokio::test]
+async fn try_map_on_none_returns_original_guard() {
+    let state = SlotLock::new(None);
+    let guard = state.builder().write_value().lock().await;
+    let (mut value, err) = smart_lock::FieldGuard::try_map(
+        guard.value,
+        |v| v.as_ref().ok_or(Empty),
+        |v| v.as_mut().ok_or(Empty),
+    )
+    .unwrap_err();
+    assert_eq!(err, Empty);
+    // Still write-locked, so the caller can fill the slot instead.
+    assert!(state.try_read_value().is_none());
+    *value = Some("filled".into());
+    drop(value);
+    assert_eq!(state.read_value().await.as_deref(), Some("filled"));
+}
+
+#[tokio::test]
+async fn try_map_fails_if_either_projection_fails() {
+    let state = SlotLock::new(Some("a".into()));
+    let guard = state.builder().write_value().lock().await;
+    // Mismatched projections: the shared one never succeeds, so mapping must fail
+    // up front rather than panic on the first `Deref`.
+    let (value, err) =
+        smart_lock::FieldGuard::try_map(guard.value, |_| Err(Empty), |v| v.as_mut().ok_or(Empty))
+            .unwrap_err();
+    assert_eq!(err, Empty);
+    assert_eq!(value.as_deref(), Some("a"));
+}
+
+#[tokio::test]
+async fn map_does_not_require_sync_values() {
+    use smart_lock::{FieldGuard, ReadLocked, WriteLocked};
+    use std::cell::Cell;
+
+    let lock = smart_lock::RwLock::new((Cell::new(1u32), 2u32));
+    let guard = FieldGuard::<'_, _, WriteLocked>::acquire(&lock).await;
+    let mut second = FieldGuard::map(guard, (|p| &p.1, |p| &mut p.1));
+    *second += 1;
+    drop(second);
+    let first = FieldGuard::map(FieldGuard::<'_, _, ReadLocked>::acquire(&lock).await, |p| {
+        &p.0
+    });
+    assert_eq!(first.get(), 1);
+    assert_eq!(lock.read().await.1, 3);
+}
+
+// --- #[lock(order = N)] ---
+
+#[smart_lock]
+struct Ordered {
+    #[lock(order = 1)]
+    first: u32,
+    #[no_lock]
+    untouched: u32,
+    second: u32,
+    #[lock(order = -1)]
+    third: u32,
+}
+
+/// Hold `first` externally, start `acquire` in a task, and report which of the
+/// fields are locked while the acquisition is pending.
+async fn held_while_blocked<F>(state: &'static OrderedLock, acquire: F) -> [bool; 3]
+where
+    F: std::future::Future<Output = ()> + Send + 'static,
+{
+    let blocker = state.write_first().await;
+    let task = tokio::spawn(acquire);
+    for _ in 0..10 {
+        tokio::task::yield_now().await;
+    }
+    let held = [
+        state.try_write_first().is_none(),
+        state.try_write_second().is_none(),
+        state.try_write_third().is_none(),
+    ];
+    drop(blocker);
+    task.await.unwrap();
+    held
+}
+
+#[tokio::test]
+async fn lock_order_attribute_controls_acquisition() {
+    let state: &'static OrderedLock = Box::leak(Box::new(OrderedLock::new(1, 0, 2, 3)));
+
+    // Acquisition order is third (-1), second (default 0), first (1): both other
+    // fields are already held while waiting on `first`.
+    let held = held_while_blocked(state, async move {
+        let guard = state.lock_all_mut().await;
+        assert_eq!((*guard.first, *guard.second, *guard.third), (1, 2, 3));
+    })
+    .await;
+    assert_eq!(held, [true, true, true]);
+
+    let held = held_while_blocked(state, async move {
+        let _guard = state.builder().write_first().write_third().lock().await;
+    })
+    .await;
+    assert_eq!(held, [true, false, true]);
+}
+
+#[tokio::test]
+async fn lock_order_keeps_layout_and_names() {
+    let state = OrderedLock::new(1, 9, 2, 3);
+    let guard = state.lock_all().await;
+    assert_eq!(*guard.first, 1);
+    assert_eq!(*guard.untouched, 9);
+    assert_eq!(*guard.second, 2);
+    assert_eq!(*guard.third, 3);
+    drop(guard);
+
+    let original = state.into_inner();
+    assert_eq!(
+        (
+            original.first,
+            original.untouched,
+            original.second,
+            original.third
+        ),
+        (1, 9, 2, 3)
+    );
+    assert_eq!(OrderedFieldId::second.index(), 2);
+}
+
+// --- #[preset] ---
+
+#[smart_lock]
+#[preset(hot = "write_counter, read_name", scan = "read_data")]
+#[preset(audit = "upgrade_counter")]
+struct WithPresets {
+    counter: u32,
+    #[no_lock]
+    tag: &'static str,
+    name: String,
+    data: Vec<u8>,
+}
+
+#[tokio::test]
+async fn preset_builder_applies_modes() {
+    use smart_lock::{ReadLocked, Unlocked, WriteLocked};
+
+    let state = WithPresetsLock::new(0, "t", "hot".into(), vec![1]);
+    let mut guard: WithPresetsLockGuard<'_, WriteLocked, ReadLocked, Unlocked> =
+        state.builder_hot().lock().await;
+    *guard.counter += 1;
+    assert_eq!(*guard.name, "hot");
+    assert_eq!(*guard.tag, "t");
+    drop(guard);
+
+    let guard = state.builder_scan().lock().await;
+    assert_eq!(*guard.data, [1]);
+    drop(guard);
+
+    let guard = state.builder_audit().lock().await;
+    let guard = guard.upgrade_counter().await;
+    assert_eq!(*guard.counter, 1);
+}
+
+#[tokio::test]
+async fn preset_builder_can_be_extended() {
+    let state = WithPresetsLock::new(0, "t", String::new(), vec![]);
+    let mut guard = state.builder_hot().write_data().lock().await;
+    guard.data.push(2);
+    *guard.counter = 5;
+    drop(guard);
+    let guard = state.lock_all().await;
+    assert_eq!((*guard.counter, &*guard.data), (5, &vec![2u8]));
+}
+
+// --- #[lock(group = "...")] ---
+
+#[smart_lock]
+struct Grouped {
+    #[lock(group = "hot")]
+    hits: u64,
+    #[lock(group = "hot", group = "meta")]
+    last_seen: u64,
+    #[lock(group = "meta", order = -1)]
+    label: String,
+    cold: Vec<u8>,
+    #[no_lock]
+    id: u32,
+}
+
+fn grouped() -> GroupedLock {
+    GroupedLock::new(0, 0, "g".into(), vec![], 7)
+}
+
+#[tokio::test]
+async fn group_builder_locks_only_group_fields() {
+    use smart_lock::{ReadLocked, Unlocked, WriteLocked};
+
+    let state = grouped();
+    let mut guard: GroupedHotGroupGuard<'_, WriteLocked, ReadLocked> = state
+        .builder_hot()
+        .write_hits()
+        .read_last_seen()
+        .lock()
+        .await;
+    *guard.hits += 1;
+    assert_eq!((*guard.last_seen, *guard.id), (0, 7));
+    // Fields outside the group stay free for other tasks.
+    assert!(state.try_write_cold().is_some());
+    drop(guard);
+
+    // The alias is the full guard with the other fields `Unlocked`.
+    let guard: GroupedLockGuard<'_, Unlocked, ReadLocked, Unlocked, Unlocked> =
+        state.builder_hot().read_last_seen().try_lock().unwrap();
+    let err = state.builder_hot().write_last_seen().try_lock_verbose();
+    assert_eq!(err.err(), Some(GroupedFieldId::last_seen));
+    drop(guard);
+
+    let guard = state
+        .builder_meta()
+        .read_label()
+        .upgrade_last_seen()
+        .lock()
+        .await;
+    let guard = guard.upgrade_last_seen().await;
+    assert_eq!((*guard.last_seen, guard.label.as_str()), (0, "g"));
+    drop(guard);
+    assert_eq!(*state.read_hits().await, 1);
+}
+
+#[tokio::test]
+async fn group_guard_relocks_into_the_full_builder() {
+    let state = grouped();
+    let guard = state.builder_hot().read_hits().lock().await;
+    let mut guard = guard.relock().read_hits().write_cold().lock().await;
+    guard.cold.push(*guard.hits as u8);
+    assert_eq!(*guard.cold, [0]);
+}
+
+// --- with_mode ---
+
+/// Generic over the mode of `counter`: one call site instead of three.
+async fn counter_in<M: smart_lock::LockMode>(
+    state: &MyStateLock,
+) -> MyStateLockGuard<'_, M, smart_lock::ReadLocked, smart_lock::Unlocked> {
+    state
+        .builder()
+        .read_name()
+        .with_mode::<my_state_field::counter, M>()
+        .lock()
+        .await
+}
+
+#[tokio::test]
+#[cfg_attr(
+    feature = "debug-deadlock",
+    ignore = "upgrades a field while holding one acquired after it"
+)]
+async fn with_mode_sets_a_field_generically() {
+    use smart_lock::{LockModeKind, ReadLocked, UpgradeLocked, WriteLocked};
+
+    let state = MyStateLock::new(1, "n".into(), vec![]);
+    let mut guard = counter_in::<WriteLocked>(&state).await;
+    *guard.counter += 1;
+    drop(guard);
+
+    let guard = counter_in::<ReadLocked>(&state).await;
+    assert_eq!((*guard.counter, guard.name.as_str()), (2, "n"));
+    drop(guard);
+
+    let guard = counter_in::<UpgradeLocked>(&state).await;
+    assert_eq!(
+        guard.mode_of(MyStateFieldId::counter),
+        LockModeKind::Upgrade
+    );
+    assert_eq!(guard.mode_of(MyStateFieldId::data), LockModeKind::None);
+    let guard = guard.upgrade_counter().await;
+    assert_eq!(*guard.counter, 2);
+}
+
+#[tokio::test]
+async fn with_mode_markers_skip_no_lock_fields() {
+    use smart_lock::WriteLocked;
+
+    // `id` is `#[no_lock]`, so `grouped_field` only has markers for locked fields.
+    let state = grouped();
+    let mut guard = state
+        .builder()
+        .with_mode::<grouped_field::cold, WriteLocked>()
+        .with_mode::<grouped_field::hits, WriteLocked>()
+        .lock()
+        .await;
+    guard.cold.push(1);
+    *guard.hits = 2;
+    assert_eq!(*guard.id, 7);
+}
+
+// --- Lockable ---
+
+async fn bump_and_unwrap<L: smart_lock::Lockable>(
+    lock: L,
+    bump: impl FnOnce(&mut L::WriteGuard<'_>),
+) -> L::Inner {
+    bump(&mut lock.lock_all_mut().await);
+    lock.into_inner()
+}
+
+async fn read_all_debug<L: smart_lock::Lockable>(lock: &L) -> String
+where
+    for<'a> L::ReadGuard<'a>: std::fmt::Debug,
+{
+    format!("{:?}", lock.lock_all().await)
+}
+
+#[tokio::test]
+async fn lockable_is_implemented_by_generated_locks() {
+    let state = MyStateLock::new(1, "a".into(), vec![]);
+    assert_eq!(
+        read_all_debug(&state).await,
+        r#"MyStateLockGuard { counter: 1, name: "a", data: [] }"#
+    );
+    let inner = bump_and_unwrap(state, |guard| *guard.counter += 1).await;
+    assert_eq!(inner.counter, 2);
+
+    let inner = bump_and_unwrap(GenericStateLock::new(vec![1u8], 0), |guard| {
+        guard.value.push(2);
+    })
+    .await;
+    assert_eq!(inner.value, [1, 2]);
+}
+
+async fn snapshot_in_task<L>(lock: std::sync::Arc<L>) -> L::Inner
+where
+    L: smart_lock::Snapshot + Send + Sync + 'static,
+    L::Inner: Send + 'static,
+{
+    tokio::spawn(async move { lock.snapshot().await })
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn snapshot_future_can_be_spawned_generically() {
+    let state = std::sync::Arc::new(MyStateLock::new(1, "a".into(), vec![2]));
+    let inner = snapshot_in_task(state).await;
+    assert_eq!(
+        (inner.counter, inner.name, inner.data),
+        (1, "a".into(), vec![2])
+    );
+
+    let inner = snapshot_in_task(std::sync::Arc::new(GenericStateLock::new(vec![1u8], 0))).await;
+    assert_eq!(inner.value, [1]);
+}
+
+// --- swap_a_b ---
+
+#[smart_lock]
+#[swap(front, back)]
+struct DoubleBuffer {
+    front: Vec<u8>,
+    generation: u64,
+    back: Vec<u8>,
+}
+
+#[tokio::test]
+async fn swap_same_typed_fields() {
+    let state = DoubleBufferLock::new(vec![1], 0, vec![2, 2]);
+    state.write_back().await.push(3);
+    state.swap_front_back().await;
+
+    let guard = state.lock_all().await;
+    assert_eq!(*guard.front, [2, 2, 3]);
+    assert_eq!(*guard.back, [1]);
+    assert_eq!(*guard.generation, 0);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_swaps_do_not_deadlock() {
+    let state = std::sync::Arc::new(UniformLock::new(1, 2, 3));
+    let tasks: Vec<_> = (0..4)
+        .map(|i| {
+            let state = state.clone();
+            tokio::spawn(async move {
+                for _ in 0..100 {
+                    match i % 3 {
+                        0 => state.swap_f1_f2().await,
+                        1 => state.swap_f2_f3().await,
+                        _ => state.swap_f1_f3().await,
+                    }
+                }
+            })
+        })
+        .collect();
+    for task in tasks {
+        task.await.unwrap();
+    }
+
+    let guard = state.lock_all().await;
+    let mut values = [*guard.f1, *guard.f2, *guard.f3];
+    values.sort();
+    assert_eq!(values, [1, 2, 3]);
+}
+
+// --- raw_* ---
+
+async fn bump(lock: &smart_lock::RwLock<u32>) {
+    *lock.write().await += 1;
+}
+
+#[tokio::test]
+async fn raw_accessors_expose_inner_lock() {
+    let state = WithNoLockLock::new(1, AtomicU32::new(2), "raw".into());
+    bump(state.raw_counter()).await;
+    assert_eq!(*state.read_counter().await, 2);
+    assert_eq!(state.raw_synced().load(Ordering::Relaxed), 2);
+    assert_eq!(*state.raw_name().read().await, "raw");
+}
+
+#[tokio::test]
+async fn raw_no_lock_field_needs_no_guard() {
+    let state = WithNoLockLock::new(1, AtomicU32::new(2), "get".into());
+    let _writer = state.lock_all_mut().await;
+    // Every locked field is write-locked, yet the `#[no_lock]` field stays reachable.
+    state.raw_synced().fetch_add(1, Ordering::Relaxed);
+    assert_eq!(state.raw_synced().load(Ordering::Relaxed), 3);
+}
+
+// --- PartialEq / Eq ---
+
+#[smart_lock]
+struct Scores {
+    total: u32,
+    #[no_lock]
+    label: String,
+    names: Vec<String>,
+}
+
+fn scores(total: u32, label: &str) -> ScoresLock {
+    ScoresLock::new(total, label.into(), vec!["a".into()])
+}
+
+#[tokio::test]
+async fn locks_compare_field_by_field() {
+    fn assert_eq_impl<T: Eq>(_: &T) {}
+
+    let a = scores(1, "x");
+    assert_eq_impl(&a);
+    assert!(a == scores(1, "x"));
+    assert!(a != scores(2, "x"));
+    assert!(a != scores(1, "y"));
+
+    let b = scores(1, "x");
+    b.write_names().await.push("b".into());
+    assert!(a != b);
+    a.write_names().await.push("b".into());
+    assert!(a == b);
+}
+
+#[tokio::test]
+async fn self_comparison_skips_locking() {
+    let state = scores(1, "x");
+    let _writer = state.write_total().await;
+    let alias = &state;
+    // Would block forever if it tried to read-lock `total`.
+    assert!(state == *alias);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn opposite_comparisons_do_not_deadlock() {
+    let a = std::sync::Arc::new(scores(1, "x"));
+    let b = std::sync::Arc::new(scores(1, "x"));
+    let tasks: Vec<_> = (0..4)
+        .map(|i| {
+            let (a, b) = (a.clone(), b.clone());
+            tokio::task::spawn_blocking(move || {
+                let (lhs, rhs) = if i % 2 == 0 { (&a, &b) } else { (&b, &a) };
+                for _ in 0..200 {
+                    assert!(**lhs == **rhs);
+                }
+            })
+        })
+        .collect();
+    for task in tasks {
+        task.await.unwrap();
+    }
+}
+
+// --- #[boxed] ---
+
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Big {
+    samples: [u64; 32],
+    label: String,
+}
+
+#[smart_lock]
+struct WithBoxed {
+    #[boxed]
+    big: Box<Big>,
+    plain: Box<u32>,
+}
+
+fn takes_big(big: &Big) -> usize {
+    big.label.len()
+}
+
+#[tokio::test]
+async fn boxed_field_guard_derefs_to_inner() {
+    let state = WithBoxedLock::new(Box::default(), Box::new(1));
+    {
+        let mut guard = state.builder().write_big().read_plain().lock().await;
+        guard.big.label.push_str("big");
+        guard.big.samples[0] = 7;
+        // One deref reaches `Big`, not `Box<Big>`.
+        let Big { samples, label } = &*guard.big;
+        assert_eq!((samples[0], label.as_str()), (7, "big"));
+        assert_eq!(takes_big(&guard.big), 3);
+        // Unannotated fields still lock the declared `Box`.
+        assert_eq!(**guard.plain, 1);
+    }
+    let big = state.read_big().await;
+    assert_eq!(takes_big(&big), 3);
+    assert_eq!(big.samples[0], 7);
+    drop(big);
+    let raw: &smart_lock::RwLock<Big> = state.raw_big();
+    assert_eq!(raw.read().await.label, "big");
+}
+
+#[tokio::test]
+async fn boxed_field_round_trips_through_box() {
+    let original = WithBoxed {
+        big: Box::new(Big {
+            samples: [1; 32],
+            label: "from".into(),
+        }),
+        plain: Box::new(2),
+    };
+    let mut state: WithBoxedLock = original.into();
+    state.get_mut_big().label.push('!');
+    let inner: WithBoxed = state.into_inner();
+    let big: Box<Big> = inner.big;
+    assert_eq!(big.label, "from!");
+    assert_eq!(big.samples, [1; 32]);
+}
+
+#[tokio::test]
+async fn boxed_field_snapshots() {
+    let state = WithBoxedLock::new(Box::default(), Box::new(2));
+    state.write_big().await.label.push('x');
+    let big: Big = state.snapshot_big().await;
+    let copy: WithBoxed = state.snapshot_all().await;
+    assert_eq!((big.label.as_str(), *copy.plain), ("x", 2));
+    assert_eq!(copy.big, Box::new(big));
+}
+
+// --- #[smart_lock(metrics)] ---
+
+#[smart_lock(metrics)]
+struct Metered {
+    hot: u32,
+    #[no_lock]
+    id: u8,
+    cold: String,
+}
+
+#[tokio::test]
+async fn metrics_count_contended_acquisitions_per_field() {
+    let state = std::sync::Arc::new(MeteredLock::new(0, 1, String::new()));
+    drop(state.lock_all_mut().await);
+    drop(state.builder().write_hot().read_cold().lock().await);
+    assert_eq!(state.contention_counts(), [0, 0, 0]);
+
+    // Per-field accessors aren't counted; the failed `try_` acquisition is.
+    let hold = state.write_hot().await;
+    assert!(state.try_lock_all().is_none());
+    assert_eq!(state.contention_counts(), [1, 0, 0]);
+
+    let task = {
+        let state = std::sync::Arc::clone(&state);
+        tokio::spawn(async move {
+            *state.builder().write_hot().write_cold().lock().await.hot += 1;
+        })
+    };
+    tokio::task::yield_now().await;
+    assert!(!task.is_finished());
+    drop(hold);
+    task.await.unwrap();
+
+    let counts = state.contention_counts();
+    assert_eq!(counts[MeteredFieldId::hot.index()], 2);
+    assert_eq!(counts[MeteredFieldId::id.index()], 0);
+    assert_eq!(counts[MeteredFieldId::cold.index()], 0);
+    assert_eq!(*state.lock_all().await.id, 1);
+}
@@ -1,4 +1,8 @@
 #[test]
+#[cfg_attr(
+    feature = "tracing",
+    ignore = "rustc's suggestions list the extra `tracing` constructors"
+)]
 fn compile_fail() {
     let t = trybuild::TestCases::new();
     t.compile_fail("tests/ui/*.rs");
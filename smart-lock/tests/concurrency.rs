@@ -44,6 +44,81 @@ async fn writer_excludes_readers() {
     assert_eq!(*guard.x, 1000);
 }
 
+#[tokio::test]
+async fn waiting_writer_blocks_new_readers() {
+    let state = Arc::new(SharedLock::new(0, 0));
+    let reader = state.read_x().await;
+    let writer = tokio::spawn({
+        let state = state.clone();
+        async move { *state.write_x().await += 1 }
+    });
+    tokio::task::yield_now().await;
+
+    // The queued writer turns new readers of `x` away, so they can't starve it.
+    assert!(state.builder().read_x().try_lock().is_none());
+    assert!(state.builder().read_y().try_lock().is_some());
+    drop(reader);
+    writer.await.unwrap();
+    assert_eq!(*state.read_x().await, 1);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn reader_swarm_does_not_starve_a_writer() {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::time::Duration;
+
+    let state = Arc::new(SharedLock::new(0, 0));
+    let stop = Arc::new(AtomicBool::new(false));
+    // Readers overlap constantly: each takes a fresh read lock while others still hold theirs.
+    let readers: Vec<_> = (0..8)
+        .map(|_| {
+            let (state, stop) = (state.clone(), stop.clone());
+            tokio::spawn(async move {
+                while !stop.load(Ordering::Relaxed) {
+                    let guard = state.read_x().await;
+                    tokio::task::yield_now().await;
+                    drop(guard);
+                }
+            })
+        })
+        .collect();
+    tokio::time::sleep(Duration::from_millis(10)).await;
+
+    let writes = tokio::time::timeout(Duration::from_secs(5), async {
+        for _ in 0..100 {
+            *state.write_x().await += 1;
+        }
+    })
+    .await;
+    stop.store(true, Ordering::Relaxed);
+    assert!(writes.is_ok(), "the writer was starved by readers");
+    for reader in readers {
+        reader.await.unwrap();
+    }
+    assert_eq!(*state.read_x().await, 100);
+}
+
+#[tokio::test]
+async fn yield_then_relock_lets_queued_readers_in() {
+    let state = Arc::new(SharedLock::new(0, 0));
+    let mut guard = state.builder().write_x().read_y().lock().await;
+    let reader = tokio::spawn({
+        let state = state.clone();
+        async move { *state.read_x().await }
+    });
+    tokio::task::yield_now().await;
+    assert!(!reader.is_finished());
+
+    for _ in 0..3 {
+        *guard.x += 1;
+        guard = guard.yield_then_relock().await;
+    }
+    // The reader ran during the first yield, between two writes.
+    assert!(reader.is_finished());
+    assert_eq!(reader.await.unwrap(), 1);
+    assert_eq!((*guard.x, *guard.y), (3, 0));
+}
+
 #[tokio::test]
 async fn different_fields_writable_concurrently() {
     let state = Arc::new(SharedLock::new(0, 0));
@@ -147,3 +222,19 @@ async fn upgrade_under_contention() {
     let guard = state.lock_all().await;
     assert_eq!(*guard.x, 500);
 }
+
+#[tokio::test]
+async fn guard_held_across_await_in_spawned_task() {
+    let state = Arc::new(SharedLock::new(0, 0));
+
+    let s = state.clone();
+    let task = tokio::spawn(async move {
+        // The guard borrows `s`, which the task owns, so no owned guard is needed.
+        let mut guard = s.builder().write_x().read_y().lock().await;
+        tokio::task::yield_now().await;
+        *guard.x += *guard.y + 1;
+    });
+    task.await.unwrap();
+
+    assert_eq!(*state.read_x().await, 1);
+}
@@ -0,0 +1,93 @@
+#![cfg(all(feature = "debug-deadlock", debug_assertions))]
+
+use smart_lock::smart_lock;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+#[smart_lock]
+struct Pair {
+    first: u32,
+    second: u32,
+    #[no_lock]
+    id: u32,
+}
+
+fn pair() -> PairLock {
+    PairLock::new(0, 0, 1)
+}
+
+#[tokio::test]
+async fn upgrading_the_last_held_field_is_allowed() {
+    let state = pair();
+    let guard = state.builder().read_first().upgrade_second().lock().await;
+    let mut guard = guard.upgrade_second().await;
+    *guard.second = *guard.first + 1;
+    drop(guard);
+
+    let mut guard = state
+        .builder()
+        .upgrade_first()
+        .lock_then_upgrade_first()
+        .await;
+    *guard.first = 2;
+    assert_eq!(*guard.id, 1);
+}
+
+#[tokio::test]
+#[should_panic(
+    expected = "potential deadlock: a `PairLock` guard waits to upgrade `first` \
+                           while holding `second`"
+)]
+async fn upgrading_before_a_held_field_panics() {
+    let state = pair();
+    let guard = state.builder().upgrade_first().read_second().lock().await;
+    let _ = guard.upgrade_first().await;
+}
+
+#[tokio::test]
+#[should_panic(expected = "holding `second`")]
+async fn upgrade_all_panics_with_several_fields() {
+    let state = pair();
+    let _ = state.lock_all_upgradable().await.upgrade_all().await;
+}
+
+#[tokio::test]
+#[should_panic(expected = "waits to upgrade `first` while holding `second`")]
+async fn upgrade_subset_checks_each_upgraded_field() {
+    let state = pair();
+    let guard = state.lock_all_upgradable().await;
+    let _ = smart_lock::upgrade_subset!(guard, first).await;
+}
+
+#[tokio::test]
+async fn upgrade_subset_of_the_last_field_is_allowed() {
+    let state = pair();
+    let guard = state.lock_all_upgradable().await;
+    let mut guard = smart_lock::upgrade_subset!(guard, second).await;
+    *guard.second = *guard.first + 1;
+}
+
+#[tokio::test]
+async fn non_blocking_upgrades_are_not_checked() {
+    let state = pair();
+    let guard = state.lock_all_upgradable().await;
+    let mut guard = guard.try_upgrade_all().ok().unwrap();
+    *guard.second = 3;
+    drop(guard);
+
+    let guard = state.builder().upgrade_first().read_second().lock().await;
+    let mut guard = guard.try_upgrade_first().ok().unwrap();
+    *guard.first = 4;
+}
+
+#[tokio::test]
+async fn panic_releases_the_guard() {
+    let state = pair();
+    let panicked = catch_unwind(AssertUnwindSafe(|| {
+        pollster::block_on(async {
+            let guard = state.builder().upgrade_first().write_second().lock().await;
+            let _ = guard.upgrade_first().await;
+        })
+    }));
+    assert!(panicked.is_err());
+    assert!(state.try_lock_all_mut().is_some());
+}
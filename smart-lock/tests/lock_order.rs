@@ -0,0 +1,221 @@
+use smart_lock::{enforce_order, smart_lock};
+use std::sync::Arc;
+
+fn block_on<F: std::future::Future>(f: F) -> F::Output {
+    pollster::block_on(f)
+}
+
+#[smart_lock]
+struct Account {
+    balance: i64,
+    owner: String,
+}
+
+fn account(balance: i64) -> AccountLock {
+    AccountLock::new(balance, String::new())
+}
+
+#[test]
+fn consistent_order_is_allowed() {
+    let (a, b) = (account(10), account(20));
+    block_on(async {
+        for _ in 0..3 {
+            enforce_order! {
+                let mut from = a.lock_all_mut().await;
+                let to = b.builder().read_balance().lock().await;
+            }
+            *from.balance += *to.balance;
+        }
+    });
+    assert_eq!(*a.try_read_balance().unwrap(), 70);
+}
+
+#[test]
+fn bindings_and_receivers() {
+    let shared = Arc::new(AccountLock::new(1, "arc".into()));
+    let plain = AccountLock::new(2, "plain".into());
+    block_on(async {
+        enforce_order! {
+            let x = shared.lock_all().await;
+            let y = (&plain).builder().read_owner().lock().await;
+        }
+        assert_eq!(*x.balance, 1);
+        assert_eq!(*y.owner, "plain");
+    });
+}
+
+#[test]
+#[cfg_attr(
+    debug_assertions,
+    should_panic(expected = "lock order violation: `a` is acquired after `b`")
+)]
+fn inconsistent_order_panics_in_debug() {
+    let (a, b) = (account(1), account(2));
+    block_on(async {
+        {
+            enforce_order! {
+                let _first = a.lock_all().await;
+                let _second = b.lock_all().await;
+            }
+        }
+        enforce_order! {
+            let _first = b.lock_all().await;
+            let _second = a.lock_all().await;
+        }
+    });
+}
+
+#[test]
+fn same_lock_twice_is_not_an_inversion() {
+    let a = account(1);
+    block_on(async {
+        enforce_order! {
+            let x = a.builder().read_balance().lock().await;
+            let y = a.builder().read_owner().lock().await;
+        }
+        assert_eq!(*x.balance, 1);
+        assert_eq!(*y.owner, "");
+    });
+}
+
+#[test]
+#[cfg_attr(
+    debug_assertions,
+    should_panic(expected = "lock order violation: `a` is acquired after `c`")
+)]
+fn transitive_cycle_panics_in_debug() {
+    let (a, b, c) = (account(1), account(2), account(3));
+    block_on(async {
+        {
+            enforce_order! {
+                let _first = a.lock_all().await;
+                let _second = b.lock_all().await;
+            }
+        }
+        {
+            enforce_order! {
+                let _first = b.lock_all().await;
+                let _second = c.lock_all().await;
+            }
+        }
+        enforce_order! {
+            let _first = c.lock_all().await;
+            let _second = a.lock_all().await;
+        }
+    });
+}
+
+#[test]
+fn dropped_locks_leave_no_stale_order() {
+    block_on(async {
+        for _ in 0..3 {
+            let (a, b) = (account(1), account(2));
+            {
+                enforce_order! {
+                    let _first = a.lock_all().await;
+                    let _second = b.lock_all().await;
+                }
+            }
+            drop((a, b));
+            // Fresh locks may reuse the old addresses but get new instance ids.
+            let (c, d) = (account(3), account(4));
+            enforce_order! {
+                let _first = d.lock_all().await;
+                let _second = c.lock_all().await;
+            }
+        }
+    });
+}
+
+#[test]
+#[cfg_attr(
+    debug_assertions,
+    should_panic(expected = "lock order violation: `a` is acquired after `c`")
+)]
+fn order_through_a_dropped_lock_still_holds() {
+    let (a, c) = (account(1), account(3));
+    block_on(async {
+        {
+            let b = account(2);
+            {
+                enforce_order! {
+                    let _first = a.lock_all().await;
+                    let _second = b.lock_all().await;
+                }
+            }
+            enforce_order! {
+                let _first = b.lock_all().await;
+                let _second = c.lock_all().await;
+            }
+        }
+        enforce_order! {
+            let _first = c.lock_all().await;
+            let _second = a.lock_all().await;
+        }
+    });
+}
+
+// --- lock_all! ---
+
+#[smart_lock]
+struct Ledger {
+    total: i64,
+}
+
+#[test]
+fn lock_all_macro_returns_guards_in_entry_order() {
+    let shared = Arc::new(account(5));
+    let ledger = LedgerLock::new(0);
+    let other = account(7);
+    block_on(async {
+        let (mut l, mut a, b) = smart_lock::lock_all!(
+            (&ledger => write_total),
+            (&shared => write_balance read_owner),
+            (&other => read_balance),
+        );
+        *a.balance -= 2;
+        *l.total += 2 + *b.balance;
+        assert_eq!(*a.owner, "");
+    });
+    assert_eq!(*shared.try_read_balance().unwrap(), 3);
+    assert_eq!(*ledger.try_read_total().unwrap(), 9);
+}
+
+#[test]
+fn lock_all_macro_orders_across_locks() {
+    let a = Arc::new(account(0));
+    let b = Arc::new(account(0));
+    let threads: Vec<_> = (0..4)
+        .map(|i| {
+            let (a, b) = (a.clone(), b.clone());
+            std::thread::spawn(move || {
+                // Half the threads list the locks in the opposite order.
+                let (first, second) = if i % 2 == 0 { (&a, &b) } else { (&b, &a) };
+                for _ in 0..200 {
+                    block_on(async {
+                        let (mut x, mut y) = smart_lock::lock_all!(
+                            (first => write_balance),
+                            (second => write_balance),
+                        );
+                        *x.balance += 1;
+                        *y.balance -= 1;
+                    });
+                }
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    assert_eq!(*a.try_read_balance().unwrap(), 0);
+    assert_eq!(*b.try_read_balance().unwrap(), 0);
+}
+
+#[test]
+#[should_panic(expected = "lock_all! lists the same lock more than once")]
+fn lock_all_macro_rejects_duplicates() {
+    let a = account(0);
+    block_on(async {
+        let _ = smart_lock::lock_all!((&a => read_balance), (&a => read_owner));
+    });
+}
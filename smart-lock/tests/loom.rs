@@ -0,0 +1,92 @@
+//! Model-checked acquisition tests. Run with:
+//!
+//! ```text
+//! RUSTFLAGS="--cfg loom" cargo test -p smart-lock --features loom --test loom --release
+//! ```
+//!
+//! With `--cfg loom`, `async-lock` builds its `RwLock` on loom's atomics, so
+//! `loom::model` explores every interleaving of the threads below and fails on
+//! any that deadlocks.
+#![cfg(loom)]
+
+use loom::future::block_on;
+use loom::sync::Arc;
+use loom::thread;
+use smart_lock::smart_lock;
+
+#[smart_lock]
+struct Pair {
+    a: u32,
+    b: u32,
+}
+
+#[test]
+fn builder_calls_in_opposite_orders_never_deadlock() {
+    loom::model(|| {
+        let state = Arc::new(PairLock::new(0, 0));
+        let other = {
+            let state = state.clone();
+            thread::spawn(move || {
+                block_on(async {
+                    let mut guard = state.builder().write_b().write_a().lock().await;
+                    *guard.a += 1;
+                    *guard.b += 1;
+                })
+            })
+        };
+        block_on(async {
+            let mut guard = state.builder().write_a().write_b().lock().await;
+            *guard.a += 1;
+            *guard.b += 1;
+        });
+        other.join().unwrap();
+
+        let guard = block_on(state.lock_all());
+        assert_eq!((*guard.a, *guard.b), (2, 2));
+    });
+}
+
+/// The reader only takes read locks. Had it also write-locked `b`, loom would
+/// report the upgrade deadlock described under "Upgradable Locks" in the README.
+#[test]
+fn upgrade_alongside_readers_never_deadlocks() {
+    loom::model(|| {
+        let state = Arc::new(PairLock::new(0, 0));
+        let reader = {
+            let state = state.clone();
+            thread::spawn(move || {
+                block_on(async {
+                    let guard = state.builder().read_a().read_b().lock().await;
+                    *guard.a
+                })
+            })
+        };
+        block_on(async {
+            let guard = state.builder().upgrade_a().read_b().lock().await;
+            let mut guard = guard.upgrade_a().await;
+            *guard.a = 7;
+        });
+        let seen = reader.join().unwrap();
+        assert!(seen == 0 || seen == 7);
+    });
+}
+
+#[test]
+fn try_lock_never_blocks() {
+    loom::model(|| {
+        let state = Arc::new(PairLock::new(0, 0));
+        let writer = {
+            let state = state.clone();
+            thread::spawn(move || {
+                block_on(async {
+                    let mut guard = state.lock_all_mut().await;
+                    *guard.b = 1;
+                })
+            })
+        };
+        if let Some(guard) = state.builder().read_a().read_b().try_lock() {
+            assert!(*guard.b == 0 || *guard.b == 1);
+        }
+        writer.join().unwrap();
+    });
+}
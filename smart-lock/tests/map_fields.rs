@@ -0,0 +1,94 @@
+//! Map-typed fields used through guards: method calls resolve through
+//! `Deref`/`DerefMut`, so the collection APIs work on `guard.field` directly.
+
+use smart_lock::{smart_lock, FieldGuard, WriteLocked};
+use std::collections::{BTreeMap, HashMap};
+
+#[smart_lock]
+struct Registry {
+    by_name: HashMap<String, u32>,
+    by_id: BTreeMap<u32, String>,
+}
+
+fn registry() -> RegistryLock {
+    RegistryLock::new(
+        HashMap::from([("a".into(), 1), ("b".into(), 2), ("c".into(), 3)]),
+        BTreeMap::from([(1, "a".into()), (2, "b".into())]),
+    )
+}
+
+#[tokio::test]
+async fn entry_api_on_write_guard() {
+    let state = registry();
+    let mut guard = state.builder().write_by_name().write_by_id().lock().await;
+
+    *guard.by_name.entry("a".into()).or_insert(0) += 10;
+    guard.by_name.entry("z".into()).or_insert(26);
+    guard
+        .by_name
+        .entry("b".into())
+        .and_modify(|v| *v *= 2)
+        .or_default();
+    guard.by_id.entry(3).or_insert_with(|| "c".into());
+    if let Some(mut first) = guard.by_id.first_entry() {
+        first.get_mut().push('!');
+    }
+
+    assert_eq!(guard.by_name["a"], 11);
+    assert_eq!(guard.by_name["b"], 4);
+    assert_eq!(guard.by_name["z"], 26);
+    assert_eq!(guard.by_id[&1], "a!");
+    assert_eq!(guard.by_id[&3], "c");
+}
+
+#[tokio::test]
+async fn get_mut_remove_and_retain_on_write_guard() {
+    let state = registry();
+    let mut guard = state.builder().write_by_name().write_by_id().lock().await;
+
+    if let Some(v) = guard.by_name.get_mut("c") {
+        *v = 30;
+    }
+    assert_eq!(guard.by_name.remove("a"), Some(1));
+    assert_eq!(guard.by_name.remove("missing"), None);
+    guard.by_name.retain(|_, v| *v > 2);
+    guard.by_id.retain(|k, _| k % 2 == 0);
+
+    assert_eq!(*guard.by_name, HashMap::from([("c".into(), 30)]));
+    assert_eq!(guard.by_id.keys().copied().collect::<Vec<_>>(), [2]);
+    drop(guard);
+    assert_eq!(state.read_by_name().await.len(), 1);
+}
+
+#[tokio::test]
+async fn lookups_and_iteration_on_read_guard() {
+    let state = registry();
+    let guard = state.lock_all().await;
+
+    assert_eq!(guard.by_name.get("b"), Some(&2));
+    assert!(guard.by_name.contains_key("c"));
+    assert_eq!(guard.by_id.range(2..).count(), 1);
+    let mut names: Vec<&String> = guard.by_name.keys().collect();
+    names.sort();
+    assert_eq!(names, ["a", "b", "c"]);
+    // `for` needs the collection itself, so deref the guard explicitly.
+    let mut total = 0;
+    for (name, v) in &*guard.by_name {
+        total += v * name.len() as u32;
+    }
+    assert_eq!(total, 6);
+}
+
+/// Helpers written against the map type accept a guard via deref coercion.
+fn bump_all(map: &mut HashMap<String, u32>) {
+    map.values_mut().for_each(|v| *v += 1);
+}
+
+#[tokio::test]
+async fn guards_coerce_to_map_references() {
+    let state = registry();
+    let mut guard: FieldGuard<'_, HashMap<String, u32>, WriteLocked> =
+        state.builder().write_by_name().lock().await.by_name;
+    bump_all(&mut guard);
+    assert_eq!(guard["c"], 4);
+}
@@ -486,3 +486,235 @@ fn zst_field_into_inner() {
     assert_eq!(original.value, 99);
     assert_eq!(original.marker, ());
 }
+
+// --- Transition matrix ---
+//
+// Every public way to build or transition a guard, from every starting mode, must
+// keep the inner lock in step with the mode marker so that no `unreachable!()` arm
+// in `guard.rs` is hit. Verified matrix:
+//
+// | From            | Transition                          | To                      |
+// |-----------------|-------------------------------------|-------------------------|
+// | —               | acquire / try_acquire (each mode)   | that mode               |
+// | —               | unlocked()                          | Unlocked                |
+// | UpgradeLocked   | upgrade().await                     | WriteLocked             |
+// | UpgradeLocked   | try_upgrade() (free / contended)    | WriteLocked / Err(self) |
+// | UpgradeLocked   | downgrade()                         | ReadLocked              |
+// | WriteLocked     | downgrade()                         | ReadLocked              |
+// | ReadLocked      | map(f)                              | ReadLocked (mapped)     |
+// | UpgradeLocked   | map(f)                              | UpgradeLocked (mapped)  |
+// | WriteLocked     | map(f, f_mut)                       | WriteLocked (mapped)    |
+//
+// The generated guard transitions are checked per locked field of a struct whose
+// locked fields are interleaved with `#[no_lock]` fields, with the other fields
+// both `Unlocked` and filled by `lock_rest_read()`.
+
+use smart_lock::{FieldGuard, ReadLocked, RwLock, Unlocked, UpgradeLocked, WriteLocked};
+
+#[test]
+fn field_guard_constructors_match_mode() {
+    block_on(async {
+        let lock = RwLock::new(7u32);
+
+        let unlocked = FieldGuard::<'_, u32, Unlocked>::unlocked();
+        assert_eq!(format!("{:?}", unlocked), "<unlocked>");
+        let acquired = FieldGuard::<'_, u32, Unlocked>::acquire(&lock).await;
+        assert_eq!(format!("{:?}", acquired), "<unlocked>");
+        drop((unlocked, acquired));
+
+        let read = FieldGuard::<'_, u32, ReadLocked>::acquire(&lock).await;
+        assert_eq!(*read, 7);
+        drop(read);
+        let read = FieldGuard::<'_, u32, ReadLocked>::try_acquire(&lock).unwrap();
+        assert_eq!(*read, 7);
+        drop(read);
+
+        let mut write = FieldGuard::<'_, u32, WriteLocked>::acquire(&lock).await;
+        *write += 1;
+        drop(write);
+        let mut write = FieldGuard::<'_, u32, WriteLocked>::try_acquire(&lock).unwrap();
+        *write += 1;
+        drop(write);
+
+        let upgrade = FieldGuard::<'_, u32, UpgradeLocked>::acquire(&lock).await;
+        assert_eq!(*upgrade, 9);
+        drop(upgrade);
+        let upgrade = FieldGuard::<'_, u32, UpgradeLocked>::try_acquire(&lock).unwrap();
+        assert_eq!(*upgrade, 9);
+    });
+}
+
+#[test]
+fn field_guard_transitions_match_mode() {
+    block_on(async {
+        let lock = RwLock::new(vec![1u32, 2]);
+
+        // Upgrade -> Write -> Read
+        let guard = FieldGuard::<'_, _, UpgradeLocked>::acquire(&lock).await;
+        let mut guard = guard.upgrade().await;
+        guard.push(3);
+        let guard = guard.downgrade();
+        assert_eq!(*guard, [1, 2, 3]);
+        drop(guard);
+
+        // Upgrade -> try_upgrade (uncontended) -> Write
+        let guard = FieldGuard::<'_, _, UpgradeLocked>::acquire(&lock).await;
+        let mut guard = guard.try_upgrade().unwrap();
+        guard.pop();
+        drop(guard);
+
+        // Upgrade -> try_upgrade (contended) -> Err(Upgrade) -> Read
+        let reader = lock.read().await;
+        let guard = FieldGuard::<'_, _, UpgradeLocked>::acquire(&lock).await;
+        let guard = guard.try_upgrade().unwrap_err();
+        assert_eq!(*guard, [1, 2]);
+        let guard = guard.downgrade();
+        assert_eq!(*guard, [1, 2]);
+        drop((guard, reader));
+
+        // Map from each readable mode
+        let read = FieldGuard::<'_, _, ReadLocked>::acquire(&lock).await;
+        let mapped = FieldGuard::map(read, |v| &v[0]);
+        assert_eq!(*mapped, 1);
+        drop(mapped);
+
+        let upgrade = FieldGuard::<'_, _, UpgradeLocked>::acquire(&lock).await;
+        let mapped = FieldGuard::map(upgrade, |v| &v[1]);
+        assert_eq!(*mapped, 2);
+        drop(mapped);
+
+        let write = FieldGuard::<'_, _, WriteLocked>::acquire(&lock).await;
+        let mut mapped = FieldGuard::map(write, (|v| &v[1], |v| &mut v[1]));
+        *mapped = 20;
+        drop(mapped);
+        assert_eq!(*lock.read().await, [1, 20]);
+    });
+}
+
+#[smart_lock]
+struct Interleaved {
+    a: u32,
+    #[no_lock]
+    b: AtomicU32,
+    c: String,
+    #[no_lock]
+    d: u8,
+    e: Vec<u8>,
+}
+
+fn interleaved() -> InterleavedLock {
+    InterleavedLock::new(1, AtomicU32::new(2), "c".into(), 4, vec![5])
+}
+
+macro_rules! assert_no_lock_fields {
+    ($guard:expr) => {
+        assert_eq!($guard.b.load(Ordering::Relaxed), 2);
+        assert_eq!(*$guard.d, 4);
+    };
+}
+
+macro_rules! assert_all_fields {
+    ($guard:expr) => {
+        assert_eq!(*$guard.a, 1);
+        assert_eq!(*$guard.c, "c");
+        assert_eq!(*$guard.e, [5]);
+        assert_no_lock_fields!($guard);
+    };
+}
+
+macro_rules! transition_matrix {
+    (
+        $(#[$attr:meta])*
+        $test:ident, $field:ident,
+        builder: $write:ident, $upgrade:ident,
+        guard: $up:ident, $try_up:ident, $down:ident
+    ) => {
+        #[test]
+        $(#[$attr])*
+        fn $test() {
+            block_on(async {
+                let state = interleaved();
+
+                // Other fields Unlocked.
+                let guard = state.builder().$upgrade().lock().await;
+                let initial = (*guard.$field).clone();
+                let mut guard = guard.$up().await;
+                *guard.$field = initial.clone();
+                let guard = guard.$down();
+                assert_eq!(*guard.$field, initial);
+                assert_no_lock_fields!(guard);
+
+                let guard = guard.relock().$upgrade().lock().await;
+                let mut guard = guard.$try_up().unwrap();
+                *guard.$field = initial.clone();
+                assert_no_lock_fields!(guard);
+
+                let guard = guard.relock().$upgrade().lock().await;
+                let guard = guard.$down();
+                assert_eq!(*guard.$field, initial);
+
+                let mut guard = guard.relock().$write().lock().await;
+                *guard.$field = initial.clone();
+                let guard = guard.$down();
+                assert_eq!(*guard.$field, initial);
+                drop(guard);
+
+                // Other fields filled with read locks.
+                let guard = state.builder().$upgrade().lock_rest_read().await;
+                assert_all_fields!(guard);
+                let mut guard = guard.$up().await;
+                *guard.$field = initial.clone();
+                let guard = guard.$down();
+                assert_all_fields!(guard);
+
+                let guard = guard.relock().$upgrade().lock_rest_read().await;
+                let mut guard = guard.$try_up().unwrap();
+                *guard.$field = initial.clone();
+                assert_all_fields!(guard);
+
+                let guard = guard.relock().$upgrade().lock_rest_read().await;
+                let guard = guard.$down();
+                assert_all_fields!(guard);
+
+                let mut guard = guard.relock().$write().lock_rest_read().await;
+                *guard.$field = initial.clone();
+                let guard = guard.$down();
+                assert_all_fields!(guard);
+                drop(guard);
+
+                // Contended try_upgrade hands back the original guard.
+                let reader = state.$field.read().await;
+                let guard = state.builder().$upgrade().lock_rest_read().await;
+                let guard = guard.$try_up().unwrap_err();
+                assert_all_fields!(guard);
+                let guard = guard.$down();
+                assert_all_fields!(guard);
+                drop((guard, reader));
+            });
+        }
+    };
+}
+
+transition_matrix!(
+    #[cfg_attr(
+        feature = "debug-deadlock",
+        ignore = "upgrades a field while holding one acquired after it"
+    )]
+    transitions_first_field, a,
+    builder: write_a, upgrade_a,
+    guard: upgrade_a, try_upgrade_a, downgrade_a
+);
+transition_matrix!(
+    #[cfg_attr(
+        feature = "debug-deadlock",
+        ignore = "upgrades a field while holding one acquired after it"
+    )]
+    transitions_middle_field, c,
+    builder: write_c, upgrade_c,
+    guard: upgrade_c, try_upgrade_c, downgrade_c
+);
+transition_matrix!(
+    transitions_last_field, e,
+    builder: write_e, upgrade_e,
+    guard: upgrade_e, try_upgrade_e, downgrade_e
+);
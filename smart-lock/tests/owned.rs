@@ -0,0 +1,344 @@
+use smart_lock::{smart_lock, OwnedFieldGuard, ReadLocked, UpgradeLocked, WriteLocked};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+#[smart_lock(owned)]
+struct Actor {
+    count: u64,
+    log: Vec<String>,
+    #[no_lock]
+    hits: AtomicU32,
+}
+
+fn actor() -> ActorLock {
+    ActorLock::new(0, vec![], AtomicU32::new(0))
+}
+
+#[tokio::test]
+async fn owned_field_guard_moves_into_spawned_task() {
+    let state = actor();
+    let mut count = state.write_count_owned().await;
+    let task = tokio::spawn(async move {
+        tokio::task::yield_now().await;
+        *count += 1;
+    });
+    task.await.unwrap();
+    assert_eq!(*state.read_count().await, 1);
+}
+
+#[tokio::test]
+async fn owned_guard_debug_shows_locked_fields() {
+    let state = actor();
+    let guard = state.builder().read_count().lock_owned().await;
+    assert_eq!(
+        format!("{guard:?}"),
+        "ActorLockOwnedGuard { count: 0, log: <unlocked> }"
+    );
+}
+
+#[tokio::test]
+async fn lock_owned_guard_outlives_the_borrow() {
+    let state = Arc::new(actor());
+    let guard = state.builder().write_log().read_count().lock_owned().await;
+    assert!(state.try_write_log().is_none());
+    assert!(state.try_read_count().is_some());
+
+    let task = tokio::spawn(async move {
+        let mut guard = guard;
+        tokio::task::yield_now().await;
+        let n = *guard.count;
+        guard.log.push(format!("seen {n}"));
+    });
+    task.await.unwrap();
+
+    assert_eq!(*state.read_log().await, ["seen 0"]);
+    state.hits.fetch_add(1, Ordering::Relaxed);
+}
+
+#[tokio::test]
+async fn try_lock_owned_releases_on_contention() {
+    let state = actor();
+    let held = state.write_log_owned().await;
+    assert!(state
+        .builder()
+        .write_count()
+        .read_log()
+        .try_lock_owned()
+        .is_none());
+    // `count` was acquired first and must have been released again.
+    assert!(state.try_write_count().is_some());
+    drop(held);
+    assert!(state
+        .builder()
+        .write_count()
+        .read_log()
+        .try_lock_owned()
+        .is_some());
+}
+
+#[tokio::test]
+async fn owned_field_guard_transitions() {
+    let state = actor();
+    let guard = state.builder().upgrade_count().lock_owned().await.count;
+    let mut guard: OwnedFieldGuard<u64, WriteLocked> = guard.upgrade().await;
+    *guard = 5;
+    let guard: OwnedFieldGuard<u64, ReadLocked> = guard.downgrade();
+    assert_eq!(*guard, 5);
+    assert!(state.try_read_count().is_some());
+    drop(guard);
+
+    let upgrade = state.builder().upgrade_count().lock_owned().await.count;
+    let reader = state.read_count().await;
+    let upgrade: OwnedFieldGuard<u64, UpgradeLocked> = upgrade.try_upgrade().unwrap_err();
+    drop(reader);
+    assert!(upgrade.try_upgrade().is_ok());
+}
+
+#[tokio::test]
+async fn exclusive_access_and_into_inner_after_guards_drop() {
+    let mut state = actor();
+    let guard = state.read_count_owned().await;
+    drop(guard);
+    *state.get_mut_count() += 2;
+    state.get_mut_log().push("extended".into());
+    let inner = state.into_inner();
+    assert_eq!(inner.count, 2);
+    assert_eq!(inner.log, ["extended"]);
+}
+
+#[tokio::test]
+async fn from_parts_wraps_prebuilt_locks_in_arcs() {
+    let state = ActorLock::from_parts(
+        smart_lock::RwLock::new(5),
+        smart_lock::RwLock::new(vec!["restored".into()]),
+        AtomicU32::new(1),
+    );
+    let guard = state.builder().write_count().lock_owned().await;
+    assert_eq!(*guard.count, 5);
+    drop(guard);
+    assert_eq!(state.hits.load(Ordering::Relaxed), 1);
+    assert_eq!(state.into_inner().log, ["restored"]);
+}
+
+#[tokio::test]
+#[should_panic(expected = "`count` is still locked by an owned guard")]
+async fn get_mut_panics_while_owned_guard_alive() {
+    let mut state = actor();
+    let _guard = state.read_count_owned().await;
+    let _ = state.get_mut_count();
+}
+
+#[tokio::test]
+#[should_panic(expected = "`log` is still locked by an owned guard")]
+async fn get_all_mut_panics_while_owned_guard_alive() {
+    let mut state = actor();
+    let _guard = state.read_log_owned().await;
+    let _ = state.get_all_mut();
+}
+
+#[tokio::test]
+#[should_panic(expected = "`log` is still locked by an owned guard")]
+async fn into_inner_panics_while_owned_guard_alive() {
+    let state = actor();
+    let _guard = state.builder().read_log().lock_owned().await;
+    let _ = state.into_inner();
+}
+
+#[tokio::test]
+async fn field_arc_shares_one_field() {
+    let state = actor();
+    let count = state.field_arc_count();
+    let same = state.field_arc_count();
+    assert!(Arc::ptr_eq(&count, &same));
+
+    let subsystem = tokio::spawn(async move { *count.write().await += 5 });
+    subsystem.await.unwrap();
+    *same.write().await += 1;
+    assert_eq!(*state.read_count().await, 6);
+
+    // The rest of the struct is untouched and still lockable on its own.
+    let guard = state.builder().write_log().lock().await;
+    assert!(guard.log.is_empty());
+    drop(guard);
+    assert!(state.try_lock_all_mut().is_some());
+}
+
+#[tokio::test]
+#[should_panic(expected = "`count` is still locked by an owned guard")]
+async fn get_mut_panics_while_field_arc_alive() {
+    let mut state = actor();
+    let _count = state.field_arc_count();
+    let _ = state.get_mut_count();
+}
+
+#[smart_lock(owned)]
+struct Pair<T: Send + Sync> {
+    left: T,
+    right: T,
+}
+
+#[tokio::test]
+async fn generic_struct_owned_guard() {
+    let state = PairLock::from(Pair {
+        left: String::from("l"),
+        right: String::from("r"),
+    });
+    let mut guard = state.builder().write_left().read_right().lock_owned().await;
+    guard.left.push_str(&guard.right);
+    drop(guard);
+    assert_eq!(*state.read_left().await, "lr");
+}
+
+#[smart_lock(owned)]
+struct Window<const N: usize> {
+    samples: [u64; N],
+    next: usize,
+}
+
+#[tokio::test]
+async fn const_generic_struct_owned_guard() {
+    let state = WindowLock::<8>::new([0; 8], 0);
+    let mut guard = state
+        .builder()
+        .write_samples()
+        .write_next()
+        .lock_owned()
+        .await;
+    tokio::spawn(async move {
+        guard.samples[*guard.next] = 5;
+        *guard.next += 1;
+    })
+    .await
+    .unwrap();
+    let inner: Window<8> = state.into_inner();
+    assert_eq!((inner.samples[0], inner.next), (5, 1));
+}
+
+#[tokio::test]
+async fn borrowed_guards_still_work_in_owned_mode() {
+    let state = actor();
+    {
+        let mut guard = state.lock_all_mut().await;
+        *guard.count = 9;
+    }
+    assert_eq!(*state.raw_count().read().await, 9);
+}
+
+#[smart_lock(owned)]
+struct Blob {
+    #[boxed]
+    bytes: Box<[u8; 256]>,
+}
+
+#[tokio::test]
+async fn boxed_field_owned_guard_derefs_to_inner() {
+    let state = BlobLock::new(Box::new([0; 256]));
+    let guard = state.builder().write_bytes().lock_owned().await;
+    let mut bytes: OwnedFieldGuard<[u8; 256], WriteLocked> = guard.bytes;
+    tokio::spawn(async move { bytes[1] = 2 }).await.unwrap();
+    let inner: Box<[u8; 256]> = state.into_inner().bytes;
+    assert_eq!(inner[..2], [0, 2]);
+}
+
+#[smart_lock(owned)]
+struct Nothing {}
+
+#[tokio::test]
+async fn owned_guards_for_a_struct_without_fields() {
+    let state = NothingLock::new();
+    let guard = state.builder().lock_owned().await;
+    tokio::spawn(async move { drop(guard) }).await.unwrap();
+    assert!(state.builder().try_lock_owned().is_some());
+    let Nothing {} = state.into_inner();
+}
+
+#[smart_lock(owned)]
+struct Feed<I>
+where
+    I: Iterator + Send + Sync + 'static,
+    I::Item: Send + Sync,
+{
+    source: I,
+    seen: Vec<I::Item>,
+}
+
+#[tokio::test]
+async fn owned_guards_with_associated_type_bounds() {
+    let state = FeedLock::new(0..3u8, vec![]);
+    let mut guard = state
+        .builder()
+        .write_source()
+        .write_seen()
+        .lock_owned()
+        .await;
+    tokio::spawn(async move {
+        let next = guard.source.next().unwrap();
+        guard.seen.push(next);
+    })
+    .await
+    .unwrap();
+    assert_eq!(state.into_inner().seen, [0]);
+}
+
+#[smart_lock(owned, suffix = "Shared")]
+struct Job {
+    done: bool,
+}
+
+#[tokio::test]
+async fn suffix_renames_the_owned_guard() {
+    let state = JobShared::new(false);
+    let mut guard: JobSharedOwnedGuard<WriteLocked> =
+        state.builder().write_done().lock_owned().await;
+    *guard.done = true;
+    drop(guard);
+    assert!(state.into_inner().done);
+}
+
+/// Only `log` is `#[lock(owned)]`; `count` keeps the plain `RwLock` layout.
+#[smart_lock]
+struct Worker {
+    count: u64,
+    #[lock(owned)]
+    log: Vec<String>,
+    #[lock(owned)]
+    #[boxed]
+    scratch: Box<[u8; 64]>,
+}
+
+#[tokio::test]
+async fn per_field_owned_guard_moves_into_spawned_task() {
+    let state = WorkerLock::new(0, vec![], Box::new([0; 64]));
+    let mut log = state.write_log_owned().await;
+    let mut scratch = state.try_write_scratch_owned().unwrap();
+    tokio::spawn(async move {
+        log.push("spawned".into());
+        scratch[0] = 1;
+    })
+    .await
+    .unwrap();
+
+    // The builder and `lock_all*()` treat owned fields like any other.
+    let mut guard = state.lock_all_mut().await;
+    *guard.count += 1;
+    assert_eq!(
+        (&*guard.log, guard.scratch[0]),
+        (&vec!["spawned".to_string()], 1)
+    );
+    drop(guard);
+
+    let shared: Arc<smart_lock::RwLock<Vec<String>>> = state.field_arc_log();
+    shared.write().await.push("shared".into());
+    drop(shared);
+    let inner = state.into_inner();
+    assert_eq!((inner.count, inner.log.len(), inner.scratch[0]), (1, 2, 1));
+}
+
+#[tokio::test]
+#[should_panic(expected = "`log` is still locked by an owned guard")]
+async fn per_field_owned_get_mut_panics_while_an_owned_guard_is_alive() {
+    let mut state = WorkerLock::new(0, vec![], Box::new([0; 64]));
+    let _log = state.read_log_owned().await;
+    state.get_mut_count();
+    state.get_mut_log();
+}
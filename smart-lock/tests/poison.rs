@@ -0,0 +1,234 @@
+use smart_lock::{smart_lock, FieldGuard};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::Arc;
+
+fn block_on<F: std::future::Future>(f: F) -> F::Output {
+    pollster::block_on(f)
+}
+
+#[smart_lock(poison)]
+struct Ledger {
+    balance: i64,
+    entries: Vec<String>,
+    #[no_lock]
+    id: u32,
+}
+
+fn panic_while<F: FnOnce()>(f: F) {
+    let result = catch_unwind(AssertUnwindSafe(f));
+    assert!(result.is_err(), "closure was expected to panic");
+}
+
+#[test]
+fn panic_under_write_guard_poisons_field() {
+    let state = LedgerLock::new(10, vec![], 7);
+    panic_while(|| {
+        block_on(async {
+            let mut b = state.write_balance().await.unwrap();
+            *b = -1;
+            panic!("invariant broken");
+        })
+    });
+    assert!(state.is_poisoned_balance());
+    assert!(!state.is_poisoned_entries());
+
+    let err = block_on(state.write_balance()).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "field poisoned: a thread panicked while holding its write lock"
+    );
+    // The guard is still usable, e.g. to repair the value.
+    let mut g = err.into_inner();
+    assert!(FieldGuard::is_poisoned(&g));
+    assert_eq!(*g, -1);
+    *g = 10;
+    drop(g);
+
+    assert!(matches!(state.try_write_balance(), Some(Err(_))));
+    state.clear_poison_balance();
+    assert!(!state.is_poisoned_balance());
+    assert_eq!(*block_on(state.write_balance()).unwrap(), 10);
+    assert_eq!(state.id, 7);
+}
+
+#[test]
+fn panic_in_modify_poisons_field() {
+    let state = LedgerLock::new(10, vec![], 7);
+    panic_while(|| {
+        let _ = block_on(state.modify_balance(|b| {
+            *b = -1;
+            panic!("invariant broken");
+        }));
+    });
+    assert!(state.is_poisoned_balance());
+    assert!(!state.is_poisoned_entries());
+
+    // A poisoned field is reported, and `f` doesn't run.
+    let err = block_on(state.modify_balance(|_| unreachable!())).unwrap_err();
+    assert_eq!(*err.into_inner(), -1);
+    assert!(block_on(state.fetch_modify_balance(|_| unreachable!())).is_err());
+    assert_eq!(
+        block_on(state.fetch_modify_entries(|e| e.push("ok".into()))).unwrap(),
+        Vec::<String>::new()
+    );
+}
+
+#[test]
+fn compare_and_set_reports_poison_and_poisons_on_panic() {
+    #[derive(Debug)]
+    struct Touchy(i64);
+    impl PartialEq for Touchy {
+        fn eq(&self, other: &Self) -> bool {
+            assert!(other.0 >= 0, "negative comparison");
+            self.0 == other.0
+        }
+    }
+
+    #[smart_lock(poison)]
+    struct Account {
+        balance: Touchy,
+    }
+
+    let state = AccountLock::new(Touchy(1));
+    assert!(!block_on(state.compare_and_set_balance(Touchy(0), Touchy(5))).unwrap());
+    assert!(block_on(state.compare_and_set_balance(Touchy(1), Touchy(5))).unwrap());
+    panic_while(|| {
+        let _ = block_on(state.compare_and_set_balance(Touchy(-1), Touchy(0)));
+    });
+    assert!(state.is_poisoned_balance());
+
+    let err = block_on(state.compare_and_set_balance(Touchy(5), Touchy(6))).unwrap_err();
+    assert_eq!(err.into_inner().0, 5);
+}
+
+#[test]
+fn blocking_write_reports_poison() {
+    let state = LedgerLock::new(10, vec![], 7);
+    panic_while(|| {
+        let _guard = state.write_entries_blocking().unwrap();
+        panic!("invariant broken");
+    });
+    assert!(state.is_poisoned_entries());
+    assert!(state.write_entries_blocking().is_err());
+    assert!(state.read_entries_blocking().is_empty());
+}
+
+#[test]
+fn read_guards_do_not_poison() {
+    let state = LedgerLock::new(1, vec![], 0);
+    panic_while(|| {
+        block_on(async {
+            let _b = state.read_balance().await;
+            let _g = state.builder().read_entries().lock().await;
+            panic!("reader panicked");
+        })
+    });
+    assert!(!state.is_poisoned_balance());
+    assert!(!state.is_poisoned_entries());
+    assert!(block_on(state.write_balance()).is_ok());
+}
+
+#[test]
+fn builder_and_lock_all_guards_poison_written_fields() {
+    let state = LedgerLock::new(1, vec![], 0);
+    panic_while(|| {
+        block_on(async {
+            let mut g = state.builder().write_entries().read_balance().lock().await;
+            g.entries.push("half-written".into());
+            panic!("mid-update");
+        })
+    });
+    assert!(state.is_poisoned_entries());
+    assert!(!state.is_poisoned_balance());
+
+    let g = block_on(state.lock_all_mut());
+    assert!(FieldGuard::is_poisoned(&g.entries));
+    assert!(!FieldGuard::is_poisoned(&g.balance));
+}
+
+#[test]
+fn transitions_arm_and_disarm() {
+    let state = LedgerLock::new(1, vec![], 0);
+
+    // Downgraded before the panic: the field is no longer write-locked.
+    panic_while(|| {
+        block_on(async {
+            let g = state.builder().write_balance().lock().await;
+            let _g = g.downgrade_balance();
+            panic!("after downgrade");
+        })
+    });
+    assert!(!state.is_poisoned_balance());
+
+    // Upgraded before the panic: the field is write-locked again.
+    panic_while(|| {
+        block_on(async {
+            let g = state.builder().upgrade_balance().lock().await;
+            let _g = g.upgrade_balance().await;
+            panic!("after upgrade");
+        })
+    });
+    assert!(state.is_poisoned_balance());
+}
+
+#[test]
+fn lock_without_poison_attribute_is_unchanged() {
+    #[smart_lock]
+    struct Plain {
+        x: u32,
+    }
+
+    let state = PlainLock::new(1);
+    panic_while(|| {
+        block_on(async {
+            let _g = state.builder().write_x().lock().await;
+            panic!("no poisoning here");
+        })
+    });
+    let mut x = block_on(state.write_x());
+    *x += 1;
+    assert_eq!(*x, 2);
+}
+
+#[test]
+fn plain_guards_carry_no_poison_state() {
+    use smart_lock::{NoHook, Poison, WriteLocked};
+    use std::mem::size_of;
+
+    assert_eq!(size_of::<NoHook>(), 0);
+    assert!(
+        size_of::<FieldGuard<'static, u64, WriteLocked>>()
+            < size_of::<FieldGuard<'static, u64, WriteLocked, Poison<'static>>>()
+    );
+}
+
+#[test]
+fn is_poisoned_does_not_shadow_field_methods() {
+    #[smart_lock(poison)]
+    struct Shared {
+        #[lock(nested)]
+        inner: std::sync::Mutex<u32>,
+    }
+
+    let state = SharedLock::new(std::sync::Mutex::new(0));
+    let g = block_on(state.lock_all());
+    // Resolves to `Mutex::is_poisoned` through `Deref`, not the guard's flag.
+    assert!(!g.inner.is_poisoned());
+    assert!(!FieldGuard::is_poisoned(&g.inner));
+}
+
+#[tokio::test]
+async fn panicking_task_poisons_field() {
+    let state = Arc::new(LedgerLock::new(0, vec![], 0));
+    let task = {
+        let state = Arc::clone(&state);
+        tokio::spawn(async move {
+            let mut g = state.write_entries().await.unwrap();
+            g.push("partial".into());
+            panic!("task failed");
+        })
+    };
+    assert!(task.await.unwrap_err().is_panic());
+    assert!(state.is_poisoned_entries());
+    assert!(state.write_entries().await.is_err());
+}
@@ -0,0 +1,219 @@
+#![cfg(feature = "serde")]
+
+use serde::{Deserialize, Serialize};
+use smart_lock::smart_lock;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+#[smart_lock]
+#[derive(Serialize, Deserialize)]
+struct Config {
+    retries: u32,
+    name: String,
+    #[no_lock]
+    hits: AtomicU32,
+}
+
+#[tokio::test]
+async fn serialize_uses_original_field_names() {
+    let state = ConfigLock::new(3, "primary".into(), AtomicU32::new(7));
+    let json = serde_json::to_string(&state).unwrap();
+    assert_eq!(json, r#"{"retries":3,"name":"primary","hits":7}"#);
+}
+
+#[tokio::test]
+async fn serialize_sees_latest_writes() {
+    let state = ConfigLock::new(0, "a".into(), AtomicU32::new(0));
+    {
+        let mut guard = state.builder().write_retries().lock().await;
+        *guard.retries = 5;
+        guard.hits.fetch_add(1, Ordering::Relaxed);
+    }
+    let json = serde_json::to_string(&state).unwrap();
+    assert_eq!(json, r#"{"retries":5,"name":"a","hits":1}"#);
+}
+
+#[tokio::test]
+async fn deserialize_wraps_each_field() {
+    let state: ConfigLock =
+        serde_json::from_str(r#"{"retries":9,"name":"restored","hits":2}"#).unwrap();
+    let guard = state.lock_all().await;
+    assert_eq!(*guard.retries, 9);
+    assert_eq!(*guard.name, "restored");
+    assert_eq!(guard.hits.load(Ordering::Relaxed), 2);
+}
+
+#[tokio::test]
+async fn round_trip() {
+    let state = ConfigLock::new(1, "x".into(), AtomicU32::new(4));
+    let json = serde_json::to_string(&state).unwrap();
+    let restored: ConfigLock = serde_json::from_str(&json).unwrap();
+    assert_eq!(serde_json::to_string(&restored).unwrap(), json);
+}
+
+#[smart_lock]
+#[derive(Serialize, Deserialize)]
+struct Keyword {
+    r#type: String,
+    #[no_lock]
+    r#ref: u32,
+}
+
+#[tokio::test]
+async fn raw_identifier_fields_use_unprefixed_keys() {
+    let state = KeywordLock::new("a".into(), 1);
+    let json = serde_json::to_string(&state).unwrap();
+    assert_eq!(json, r#"{"type":"a","ref":1}"#);
+    let restored: KeywordLock = serde_json::from_str(&json).unwrap();
+    assert_eq!(*restored.read_type().await, "a");
+}
+
+#[tokio::test]
+async fn raw_identifier_snapshot_uses_unprefixed_keys() {
+    let state = KeywordLock::new("b".into(), 2);
+    let snapshot = state.serialize_snapshot().await;
+    assert_eq!(
+        serde_json::to_string(&snapshot).unwrap(),
+        r#"{"type":"b","ref":2}"#
+    );
+}
+
+#[tokio::test]
+async fn raw_identifier_guard_uses_unprefixed_keys() {
+    let state = KeywordLock::new("c".into(), 3);
+    let json = serde_json::to_string(&state.lock_all().await).unwrap();
+    assert_eq!(json, r#"{"type":"c","ref":3}"#);
+}
+
+mod private_fields {
+    use smart_lock::smart_lock;
+
+    #[smart_lock]
+    #[derive(serde::Serialize)]
+    pub struct Account {
+        pub owner: String,
+        pub(crate) balance: u64,
+    }
+
+    pub fn snapshot_owner(state: &AccountLock) -> String {
+        let snapshot = pollster::block_on(state.serialize_snapshot());
+        snapshot.owner
+    }
+}
+
+#[test]
+fn snapshot_fields_keep_the_original_visibility() {
+    let state = private_fields::AccountLock::new("ann".into(), 5);
+    assert_eq!(private_fields::snapshot_owner(&state), "ann");
+    // `pub(crate)` in the original struct, so still reachable from this crate.
+    let snapshot = pollster::block_on(state.serialize_snapshot());
+    assert_eq!(snapshot.balance, 5);
+}
+
+#[smart_lock]
+#[derive(Serialize, Deserialize)]
+struct Pair<T: Send + Sync> {
+    left: T,
+    right: T,
+}
+
+#[tokio::test]
+async fn generic_round_trip() {
+    let state = PairLock::new(vec![1u8], vec![2u8, 3]);
+    let json = serde_json::to_string(&state).unwrap();
+    assert_eq!(json, r#"{"left":[1],"right":[2,3]}"#);
+    let restored: PairLock<Vec<u8>> = serde_json::from_str(&json).unwrap();
+    assert_eq!(*restored.read_right().await, vec![2, 3]);
+}
+
+// A struct with a non-serializable field still expands; the impls just don't apply.
+#[smart_lock]
+struct NotSerializable {
+    started: std::time::Instant,
+}
+
+#[test]
+fn non_serializable_fields_still_compile() {
+    let _ = NotSerializableLock::new(std::time::Instant::now());
+}
+
+#[tokio::test]
+async fn snapshot_serializes_like_the_lock() {
+    let state = ConfigLock::new(3, "primary".into(), AtomicU32::new(7));
+    let snapshot = state.serialize_snapshot().await;
+    assert_eq!(snapshot.retries, 3);
+    assert_eq!(snapshot.name, "primary");
+    assert_eq!(
+        serde_json::to_string(&snapshot).unwrap(),
+        serde_json::to_string(&state).unwrap()
+    );
+}
+
+#[tokio::test]
+async fn snapshot_releases_locks() {
+    let state = ConfigLock::new(0, "a".into(), AtomicU32::new(0));
+    let snapshot = state.serialize_snapshot().await;
+    // Locks are already released, so a writer doesn't block while the snapshot lives.
+    *state.write_retries().await = 1;
+    assert_eq!(snapshot.retries, 0);
+}
+
+// Writers always keep `left + right == 0`. A snapshot must never observe a torn state.
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn snapshot_is_never_torn() {
+    let state = std::sync::Arc::new(PairLock::new(0i64, 0i64));
+
+    let writers: Vec<_> = (0..4)
+        .map(|_| {
+            let state = state.clone();
+            tokio::spawn(async move {
+                for _ in 0..500 {
+                    let mut guard = state.builder().write_left().write_right().lock().await;
+                    *guard.left += 1;
+                    tokio::task::yield_now().await;
+                    *guard.right -= 1;
+                }
+            })
+        })
+        .collect();
+
+    for _ in 0..500 {
+        let snapshot = state.serialize_snapshot().await;
+        assert_eq!(snapshot.left + snapshot.right, 0);
+        let json: serde_json::Value = serde_json::to_value(&snapshot).unwrap();
+        assert_eq!(
+            json["left"].as_i64().unwrap() + json["right"].as_i64().unwrap(),
+            0
+        );
+        tokio::task::yield_now().await;
+    }
+
+    for w in writers {
+        w.await.unwrap();
+    }
+}
+
+#[tokio::test]
+async fn all_read_guard_serializes() {
+    let state = ConfigLock::new(3, "primary".into(), AtomicU32::new(7));
+    let json = serde_json::to_string(&state.lock_all().await).unwrap();
+    assert_eq!(json, r#"{"retries":3,"name":"primary","hits":7}"#);
+}
+
+#[tokio::test]
+async fn write_guard_serializes_pending_changes() {
+    let state = ConfigLock::new(0, "a".into(), AtomicU32::new(0));
+    let mut guard = state.lock_all_mut().await;
+    *guard.retries = 4;
+    let json = serde_json::to_string(&guard).unwrap();
+    assert_eq!(json, r#"{"retries":4,"name":"a","hits":0}"#);
+}
+
+#[tokio::test]
+async fn generic_guard_serializes() {
+    let state = PairLock::new(vec![1u8], vec![2u8, 3]);
+    let guard = state.builder().read_left().upgrade_right().lock().await;
+    assert_eq!(
+        serde_json::to_string(&guard).unwrap(),
+        r#"{"left":[1],"right":[2,3]}"#
+    );
+}
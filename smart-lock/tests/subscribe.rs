@@ -0,0 +1,267 @@
+#![cfg(feature = "subscribe")]
+
+use smart_lock::{smart_lock, FieldGuard};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::Arc;
+
+#[smart_lock(subscribe)]
+struct Stats {
+    counter: u64,
+    name: String,
+    #[no_lock]
+    id: u32,
+}
+
+fn stats() -> StatsLock {
+    StatsLock::new(0, String::new(), 1)
+}
+
+#[tokio::test]
+async fn writes_yield_field_ids_in_order() {
+    let state = stats();
+    let mut changes = state.subscribe();
+
+    *state.write_counter().await += 1;
+    state.write_name().await.push_str("hello");
+
+    assert_eq!(changes.recv().await, Some(StatsFieldId::counter));
+    assert_eq!(changes.recv().await, Some(StatsFieldId::name));
+    assert_eq!(changes.try_recv(), None);
+    assert_eq!(state.id, 1);
+}
+
+#[tokio::test]
+async fn reads_do_not_notify() {
+    let state = stats();
+    let mut changes = state.subscribe();
+
+    let _ = *state.read_counter().await;
+    let _ = state.lock_all().await;
+    drop(state.builder().read_name().upgrade_counter().lock().await);
+    assert_eq!(changes.try_recv(), None);
+}
+
+#[tokio::test]
+#[cfg_attr(
+    feature = "debug-deadlock",
+    ignore = "upgrades a field while holding one acquired after it"
+)]
+async fn guard_transitions_notify_when_write_ends() {
+    let state = stats();
+    let mut changes = state.subscribe();
+
+    let guard = state.builder().upgrade_counter().read_name().lock().await;
+    let mut guard = guard.upgrade_counter().await;
+    *guard.counter = 5;
+    assert_eq!(changes.try_recv(), None);
+    let guard = guard.downgrade_counter();
+    assert_eq!(changes.try_recv(), Some(StatsFieldId::counter));
+    drop(guard);
+    assert_eq!(changes.try_recv(), None);
+
+    let upgradable = state.upgrade_name().await;
+    let mut name = upgradable.upgrade().await;
+    name.push('!');
+    drop(name);
+    assert_eq!(changes.try_recv(), Some(StatsFieldId::name));
+}
+
+#[tokio::test]
+async fn failed_try_upgrade_all_does_not_notify() {
+    let state = stats();
+    let mut changes = state.subscribe();
+
+    // `counter` upgrades, `name` can't: the rollback must not count as a write.
+    let reader = state.read_name().await;
+    let guard = state.lock_all_upgradable().await;
+    let Err(guard) = guard.try_upgrade_all() else {
+        panic!("upgrade should fail while `name` is read-locked");
+    };
+    drop(guard);
+    drop(reader);
+    assert_eq!(changes.try_recv(), None);
+
+    let mut guard = state
+        .lock_all_upgradable()
+        .await
+        .try_upgrade_all()
+        .ok()
+        .unwrap();
+    *guard.counter = 1;
+    drop(guard);
+    assert_eq!(changes.try_recv(), Some(StatsFieldId::name));
+    assert_eq!(changes.try_recv(), Some(StatsFieldId::counter));
+}
+
+#[smart_lock(subscribe)]
+struct Ordered {
+    first: u8,
+    #[lock(order = 1)]
+    last: u8,
+    middle: u8,
+}
+
+/// The `Notify` hook fires as each field's lock is released, so the ids arrive in
+/// release order: the reverse of acquisition order (`first`, `middle`, `last`).
+#[tokio::test]
+async fn guard_releases_fields_in_reverse_acquisition_order() {
+    let state = OrderedLock::new(0, 0, 0);
+    let mut changes = state.subscribe();
+
+    drop(state.lock_all_mut().await);
+    assert_eq!(changes.try_recv(), Some(OrderedFieldId::last));
+    assert_eq!(changes.try_recv(), Some(OrderedFieldId::middle));
+    assert_eq!(changes.try_recv(), Some(OrderedFieldId::first));
+
+    let guard = state.builder().write_first().write_last().lock().await;
+    drop(guard.relock().write_middle().write_first().lock().await);
+    assert_eq!(changes.try_recv(), Some(OrderedFieldId::last));
+    assert_eq!(changes.try_recv(), Some(OrderedFieldId::first));
+    assert_eq!(changes.try_recv(), Some(OrderedFieldId::middle));
+    assert_eq!(changes.try_recv(), Some(OrderedFieldId::first));
+    assert_eq!(changes.try_recv(), None);
+}
+
+#[tokio::test]
+async fn compare_and_set_notifies_only_when_it_sets() {
+    let state = stats();
+    let mut changes = state.subscribe();
+
+    assert!(!state.compare_and_set_counter(1, 2).await);
+    assert_eq!(changes.try_recv(), None);
+    assert!(state.compare_and_set_counter(0, 2).await);
+    assert_eq!(changes.try_recv(), Some(StatsFieldId::counter));
+}
+
+#[tokio::test]
+async fn modify_notifies() {
+    let state = stats();
+    let mut changes = state.subscribe();
+
+    state.modify_counter(|c| *c += 1).await;
+    assert_eq!(changes.try_recv(), Some(StatsFieldId::counter));
+    assert_eq!(state.fetch_modify_counter(|c| *c += 1).await, 1);
+    assert_eq!(changes.try_recv(), Some(StatsFieldId::counter));
+    assert_eq!(changes.try_recv(), None);
+}
+
+#[tokio::test]
+async fn blocking_writes_notify() {
+    let state = stats();
+    let mut changes = state.subscribe();
+
+    state.write_name_blocking().push('!');
+    drop(state.read_counter_blocking());
+    assert_eq!(changes.try_recv(), Some(StatsFieldId::name));
+    assert_eq!(changes.try_recv(), None);
+}
+
+#[tokio::test]
+async fn every_subscriber_sees_every_write() {
+    let state = stats();
+    let mut early = state.subscribe();
+    drop(state.lock_all_mut().await);
+    let mut late = state.subscribe();
+    drop(state.builder().write_name().lock().await);
+
+    assert_eq!(early.try_recv(), Some(StatsFieldId::name));
+    assert_eq!(early.try_recv(), Some(StatsFieldId::counter));
+    assert_eq!(early.try_recv(), Some(StatsFieldId::name));
+    assert_eq!(late.try_recv(), Some(StatsFieldId::name));
+    assert_eq!(late.try_recv(), None);
+}
+
+#[tokio::test]
+async fn recv_waits_for_a_write_from_another_task() {
+    let state = Arc::new(stats());
+    let mut changes = state.subscribe();
+    let writer = {
+        let state = Arc::clone(&state);
+        tokio::spawn(async move {
+            tokio::task::yield_now().await;
+            *state.write_counter().await = 7;
+        })
+    };
+    assert_eq!(changes.recv().await, Some(StatsFieldId::counter));
+    assert_eq!(*state.read_counter().await, 7);
+    writer.await.unwrap();
+}
+
+#[tokio::test]
+async fn recv_ends_when_lock_is_dropped() {
+    let state = stats();
+    let mut changes = state.subscribe();
+    drop(state.write_name().await);
+    drop(state);
+    assert_eq!(changes.recv().await, Some(StatsFieldId::name));
+    assert_eq!(changes.recv().await, None);
+}
+
+#[smart_lock(subscribe, poison)]
+#[swap(low, high)]
+struct Range {
+    low: i64,
+    high: i64,
+}
+
+#[tokio::test]
+async fn bulk_writes_and_poison_combine() {
+    let state = RangeLock::new(5, 1);
+    let mut changes = state.subscribe();
+
+    state.swap_low_high().await;
+    state
+        .apply_updates([(RangeFieldId::high, 10), (RangeFieldId::low, 0)])
+        .await;
+    assert_eq!(changes.try_recv(), Some(RangeFieldId::low));
+    assert_eq!(changes.try_recv(), Some(RangeFieldId::high));
+    assert_eq!(changes.try_recv(), Some(RangeFieldId::low));
+    assert_eq!(changes.try_recv(), Some(RangeFieldId::high));
+
+    let panicked = catch_unwind(AssertUnwindSafe(|| {
+        pollster::block_on(async {
+            let _g = state.write_high().await.unwrap();
+            panic!("mid-update");
+        })
+    }));
+    assert!(panicked.is_err());
+    assert!(state.is_poisoned_high());
+    assert_eq!(changes.try_recv(), Some(RangeFieldId::high));
+    let g = state.lock_all().await;
+    assert!(FieldGuard::is_poisoned(&g.high));
+    assert!(!FieldGuard::is_poisoned(&g.low));
+}
+
+#[tokio::test]
+async fn changed_waits_for_a_write_to_that_field() {
+    let state = Arc::new(stats());
+    let counter_changed = state.changed_counter();
+
+    // A write made before the future is polled still counts.
+    state.write_name().await.push('x');
+    *state.write_counter().await += 1;
+    counter_changed.await;
+
+    let waiter = tokio::spawn({
+        let state = state.clone();
+        async move {
+            state.changed_name().await;
+            state.read_name().await.clone()
+        }
+    });
+    tokio::task::yield_now().await;
+    let _ = *state.read_name().await;
+    *state.write_counter().await += 1;
+    assert!(!waiter.is_finished());
+    state.write_name().await.push('y');
+    assert_eq!(waiter.await.unwrap(), "xy");
+}
+
+#[tokio::test]
+async fn changed_ignores_earlier_writes() {
+    let state = stats();
+    *state.write_counter().await += 1;
+    let changed = state.changed_counter();
+    let timed_out = tokio::time::timeout(std::time::Duration::from_millis(20), changed).await;
+    assert!(timed_out.is_err());
+}
@@ -0,0 +1,110 @@
+#![cfg(feature = "tracing")]
+
+use smart_lock::smart_lock;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::fmt::format::FmtSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+
+#[smart_lock]
+struct Stats {
+    counter: u64,
+    name: String,
+}
+
+/// Collects formatted `tracing` output for assertions.
+#[derive(Clone, Default)]
+struct Capture(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Capture {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Capture {
+    fn install(&self) -> tracing::subscriber::DefaultGuard {
+        let writer = self.clone();
+        tracing_subscriber::fmt()
+            .with_max_level(LevelFilter::TRACE)
+            .with_span_events(FmtSpan::CLOSE)
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish()
+            .set_default()
+    }
+
+    fn output(&self) -> String {
+        String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+    }
+}
+
+#[tokio::test]
+async fn acquisitions_emit_spans_per_field() {
+    let capture = Capture::default();
+    let _subscriber = capture.install();
+
+    let state = StatsLock::new(0, "stats".into());
+    let guard = state.builder().write_counter().lock().await;
+    drop(guard);
+    let output = capture.output();
+
+    assert!(output.contains("smart_lock::acquire"), "{output}");
+    assert!(output.contains("lock=\"StatsLock\""), "{output}");
+    assert!(output.contains("field=\"counter\""), "{output}");
+    assert!(output.contains("mode=Write"), "{output}");
+    // Unlocked fields are not acquired, so they get no span.
+    assert!(!output.contains("field=\"name\""), "{output}");
+    assert!(!output.contains("wait_time"), "{output}");
+}
+
+#[tokio::test]
+async fn contended_acquisition_records_wait_time() {
+    let capture = Capture::default();
+    let _subscriber = capture.install();
+
+    let state = Arc::new(StatsLock::new(0, "stats".into()));
+    let held = state.lock_all_mut().await;
+    let waiter = {
+        let state = Arc::clone(&state);
+        tokio::spawn(async move {
+            let guard = state.builder().read_name().lock().await;
+            guard.name.len()
+        })
+    };
+    tokio::task::yield_now().await;
+    drop(held);
+    assert_eq!(waiter.await.unwrap(), 5);
+
+    let output = capture.output();
+    let wait_line = output
+        .lines()
+        .find(|line| line.contains("wait_time"))
+        .unwrap_or_else(|| panic!("no wait_time event in:\n{output}"));
+    assert!(wait_line.contains("field=\"name\""), "{wait_line}");
+    assert!(wait_line.contains("mode=Read"), "{wait_line}");
+    assert!(wait_line.contains("wait_us="), "{wait_line}");
+}
+
+#[tokio::test]
+async fn failed_try_lock_emits_contended_event() {
+    let capture = Capture::default();
+    let _subscriber = capture.install();
+
+    let state = StatsLock::new(0, "stats".into());
+    let _held = state.write_counter().await;
+    assert!(state.try_lock_all().is_none());
+
+    let output = capture.output();
+    let line = output
+        .lines()
+        .find(|line| line.contains("contended"))
+        .unwrap_or_else(|| panic!("no contended event in:\n{output}"));
+    assert!(line.contains("field=\"counter\""), "{line}");
+}
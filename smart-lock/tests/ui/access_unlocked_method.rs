@@ -0,0 +1,15 @@
+use smart_lock::smart_lock;
+
+#[smart_lock]
+struct Foo {
+    x: u32,
+    y: Vec<u32>,
+}
+
+#[tokio::main]
+async fn main() {
+    let state = FooLock::new(0, vec![]);
+    let mut guard = state.builder().read_x().lock().await;
+    let _ = guard.y.len(); // ERROR: Unlocked has no Deref for auto-deref
+    guard.y.push(1); // ERROR: nor DerefMut
+}
@@ -0,0 +1,9 @@
+use smart_lock::smart_lock;
+
+#[smart_lock]
+struct MyStruct {
+    #[boxed] // ERROR: the field must be a `Box<T>`
+    data: Vec<u8>,
+}
+
+fn main() {}
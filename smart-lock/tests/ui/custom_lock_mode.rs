@@ -0,0 +1,11 @@
+use smart_lock::{LockMode, LockModeKind, Readable};
+
+struct Sneaky;
+
+impl LockMode for Sneaky {
+    const MODE: LockModeKind = LockModeKind::None;
+}
+
+impl Readable for Sneaky {} // ERROR: mode traits are sealed
+
+fn main() {}
@@ -0,0 +1,16 @@
+use smart_lock::smart_lock;
+
+#[smart_lock]
+struct Foo {
+    x: u32,
+}
+
+#[tokio::main]
+async fn main() {
+    let state = FooLock::new(0);
+    // ERROR: every mode selector requires the field to still be Unlocked
+    let _ = state.builder().read_x().read_x();
+    let _ = state.builder().write_x().write_x();
+    let _ = state.builder().upgrade_x().write_x();
+    let _ = state.builder().write_x().upgrade_x();
+}
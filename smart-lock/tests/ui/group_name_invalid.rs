@@ -0,0 +1,16 @@
+use smart_lock::smart_lock;
+
+#[smart_lock]
+struct Foo {
+    #[lock(group = "hot path")] // ERROR: not an identifier
+    x: u32,
+}
+
+#[smart_lock]
+#[preset(hot = "read_x")]
+struct Bar {
+    #[lock(group = "hot")] // ERROR: same name as a preset
+    x: u32,
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+use smart_lock::smart_lock;
+
+#[smart_lock]
+struct Foo {
+    #[lock(group = "hot")]
+    x: u32,
+    y: u32,
+}
+
+#[tokio::main]
+async fn main() {
+    let state = FooLock::new(0, 0);
+    let _ = state.builder_hot().write_y(); // ERROR: `y` isn't in the group
+    let guard = state.builder_hot().read_x().lock().await;
+    let _ = *guard.y; // ERROR: Unlocked has no Deref
+}
@@ -0,0 +1,25 @@
+use smart_lock::{smart_lock, ReadLocked, Unlocked, WriteLocked};
+use std::cell::Cell;
+use std::rc::Rc;
+
+// `Cell` is `Send` but not `Sync`, so the lock isn't `Sync` and guards
+// borrowing it can't move to another thread.
+#[smart_lock(no_send_assert)]
+struct Local {
+    count: Cell<u32>,
+}
+
+smart_lock::assert_send!(LocalLockGuard<'static, ReadLocked>); // ERROR
+smart_lock::assert_send!(LocalLockGuard<'static, WriteLocked>); // ERROR
+
+// A `#[no_lock]` field counts too, even for a guard that locks nothing.
+#[smart_lock(no_send_assert)]
+struct Tagged {
+    count: u32,
+    #[no_lock]
+    tag: Rc<str>,
+}
+
+smart_lock::assert_send!(TaggedLockGuard<'static, Unlocked>); // ERROR
+
+fn main() {}
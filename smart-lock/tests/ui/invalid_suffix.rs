@@ -0,0 +1,13 @@
+use smart_lock::smart_lock;
+
+#[smart_lock(suffix = "")] // ERROR: the lock would be named like the struct
+struct Empty {
+    x: u32,
+}
+
+#[smart_lock(suffix = "-Sync")] // ERROR: `Dash-Sync` is not an identifier
+struct Dash {
+    x: u32,
+}
+
+fn main() {}
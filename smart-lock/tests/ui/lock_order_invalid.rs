@@ -0,0 +1,9 @@
+use smart_lock::smart_lock;
+
+#[smart_lock]
+struct Foo {
+    #[lock(priority = 1)] // ERROR: unknown #[lock] option
+    x: u32,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use smart_lock::smart_lock;
+
+#[smart_lock]
+struct Foo {
+    x: u32,
+    #[no_lock]
+    #[lock(order = 1)] // ERROR: #[no_lock] fields are never locked
+    y: u32,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use smart_lock::smart_lock;
+
+#[smart_lock]
+struct Foo {
+    x: u32,
+}
+
+#[tokio::main]
+async fn main() {
+    let state = FooLock::new(0);
+    let mut guard = state.builder().upgrade_x().lock().await;
+    *guard.x = 1; // ERROR: UpgradeLocked has no DerefMut until upgraded
+}
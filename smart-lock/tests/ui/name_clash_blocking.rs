@@ -0,0 +1,9 @@
+use smart_lock::smart_lock;
+
+#[smart_lock]
+struct Positions {
+    y: u32,
+    y_blocking: u32, // ERROR: `read_y_blocking()` is also the blocking read of `y`
+}
+
+fn main() {}
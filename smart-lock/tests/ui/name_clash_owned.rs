@@ -0,0 +1,9 @@
+use smart_lock::smart_lock;
+
+#[smart_lock(owned)]
+struct Shared {
+    z: u32,
+    z_owned: u32, // ERROR: `read_z_owned()` is also the owned read of `z`
+}
+
+fn main() {}
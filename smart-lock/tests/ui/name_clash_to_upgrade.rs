@@ -0,0 +1,9 @@
+use smart_lock::smart_lock;
+
+#[smart_lock]
+struct Weights {
+    w: u32,
+    w_to_upgrade: u32, // ERROR: `downgrade_w_to_upgrade()` is also a downgrade of `w`
+}
+
+fn main() {}
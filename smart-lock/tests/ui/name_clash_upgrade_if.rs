@@ -0,0 +1,9 @@
+use smart_lock::smart_lock;
+
+#[smart_lock]
+struct Limits {
+    x: u32,
+    x_if: u32, // ERROR: `upgrade_x_if()` is also the conditional upgrade of `x`
+}
+
+fn main() {}
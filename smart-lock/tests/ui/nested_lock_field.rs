@@ -0,0 +1,23 @@
+use smart_lock::smart_lock;
+use std::sync::{Arc, Mutex};
+
+#[smart_lock]
+struct Direct {
+    count: smart_lock::RwLock<u32>, // ERROR: already a lock
+}
+
+#[smart_lock]
+struct Shared {
+    #[lock(order = 1)]
+    queue: Arc<Mutex<Vec<u8>>>, // ERROR: a lock behind an `Arc`
+}
+
+#[smart_lock]
+struct Allowed {
+    #[no_lock]
+    queue: Arc<Mutex<Vec<u8>>>,
+    #[lock(nested)]
+    slot: Mutex<u32>,
+}
+
+fn main() {}
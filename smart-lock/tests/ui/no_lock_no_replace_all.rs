@@ -0,0 +1,16 @@
+use smart_lock::smart_lock;
+use std::sync::atomic::AtomicU32;
+
+#[smart_lock]
+struct Foo {
+    x: u32,
+    #[no_lock]
+    synced: AtomicU32,
+}
+
+#[tokio::main]
+async fn main() {
+    let state = FooLock::new(0, AtomicU32::new(0));
+    let _old = state.replace_all(Foo { x: 1, synced: AtomicU32::new(1) }).await;
+    // ERROR: replace_all not found — it can't replace the #[no_lock] field through `&self`
+}
@@ -0,0 +1,11 @@
+use smart_lock::smart_lock;
+use std::rc::Rc;
+
+#[smart_lock]
+struct Bad {
+    count: u32,
+    #[no_lock]
+    shared: Rc<String>, // ERROR: `#[no_lock]` fields are checked too
+}
+
+fn main() {}
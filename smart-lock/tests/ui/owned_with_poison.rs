@@ -0,0 +1,14 @@
+use smart_lock::smart_lock;
+
+#[smart_lock(poison, owned)] // ERROR: owned guards can't track poison flags
+struct Foo {
+    x: u32,
+}
+
+#[smart_lock(poison)]
+struct Bar {
+    #[lock(owned)] // ERROR: same for a single owned field
+    x: u32,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use smart_lock::smart_lock;
+
+#[smart_lock]
+#[preset(hot = "write_counter, read_nmae")] // ERROR: no field `nmae`
+struct Foo {
+    counter: u32,
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,32 @@
+mod api {
+    use smart_lock::smart_lock;
+
+    #[smart_lock(vis = "pub(self)")]
+    pub struct Counter {
+        pub hits: u64,
+    }
+
+    pub struct Service {
+        state: CounterLock,
+    }
+
+    impl Service {
+        pub fn new() -> Self {
+            Service {
+                state: CounterLock::new(0),
+            }
+        }
+
+        pub async fn hit(&self) -> u64 {
+            let mut guard = self.state.write_hits().await;
+            *guard += 1;
+            *guard
+        }
+    }
+}
+
+fn main() {
+    let _service = api::Service::new();
+    let _counter = api::Counter { hits: 0 }; // OK: the struct keeps its own `pub`
+    let _lock = api::CounterLock::new(0); // ERROR: the lock is private to `api`
+}
@@ -0,0 +1,31 @@
+use smart_lock::smart_lock;
+
+#[smart_lock]
+struct Foo {
+    x: u32,
+    y: u32,
+}
+
+#[tokio::main]
+async fn main() {
+    let state = FooLock::new(0, 0);
+
+    // A field guard moved out of the guard keeps its lock; `relock()` consumes the
+    // whole guard, so it can't run while part of it lives on elsewhere.
+    let guard = state.builder().write_x().read_y().lock().await;
+    let x = guard.x;
+    let _ = guard.relock(); // ERROR: use of partially moved value
+    drop(x);
+
+    // The old guard is gone once relocked.
+    let guard = state.builder().read_y().lock().await;
+    let builder = guard.relock();
+    let _ = *guard.y; // ERROR: use of moved value
+    drop(builder);
+
+    // The relocked builder still borrows the lock for the original `'a`.
+    let guard = state.builder().write_x().lock().await;
+    let builder = guard.relock();
+    let _ = state.into_inner(); // ERROR: cannot move out of `state` while borrowed
+    drop(builder);
+}
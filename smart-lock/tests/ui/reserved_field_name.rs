@@ -0,0 +1,15 @@
+use smart_lock::smart_lock;
+
+#[smart_lock]
+struct Collection {
+    all: u32, // ERROR: per-field methods clash with the whole-struct ones
+    other: u32,
+}
+
+#[smart_lock]
+struct Renamed {
+    all_items: u32,
+    other: u32,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use smart_lock::smart_lock;
+
+struct NoDefault;
+
+#[smart_lock]
+struct Foo {
+    x: u32,
+    #[lock(skip_new)] // ERROR: `NoDefault` has no `Default` to start from
+    y: NoDefault,
+}
+
+fn main() {}
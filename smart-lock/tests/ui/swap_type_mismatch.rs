@@ -0,0 +1,10 @@
+use smart_lock::smart_lock;
+
+#[smart_lock]
+#[swap(count, name)] // ERROR: `u32` and `String` differ
+struct Foo {
+    count: u32,
+    name: String,
+}
+
+fn main() {}
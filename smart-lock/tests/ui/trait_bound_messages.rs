@@ -0,0 +1,17 @@
+use smart_lock::smart_lock;
+use std::ops::{Deref, DerefMut};
+
+#[smart_lock]
+struct Foo {
+    x: u32,
+    y: u32,
+}
+
+#[tokio::main]
+async fn main() {
+    let state = FooLock::new(0, 0);
+    let mut guard = state.builder().read_x().lock().await;
+    // ERROR: explicit trait calls report the `Readable` / `Writable` messages
+    let _ = Deref::deref(&guard.y);
+    let _ = DerefMut::deref_mut(&mut guard.x);
+}
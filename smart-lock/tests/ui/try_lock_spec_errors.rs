@@ -0,0 +1,14 @@
+use smart_lock::{smart_lock, try_lock_spec};
+
+#[smart_lock]
+struct Foo {
+    x: u32,
+    y: u32,
+}
+
+fn main() {
+    let state = FooLock::new(0, 0);
+    let _ = try_lock_spec!(state, mut: [x]); // ERROR: not a lock mode
+    let _ = try_lock_spec!(state, write: [z]); // ERROR: no field `z`
+    let _ = try_lock_spec!(state, write: [x], read: [x]); // ERROR: `x` is listed twice
+}
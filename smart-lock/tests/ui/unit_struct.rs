@@ -0,0 +1,6 @@
+use smart_lock::smart_lock;
+
+#[smart_lock] // ERROR: needs braces, even without fields
+struct Marker;
+
+fn main() {}
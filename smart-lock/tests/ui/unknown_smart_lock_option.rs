@@ -0,0 +1,13 @@
+use smart_lock::smart_lock;
+
+#[smart_lock(posion)] // ERROR: unknown option
+struct Foo {
+    x: u32,
+}
+
+#[smart_lock(vis = "public")] // ERROR: not a visibility
+struct Bar {
+    x: u32,
+}
+
+fn main() {}
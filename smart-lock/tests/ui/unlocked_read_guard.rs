@@ -0,0 +1,7 @@
+use smart_lock::{FieldGuard, ReadLocked};
+
+fn main() {
+    // ERROR: `unlocked()` only constructs `Unlocked` guards
+    let guard = FieldGuard::<'static, u32, ReadLocked>::unlocked();
+    let _ = *guard;
+}
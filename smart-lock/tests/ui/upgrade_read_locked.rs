@@ -0,0 +1,21 @@
+use smart_lock::smart_lock;
+
+#[smart_lock]
+struct Foo {
+    x: u32,
+}
+
+#[tokio::main]
+async fn main() {
+    let state = FooLock::new(0);
+    let guard = state.builder().read_x().lock().await;
+    // ERROR: only UpgradeLocked fields can be upgraded
+    let _ = guard.upgrade_x().await;
+
+    let guard = state.builder().read_x().lock().await;
+    // ERROR: same for the non-blocking variant
+    let _ = guard.try_upgrade_x();
+
+    // ERROR: and for a read-locked `FieldGuard` on its own
+    let _ = state.lock_all().await.x.upgrade();
+}
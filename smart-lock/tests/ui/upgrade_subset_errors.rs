@@ -0,0 +1,26 @@
+use smart_lock::{smart_lock, upgrade_subset};
+
+#[smart_lock]
+struct Foo {
+    x: u32,
+    y: u32,
+}
+
+async fn read_locked(state: &FooLock) {
+    let guard = state.builder().upgrade_x().read_y().lock().await;
+    let _ = upgrade_subset!(guard, y).await; // ERROR: `y` is read-locked
+}
+
+async fn unknown_field(state: &FooLock) {
+    let guard = state.lock_all_upgradable().await;
+    let _ = upgrade_subset!(guard, z).await; // ERROR: no field `z`
+}
+
+async fn listed_twice(state: &FooLock) {
+    let guard = state.lock_all_upgradable().await;
+    let _ = upgrade_subset!(guard, x, x).await; // ERROR: `x` is listed twice
+}
+
+fn main() {
+    let _ = (read_locked, unknown_field, listed_twice);
+}
@@ -0,0 +1,14 @@
+use smart_lock::smart_lock;
+
+#[smart_lock]
+struct Foo {
+    x: u32,
+}
+
+#[tokio::main]
+async fn main() {
+    let state = FooLock::new(0);
+    let guard = state.builder().write_x().lock().await;
+    // ERROR: `x` is already WriteLocked
+    let _ = guard.upgrade_x().await;
+}
@@ -0,0 +1,18 @@
+use smart_lock::{smart_lock, ReadLocked, WriteLocked};
+
+#[smart_lock]
+struct Foo {
+    x: u32,
+}
+
+#[tokio::main]
+async fn main() {
+    let state = FooLock::new(0);
+    // ERROR: `x` is already selected
+    let _ = state
+        .builder()
+        .read_x()
+        .with_mode::<foo_field::x, WriteLocked>();
+    // ERROR: not a field marker of this builder
+    let _ = state.builder().with_mode::<u32, ReadLocked>();
+}